@@ -0,0 +1,45 @@
+//! Frame time of the headless editor with many open machines
+//!
+//! Every frame runs the edge visual sync, which buckets edges by machine root once per frame;
+//! the time per frame should grow with the total number of edges, not edges × open machines.
+//!
+//! Run with `cargo bench --features test_support`.
+
+use bevy::prelude::*;
+use bevy_gearbox_editor::test_support::{headless_app, open_machine, spawn_machine};
+use bevy_gearbox_editor::CreateTransition;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const STATES_PER_MACHINE: usize = 20;
+
+/// A headless app with `machines` open machines, each a ring of Always transitions between its states
+fn app_with_open_machines(machines: usize) -> App {
+    let mut app = headless_app();
+    let names: Vec<String> = (0..STATES_PER_MACHINE).map(|index| format!("State {index}")).collect();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    for machine_index in 0..machines {
+        let (machine, states) = spawn_machine(app.world_mut(), &format!("Machine {machine_index}"), &names);
+        for (index, &source) in states.iter().enumerate() {
+            app.world_mut().trigger(CreateTransition {
+                source_entity: source,
+                target_entity: states[(index + 1) % states.len()],
+                event_type: "Always".to_string(),
+                internal: false,
+            });
+        }
+        open_machine(&mut app, machine);
+    }
+    app
+}
+
+fn edge_visual_sync(c: &mut Criterion) {
+    let mut group = c.benchmark_group("editor_frame");
+    for machines in [1, 8, 32] {
+        let mut app = app_with_open_machines(machines);
+        group.bench_with_input(BenchmarkId::new("open_machines", machines), &machines, |b, _| b.iter(|| app.update()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, edge_visual_sync);
+criterion_main!(benches);
@@ -54,7 +54,7 @@ fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
         .add_plugins(GearboxPlugin)
-        .add_plugins((EguiPlugin::default(), DefaultInspectorConfigPlugin, bevy_gearbox_editor::GearboxEditorPlugin))
+        .add_plugins((EguiPlugin::default(), DefaultInspectorConfigPlugin, bevy_gearbox_editor::GearboxEditorPlugin::default()))
         .init_state::<ExampleState>()
         .add_state_bridge::<ExampleState>()
         .add_systems(Startup, setup_machine)
@@ -126,7 +126,7 @@ fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
         .add_plugins(GearboxPlugin)
-        .add_plugins((EguiPlugin::default(), DefaultInspectorConfigPlugin, GearboxEditorPlugin))
+        .add_plugins((EguiPlugin::default(), DefaultInspectorConfigPlugin, GearboxEditorPlugin::default()))
         .init_resource::<RespawnQueue>()
         .add_observer(print_enter_state)
         .add_observer(apply_damage_system)
@@ -12,7 +12,7 @@ fn main() {
         .add_plugins(DefaultPlugins)
         .add_plugins(GearboxPlugin)
         .add_plugins(EguiPlugin::default())
-        .add_plugins(bevy_gearbox_editor::GearboxEditorPlugin)
+        .add_plugins(bevy_gearbox_editor::GearboxEditorPlugin::default())
         .add_plugins(WorldInspectorPlugin::new())
         .add_systems(Startup, setup)
         .add_systems(Update, input_system)
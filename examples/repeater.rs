@@ -11,7 +11,7 @@ fn main() {
         .add_plugins(DefaultPlugins)
         .add_plugins(EguiPlugin::default())
         .add_plugins(GearboxPlugin)
-        .add_plugins(bevy_gearbox_editor::GearboxEditorPlugin)
+        .add_plugins(bevy_gearbox_editor::GearboxEditorPlugin::default())
         .add_systems(Startup, setup)
         .add_systems(Update, input_system)
         .add_observer(on_enter_repeating_emit_events)
@@ -0,0 +1,240 @@
+//! Alignment tools for multi-selected nodes
+//!
+//! This module handles:
+//! - Aligning selected nodes to a shared left/right/top/bottom edge or center line
+//! - Evenly distributing selected node centers between the extreme members
+//! - Applying the resulting moves so children follow their parents
+
+use bevy::prelude::*;
+use bevy::platform::collections::HashMap;
+use bevy_egui::egui;
+use bevy_gearbox::StateMachine;
+
+use crate::components::NodeType;
+use crate::editor_state::{AlignSelection, EditorState, NodeDragged, StateMachinePersistentData};
+
+/// How to rearrange the selected nodes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlignMode {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// Same center x, on the selection's vertical center line
+    CenterHorizontally,
+    /// Same center y, on the selection's horizontal center line
+    CenterVertically,
+    DistributeHorizontally,
+    DistributeVertically,
+}
+
+impl AlignMode {
+    /// Every mode, in menu order
+    pub const ALL: [AlignMode; 8] = [
+        AlignMode::Left,
+        AlignMode::Right,
+        AlignMode::Top,
+        AlignMode::Bottom,
+        AlignMode::CenterHorizontally,
+        AlignMode::CenterVertically,
+        AlignMode::DistributeHorizontally,
+        AlignMode::DistributeVertically,
+    ];
+
+    /// Menu label
+    pub fn label(&self) -> &'static str {
+        match self {
+            AlignMode::Left => "Align left",
+            AlignMode::Right => "Align right",
+            AlignMode::Top => "Align top",
+            AlignMode::Bottom => "Align bottom",
+            AlignMode::CenterHorizontally => "Align centers horizontally",
+            AlignMode::CenterVertically => "Align centers vertically",
+            AlignMode::DistributeHorizontally => "Distribute horizontally",
+            AlignMode::DistributeVertically => "Distribute vertically",
+        }
+    }
+}
+
+/// Compute how far each of `selection` must move to satisfy `mode`
+///
+/// Entities missing from `nodes` are ignored; unmoved entities are omitted from the result.
+pub fn alignment_deltas(
+    nodes: &HashMap<Entity, NodeType>,
+    selection: &[Entity],
+    mode: AlignMode,
+) -> Vec<(Entity, egui::Vec2)> {
+    let rects: Vec<(Entity, egui::Rect)> = selection
+        .iter()
+        .filter_map(|entity| nodes.get(entity).map(|node| (*entity, node.current_rect())))
+        .collect();
+    if rects.len() < 2 {
+        return Vec::new();
+    }
+
+    let deltas: Vec<(Entity, egui::Vec2)> = match mode {
+        AlignMode::Left => {
+            let left = rects.iter().map(|(_, rect)| rect.min.x).fold(f32::INFINITY, f32::min);
+            rects.iter().map(|(entity, rect)| (*entity, egui::vec2(left - rect.min.x, 0.0))).collect()
+        }
+        AlignMode::Right => {
+            let right = rects.iter().map(|(_, rect)| rect.max.x).fold(f32::NEG_INFINITY, f32::max);
+            rects.iter().map(|(entity, rect)| (*entity, egui::vec2(right - rect.max.x, 0.0))).collect()
+        }
+        AlignMode::Top => {
+            let top = rects.iter().map(|(_, rect)| rect.min.y).fold(f32::INFINITY, f32::min);
+            rects.iter().map(|(entity, rect)| (*entity, egui::vec2(0.0, top - rect.min.y))).collect()
+        }
+        AlignMode::Bottom => {
+            let bottom = rects.iter().map(|(_, rect)| rect.max.y).fold(f32::NEG_INFINITY, f32::max);
+            rects.iter().map(|(entity, rect)| (*entity, egui::vec2(0.0, bottom - rect.max.y))).collect()
+        }
+        AlignMode::CenterHorizontally => {
+            let center = bounds(&rects).center().x;
+            rects.iter().map(|(entity, rect)| (*entity, egui::vec2(center - rect.center().x, 0.0))).collect()
+        }
+        AlignMode::CenterVertically => {
+            let center = bounds(&rects).center().y;
+            rects.iter().map(|(entity, rect)| (*entity, egui::vec2(0.0, center - rect.center().y))).collect()
+        }
+        AlignMode::DistributeHorizontally => distribute(rects, |pos| pos.x, |delta| egui::vec2(delta, 0.0)),
+        AlignMode::DistributeVertically => distribute(rects, |pos| pos.y, |delta| egui::vec2(0.0, delta)),
+    };
+
+    deltas.into_iter().filter(|(_, delta)| *delta != egui::Vec2::ZERO).collect()
+}
+
+/// Smallest rect containing every member
+fn bounds(rects: &[(Entity, egui::Rect)]) -> egui::Rect {
+    rects.iter().fold(egui::Rect::NOTHING, |bounds, (_, rect)| bounds.union(*rect))
+}
+
+/// Space centers evenly along one axis, keeping the two extreme members in place
+fn distribute(
+    mut rects: Vec<(Entity, egui::Rect)>,
+    axis: impl Fn(egui::Pos2) -> f32,
+    along: impl Fn(f32) -> egui::Vec2,
+) -> Vec<(Entity, egui::Vec2)> {
+    rects.sort_by(|(_, a), (_, b)| axis(a.center()).total_cmp(&axis(b.center())));
+    let first = axis(rects[0].1.center());
+    let last = axis(rects[rects.len() - 1].1.center());
+    let step = (last - first) / (rects.len() - 1) as f32;
+    rects
+        .iter()
+        .enumerate()
+        .map(|(index, (entity, rect))| (*entity, along(first + step * index as f32 - axis(rect.center()))))
+        .collect()
+}
+
+/// Observer to align or distribute a machine's multi-selection
+pub fn handle_align_selection(
+    align: On<AlignSelection>,
+    editor_state: Res<EditorState>,
+    mut q_sm: Query<&mut StateMachinePersistentData, With<StateMachine>>,
+    q_child_of: Query<&bevy_gearbox::StateChildOf>,
+    mut commands: Commands,
+) {
+    let Ok(mut persistent_data) = q_sm.get_mut(align.machine) else {
+        return;
+    };
+
+    // Only move outermost members; their children follow through NodeDragged
+    let selection: Vec<Entity> = crate::node_editor::outermost_selected_nodes(&editor_state.view.selected_nodes, &q_child_of)
+        .into_iter()
+        .filter(|entity| persistent_data.nodes.contains_key(entity))
+        .collect();
+    for (entity, delta) in alignment_deltas(&persistent_data.nodes, &selection, align.mode) {
+        if let Some(node) = persistent_data.nodes.get_mut(&entity) {
+            let position = node.position();
+            node.set_position(position + delta);
+        }
+        commands.trigger(NodeDragged { entity, drag_delta: delta });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::LeafNode;
+
+    /// Same-sized leaves with their top-left corners at `positions`
+    fn leaves(positions: &[(f32, f32)]) -> (HashMap<Entity, NodeType>, Vec<Entity>) {
+        let mut world = World::new();
+        let mut nodes = HashMap::new();
+        let mut selection = Vec::new();
+        for &(x, y) in positions {
+            let entity = world.spawn_empty().id();
+            nodes.insert(entity, NodeType::Leaf(LeafNode::new(egui::pos2(x, y))));
+            selection.push(entity);
+        }
+        (nodes, selection)
+    }
+
+    fn delta_of(deltas: &[(Entity, egui::Vec2)], entity: Entity) -> Option<egui::Vec2> {
+        deltas.iter().find(|(moved, _)| *moved == entity).map(|(_, delta)| *delta)
+    }
+
+    #[test]
+    fn fewer_than_two_nodes_never_move() {
+        for mode in AlignMode::ALL {
+            let (nodes, selection) = leaves(&[]);
+            assert!(alignment_deltas(&nodes, &selection, mode).is_empty());
+            let (nodes, selection) = leaves(&[(30.0, 40.0)]);
+            assert!(alignment_deltas(&nodes, &selection, mode).is_empty());
+        }
+    }
+
+    #[test]
+    fn unknown_entities_are_ignored() {
+        let (nodes, mut selection) = leaves(&[(0.0, 0.0)]);
+        let (_, strangers) = leaves(&[(100.0, 0.0)]);
+        selection.extend(strangers);
+        assert!(alignment_deltas(&nodes, &selection, AlignMode::Left).is_empty());
+    }
+
+    #[test]
+    fn align_left_moves_to_leftmost_edge() {
+        let (nodes, selection) = leaves(&[(0.0, 0.0), (100.0, 50.0), (40.0, 80.0)]);
+        let deltas = alignment_deltas(&nodes, &selection, AlignMode::Left);
+
+        assert_eq!(delta_of(&deltas, selection[0]), None);
+        assert_eq!(delta_of(&deltas, selection[1]), Some(egui::vec2(-100.0, 0.0)));
+        assert_eq!(delta_of(&deltas, selection[2]), Some(egui::vec2(-40.0, 0.0)));
+    }
+
+    #[test]
+    fn align_bottom_moves_to_lowest_edge() {
+        let (nodes, selection) = leaves(&[(0.0, 0.0), (100.0, 60.0)]);
+        let deltas = alignment_deltas(&nodes, &selection, AlignMode::Bottom);
+
+        assert_eq!(delta_of(&deltas, selection[0]), Some(egui::vec2(0.0, 60.0)));
+        assert_eq!(delta_of(&deltas, selection[1]), None);
+    }
+
+    #[test]
+    fn align_centers_meets_on_selection_center_line() {
+        let (nodes, selection) = leaves(&[(0.0, 0.0), (100.0, 30.0)]);
+        let deltas = alignment_deltas(&nodes, &selection, AlignMode::CenterHorizontally);
+
+        assert_eq!(delta_of(&deltas, selection[0]), Some(egui::vec2(50.0, 0.0)));
+        assert_eq!(delta_of(&deltas, selection[1]), Some(egui::vec2(-50.0, 0.0)));
+    }
+
+    #[test]
+    fn distribute_spaces_centers_evenly_between_extremes() {
+        // Unsorted on purpose; distribution orders by center
+        let (nodes, selection) = leaves(&[(300.0, 0.0), (0.0, 0.0), (20.0, 0.0), (100.0, 0.0)]);
+        let deltas = alignment_deltas(&nodes, &selection, AlignMode::DistributeHorizontally);
+
+        assert_eq!(delta_of(&deltas, selection[0]), None);
+        assert_eq!(delta_of(&deltas, selection[1]), None);
+        assert_eq!(delta_of(&deltas, selection[2]), Some(egui::vec2(80.0, 0.0)));
+        assert_eq!(delta_of(&deltas, selection[3]), Some(egui::vec2(100.0, 0.0)));
+    }
+
+    #[test]
+    fn distribute_two_nodes_is_a_no_op() {
+        let (nodes, selection) = leaves(&[(0.0, 0.0), (0.0, 250.0)]);
+        assert!(alignment_deltas(&nodes, &selection, AlignMode::DistributeVertically).is_empty());
+    }
+}
@@ -0,0 +1,180 @@
+//! Copy/paste of state subtrees
+//!
+//! This module handles:
+//! - Ctrl+C / Ctrl+V hotkeys on the editor window
+//! - Snapshotting the selected state, its descendants and the transitions between them
+//! - Pasting the snapshot as fresh entities under the original parent
+
+use bevy::prelude::*;
+use bevy::ecs::entity::EntityHashMap;
+use bevy::platform::collections::HashMap;
+use bevy::scene::{DynamicScene, DynamicSceneBuilder};
+use bevy_egui::{egui, EguiContext, PrimaryEguiContext};
+use bevy_gearbox::{StateChildOf, StateChildren};
+use crate::reflectable::deny_runtime_components;
+use bevy_gearbox::transitions::{Target, Transitions as EdgeTransitions};
+
+use crate::components::NodeType;
+use crate::editor_state::{EditorState, EditorWindow, MachineNodesPopulated, Select, StateMachinePersistentData};
+use crate::window_management::{CurrentEditorWindow, EditorHotkeys};
+use crate::history::{collect_subtree, disabled_edges_from, root_ancestor};
+
+/// Offset applied to each successive paste so copies don't stack on the original
+pub const PASTE_OFFSET: egui::Vec2 = egui::vec2(40.0, 40.0);
+
+/// A copied subtree, ready to be pasted any number of times
+pub struct SubtreeClipboard {
+    /// The copied state
+    pub root: Entity,
+    /// Parent the copy is pasted under
+    pub parent: Entity,
+    /// State machine root the subtree was copied from
+    pub machine: Entity,
+    /// Reflected components of the subtree and its internal edges
+    pub scene: DynamicScene,
+    /// Editor node layouts of the subtree
+    pub nodes: HashMap<Entity, NodeType>,
+    /// How many times this clipboard has been pasted
+    pub paste_count: u32,
+}
+
+/// System to copy (`EditorHotkeys::copy`, Ctrl+C) and paste (`EditorHotkeys::paste`, Ctrl+V) the selected subtree
+pub fn handle_clipboard_hotkeys(
+    mut q_editor_context: Query<&mut EguiContext, (With<EditorWindow>, Without<PrimaryEguiContext>)>,
+    current_window: Res<CurrentEditorWindow>,
+    hotkeys: Res<EditorHotkeys>,
+    mut commands: Commands,
+) {
+    let Ok(mut egui_context) = q_editor_context.get_mut(current_window.camera) else {
+        return;
+    };
+    let ctx = egui_context.get_mut();
+    if ctx.wants_keyboard_input() {
+        return;
+    }
+
+    // Depending on the platform, egui reports these as clipboard events rather than key presses
+    let (copy, paste) = ctx.input(|i| {
+        let copy = i.events.iter().any(|event| matches!(event, egui::Event::Copy))
+            || hotkeys.copy.pressed_in(i);
+        let paste = i.events.iter().any(|event| matches!(event, egui::Event::Paste(_)))
+            || hotkeys.paste.pressed_in(i);
+        (copy, paste)
+    });
+    if copy {
+        commands.queue(copy_selected_subtree);
+    } else if paste {
+        commands.queue(paste_subtree);
+    }
+}
+
+/// Snapshot the selected state and its subtree into the clipboard
+pub fn copy_selected_subtree(world: &mut World) {
+    let Some(selected) = world.resource::<EditorState>().view.selected_entity else {
+        return;
+    };
+    if !world.entities().contains(selected) {
+        return;
+    }
+    let Some(parent) = world.get::<StateChildOf>(selected).map(|child_of| child_of.0) else {
+        warn!("⚠️ Cannot copy a state machine root");
+        return;
+    };
+    let machine = root_ancestor(world, selected);
+    let Some(persistent_data) = world.get::<StateMachinePersistentData>(machine) else {
+        return;
+    };
+    if !persistent_data.nodes.contains_key(&selected) {
+        return;
+    }
+
+    let states = collect_subtree(world, selected);
+
+    // Only edges that both start and end inside the subtree
+    let mut edges = Vec::new();
+    for state in &states {
+        if let Some(transitions) = world.get::<EdgeTransitions>(*state) {
+            edges.extend(
+                transitions
+                    .into_iter()
+                    .copied()
+                    .filter(|edge| world.get::<Target>(*edge).is_some_and(|target| states.contains(&target.0))),
+            );
+        }
+    }
+    let disabled = disabled_edges_from(world, &states);
+    edges.extend(disabled.into_iter().filter(|edge| world.get::<Target>(*edge).is_some_and(|target| states.contains(&target.0))));
+
+    // Relationship targets are rebuilt by hooks when the copies are spawned
+    let scene = deny_runtime_components(DynamicSceneBuilder::from_world(world))
+        .deny_component::<StateChildren>()
+        .deny_component::<EdgeTransitions>()
+        .extract_entities(states.iter().chain(edges.iter()).copied())
+        .build();
+
+    let persistent_data = world.get::<StateMachinePersistentData>(machine).unwrap();
+    let nodes = states
+        .iter()
+        .filter_map(|state| persistent_data.nodes.get(state).map(|node| (*state, node.clone())))
+        .collect();
+
+    info!("✅ Copied {} states and {} transitions", states.len(), edges.len());
+    world.resource_mut::<EditorState>().clipboard = Some(SubtreeClipboard {
+        root: selected,
+        parent,
+        machine,
+        scene,
+        nodes,
+        paste_count: 0,
+    });
+}
+
+/// Spawn a fresh copy of the clipboard subtree under the original parent
+pub fn paste_subtree(world: &mut World) {
+    let result = world.resource_scope(|world, mut editor_state: Mut<EditorState>| {
+        let Some(clipboard) = editor_state.clipboard.as_mut() else {
+            return Ok(None);
+        };
+        if !world.entities().contains(clipboard.parent) || world.get::<StateMachinePersistentData>(clipboard.machine).is_none() {
+            return Err("the copied state's parent no longer exists".into());
+        }
+
+        // Only the parent lives outside the subtree; everything else gets a fresh entity
+        let mut entity_map: EntityHashMap<Entity> = EntityHashMap::default();
+        entity_map.insert(clipboard.parent, clipboard.parent);
+        clipboard.scene.write_to_world(world, &mut entity_map)?;
+        let new_root = *entity_map.get(&clipboard.root).ok_or("pasted scene is missing the copied state")?;
+
+        clipboard.paste_count += 1;
+        let offset = PASTE_OFFSET * clipboard.paste_count as f32;
+        let mut persistent_data = world.get_mut::<StateMachinePersistentData>(clipboard.machine).unwrap();
+        for (old_entity, node) in &clipboard.nodes {
+            let Some(new_entity) = entity_map.get(old_entity) else {
+                continue;
+            };
+            let mut node = node.clone();
+            let position = node.position();
+            node.set_position(position + offset);
+            persistent_data.nodes.insert(*new_entity, node);
+        }
+
+        if let Some(mut name) = world.get_mut::<Name>(new_root) {
+            let copied_name = format!("{} (copy)", name.as_str());
+            name.set(copied_name);
+        }
+
+        Ok::<_, Box<dyn std::error::Error>>(Some((clipboard.machine, new_root)))
+    });
+
+    match result {
+        Ok(Some((machine, new_root))) => {
+            // Build NodeKind machines for the pasted nodes and select the copy
+            world.trigger(MachineNodesPopulated { root: machine });
+            world.trigger(Select { selected: Some(new_root) });
+            world.flush();
+            info!("✅ Pasted subtree as {:?}", new_root);
+        }
+        Ok(None) => {}
+        Err(e) => error!("❌ Failed to paste subtree: {}", e),
+    }
+}
@@ -1,5 +1,7 @@
 use egui::{Color32, FontId, Pos2};
 
+use crate::editor_state::EditorTheme;
+
 /// Shared properties for all node types (leaf and parent nodes)
 #[derive(Debug, Clone)]
 pub struct EntityNode {
@@ -17,14 +19,8 @@ pub struct EntityNode {
     pub padding: egui::Vec2,
     /// Font size for text
     pub font_size: f32,
-    /// Background color when not selected
-    pub bg_color: Color32,
-    /// Background color when selected
-    pub selected_bg_color: Color32,
-    /// Text color
-    pub text_color: Color32,
-    /// Border color
-    pub border_color: Color32,
+    /// Palette for fills, borders and text (refreshed from the `EditorTheme` resource each frame)
+    pub theme: EditorTheme,
     /// Whether this node is currently being dragged by the primary mouse button
     pub is_being_dragged_by_primary: bool,
 }
@@ -40,14 +36,24 @@ impl EntityNode {
             max_size: egui::Vec2::new(300.0, 200.0),
             padding: egui::Vec2::new(12.0, 8.0),
             font_size: 14.0,
-            bg_color: Color32::from_rgb(45, 45, 55),
-            selected_bg_color: Color32::from_rgb(65, 65, 85),
-            text_color: Color32::WHITE,
-            border_color: Color32::from_rgb(80, 80, 90),
+            theme: EditorTheme::default(),
             is_being_dragged_by_primary: false,
         }
     }
     
+    /// Switch between the default and the compact (denser) padding, font and minimum size
+    pub fn set_compact(&mut self, compact: bool) {
+        if compact {
+            self.padding = egui::Vec2::new(6.0, 3.0);
+            self.font_size = 11.0;
+            self.min_size = egui::Vec2::new(50.0, 24.0);
+        } else {
+            self.padding = egui::Vec2::new(12.0, 8.0);
+            self.font_size = 14.0;
+            self.min_size = egui::Vec2::new(80.0, 40.0);
+        }
+    }
+    
     /// Get the font ID for the main text
     pub fn main_font_id(&self) -> FontId {
         FontId::new(self.font_size, egui::FontFamily::Proportional)
@@ -61,9 +67,9 @@ impl EntityNode {
     /// Get the background color based on selection state
     pub fn current_bg_color(&self) -> Color32 {
         if self.selected {
-            self.selected_bg_color
+            self.theme.zone_selected_fill
         } else {
-            self.bg_color
+            self.theme.zone_fill
         }
     }
     
@@ -81,6 +87,11 @@ pub struct NodeResponse {
     pub drag_delta: egui::Vec2,
     pub hovered: bool,
     pub right_clicked: bool,
+    /// Double-clicked on the node's label (the whole leaf, or a parent's title bar)
+    pub label_double_clicked: bool,
+    /// Double-clicked on a parent's body, below the title bar
+    pub body_double_clicked: bool,
     pub add_transition_clicked: bool,
+    pub collapse_toggled: bool,
 }
 
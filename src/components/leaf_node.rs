@@ -49,9 +49,9 @@ impl LeafNode {
     ) -> NodeResponse {
         // Determine text color based on background color using smooth interpolation
         let text_color = if let Some(bg_color) = custom_color {
-            crate::editor_state::compute_text_color_for_bg(bg_color)
+            self.entity_node.theme.text_color_for_bg(bg_color)
         } else {
-            self.entity_node.text_color
+            self.entity_node.theme.text_color_for_bg(self.entity_node.current_bg_color())
         };
         
         // Calculate text dimensions
@@ -60,11 +60,9 @@ impl LeafNode {
         
         let subscript_galley = entity_id.map(|id| {
             let subscript_font_id = self.entity_node.subscript_font_id();
-            let subscript_color = if crate::editor_state::prefers_dark_text(text_color) {
-                egui::Color32::from_rgba_unmultiplied(0, 0, 0, 180) // Semi-transparent black
-            } else {
-                egui::Color32::from_rgba_unmultiplied(255, 255, 255, 180) // Semi-transparent white
-            };
+            // Dimmed version of the contrasting text color for the node's current background
+            let bg_color = custom_color.unwrap_or_else(|| self.entity_node.current_bg_color());
+            let subscript_color = self.entity_node.theme.text_color_for_bg(bg_color).gamma_multiply(0.7);
             ui.fonts(|f| f.layout_no_wrap(id.to_string(), subscript_font_id, subscript_color))
         });
         
@@ -121,6 +119,13 @@ impl LeafNode {
             node_response.right_clicked = true;
         }
         
+        // Double-click renames; it never moves the node
+        if response.double_clicked_by(egui::PointerButton::Primary) {
+            node_response.label_double_clicked = true;
+            node_response.dragged = false;
+            node_response.drag_delta = Vec2::ZERO;
+        }
+        
         node_response.hovered = response.hovered();
         
         // Draw the leaf node (with editing support)
@@ -164,6 +169,7 @@ impl LeafNode {
             painter.circle_filled(button_rect.center(), button_size / 2.0, button_color);
             
             // Draw the + symbol
+            let cross_color = self.entity_node.theme.text_color_for_bg(button_color);
             let line_width = 1.5;
             let cross_size = 6.0;
             painter.line_segment(
@@ -171,14 +177,14 @@ impl LeafNode {
                     button_rect.center() - egui::Vec2::new(cross_size / 2.0, 0.0),
                     button_rect.center() + egui::Vec2::new(cross_size / 2.0, 0.0),
                 ],
-                egui::Stroke::new(line_width, egui::Color32::WHITE),
+                egui::Stroke::new(line_width, cross_color),
             );
             painter.line_segment(
                 [
                     button_rect.center() - egui::Vec2::new(0.0, cross_size / 2.0),
                     button_rect.center() + egui::Vec2::new(0.0, cross_size / 2.0),
                 ],
-                egui::Stroke::new(line_width, egui::Color32::WHITE),
+                egui::Stroke::new(line_width, cross_color),
             );
         }
         
@@ -232,7 +238,7 @@ impl LeafNode {
         
         // Draw border (dotted optional)
         let selected_border = egui::Color32::from_rgb(100, 150, 255);
-        let border_color = if is_selected { selected_border } else { self.entity_node.border_color };
+        let border_color = if is_selected { selected_border } else { self.entity_node.theme.node_border };
         if dotted_border {
             super::draw_dotted_rect(
                 painter,
@@ -264,7 +270,7 @@ impl LeafNode {
             rect.center().x - main_text_size.x * 0.5,
             text_block_start_y,
         );
-        painter.galley(main_text_pos, main_text_galley.clone().into(), self.entity_node.text_color);
+        painter.galley(main_text_pos, main_text_galley.clone().into(), self.entity_node.theme.text_light);
         
         // Draw subscript text if present (centered horizontally, below main text)
         if let Some(subscript_galley) = subscript_galley {
@@ -300,15 +306,16 @@ impl LeafNode {
             egui::Vec2::new(rect.width() - self.entity_node.padding.x * 2.0, text_input_height),
         );
         
+        let edit_bg_color = custom_color.unwrap_or_else(|| self.entity_node.current_bg_color());
+        
         // First scope: Draw background and border using painter (same as normal, no editing-specific outline)
         {
             let painter = ui.painter();
             // Background same as normal
-            let bg_color = custom_color.unwrap_or_else(|| self.entity_node.current_bg_color());
-            painter.rect_filled(rect, egui::CornerRadius::same(10), bg_color);
+            painter.rect_filled(rect, egui::CornerRadius::same(10), edit_bg_color);
             // Border based on selection
             let selected_border = egui::Color32::from_rgb(100, 150, 255);
-            let border_color = if is_selected { selected_border } else { self.entity_node.border_color };
+            let border_color = if is_selected { selected_border } else { self.entity_node.theme.node_border };
             if dotted_border {
                 super::draw_dotted_rect(
                     painter,
@@ -335,7 +342,7 @@ impl LeafNode {
             let text_edit = egui::TextEdit::singleline(editing_text)
                 .id(text_edit_id)
                 .font(self.entity_node.main_font_id())
-                .text_color(egui::Color32::WHITE)
+                .text_color(self.entity_node.theme.text_color_for_bg(edit_bg_color))
                 .desired_width(text_input_rect.width())
                 .margin(egui::Vec2::ZERO);
             
@@ -8,7 +8,7 @@ pub use leaf_node::*;
 pub use parent_node::*;
 
 /// Enum representing the different types of nodes in the editor
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum NodeType {
     Leaf(LeafNode),
     Parent(ParentNode),
@@ -29,6 +29,19 @@ impl NodeType {
             NodeType::Parent(parent_node) => parent_node.entity_node.position,
         }
     }
+
+    /// Set the top-left position of the node
+    pub fn set_position(&mut self, position: Pos2) {
+        match self {
+            NodeType::Leaf(leaf_node) => leaf_node.entity_node.position = position,
+            NodeType::Parent(parent_node) => parent_node.entity_node.position = position,
+        }
+    }
+
+    /// Whether this node is drawn as a parent zone
+    pub fn is_parent(&self) -> bool {
+        matches!(self, NodeType::Parent(_))
+    }
 }
 
 pub fn draw_dotted_rect(
@@ -60,3 +73,24 @@ pub fn draw_dotted_rect(
     draw_segmented(egui::pos2(r.min.x, r.max.y), r.max); // bottom (right to left will be drawn via min..max; acceptable)
     draw_segmented(r.min, egui::pos2(r.min.x, r.max.y)); // left
 }
+
+/// Fill a rect with 45° stripes, clipped to the rect
+pub fn draw_diagonal_stripes(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    stroke: egui::Stroke,
+    spacing: f32,
+) {
+    if !rect.is_positive() || spacing <= 0.0 {
+        return;
+    }
+    let painter = painter.with_clip_rect(rect.intersect(painter.clip_rect()));
+    // Each stripe runs down-left from a point on the top edge (extended to the right by the height)
+    let mut x = rect.min.x;
+    while x < rect.max.x + rect.height() {
+        let start = egui::pos2(x, rect.min.y);
+        let end = egui::pos2(x - rect.height(), rect.max.y);
+        painter.line_segment([start, end], stroke);
+        x += spacing;
+    }
+}
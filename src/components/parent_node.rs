@@ -2,6 +2,9 @@ use bevy::prelude::*;
 use egui::{text::CCursorRange, Color32, Pos2, Rect, Vec2};
 use super::{EntityNode, NodeResponse};
 
+/// Space kept between the furthest child and the parent's right/bottom border
+const BOTTOM_RIGHT_MARGIN: f32 = 30.0;
+
 /// Component for nodes that contain children (have InitialState or Children components)
 #[derive(Debug, Clone)]
 pub struct ParentNode {
@@ -13,6 +16,12 @@ pub struct ParentNode {
     pub min_content_size: Vec2,
     /// Margin around children
     pub child_margin: Vec2,
+    /// Whether the node is collapsed into a compact box hiding its descendants
+    pub collapsed: bool,
+    /// Number of descendants hidden while collapsed (shown as a badge)
+    pub hidden_descendant_count: usize,
+    /// Whether the state is parallel (all children active together), drawn striped with a "‖" badge
+    pub parallel: bool,
 }
 
 impl ParentNode {
@@ -23,12 +32,31 @@ impl ParentNode {
             title_bar_height: 30.0,
             min_content_size: Vec2::new(150.0, 80.0),
             child_margin: Vec2::new(10.0, 10.0),
+            collapsed: false,
+            hidden_descendant_count: 0,
+            parallel: false,
         };
         // Set initial size
         parent.entity_node.current_size = Vec2::new(200.0, 120.0);
         parent
     }
     
+    /// Switch between the default and the compact title bar, margins and font
+    ///
+    /// The height follows the title bar change right away so the move is picked up as a layout change.
+    pub fn set_compact(&mut self, compact: bool) {
+        self.entity_node.set_compact(compact);
+        let title_bar_height = if compact { 22.0 } else { 30.0 };
+        self.entity_node.current_size.y += title_bar_height - self.title_bar_height;
+        self.title_bar_height = title_bar_height;
+        self.child_margin = if compact { Vec2::new(6.0, 6.0) } else { Vec2::new(10.0, 10.0) };
+    }
+    
+    /// Size of the compact box drawn while collapsed
+    pub fn collapsed_size(&self) -> Vec2 {
+        Vec2::new(180.0, self.title_bar_height)
+    }
+    
     /// Get the rectangle for the entire parent node
     pub fn rect(&self) -> Rect {
         Rect::from_min_size(self.entity_node.position, self.entity_node.current_size)
@@ -49,6 +77,18 @@ impl ParentNode {
         Rect::from_min_size(content_start, content_size)
     }
     
+    /// Area children may occupy if the content area were `content_size`
+    /// 
+    /// Uses the same margins as `calculate_size_for_children`, so a child kept inside it
+    /// never makes the parent grow past `content_size`.
+    pub fn child_bounds(&self, content_size: Vec2) -> Rect {
+        let content_start = self.content_rect().min;
+        Rect::from_min_max(
+            content_start + self.child_margin,
+            content_start + content_size - Vec2::splat(BOTTOM_RIGHT_MARGIN),
+        )
+    }
+    
     /// Calculate the bounding box that should contain all child rectangles
     /// Parents only expand right and down, never left or up
     pub fn calculate_size_for_children(&mut self, child_rects: &[Rect]) {
@@ -77,9 +117,8 @@ impl ParentNode {
         
         // Calculate required content size based on maximum extents
         // Add extra margin to bottom and right edges so children aren't right against the border
-        let bottom_right_margin = 30.0;
-        let required_content_width = (max_x - content_start.x) + self.child_margin.x + bottom_right_margin;
-        let required_content_height = (max_y - content_start.y) + self.child_margin.y + bottom_right_margin;
+        let required_content_width = (max_x - content_start.x) + self.child_margin.x + BOTTOM_RIGHT_MARGIN;
+        let required_content_height = (max_y - content_start.y) + self.child_margin.y + BOTTOM_RIGHT_MARGIN;
         
         // Apply minimum size constraints
         let final_content_width = required_content_width.max(self.min_content_size.x);
@@ -155,11 +194,62 @@ impl ParentNode {
             node_response.right_clicked = true;
         }
         
+        // Double-clicking the title renames and the body adds a child; neither moves the node
+        if response.double_clicked_by(egui::PointerButton::Primary) {
+            if response.interact_pointer_pos().is_some_and(|pos| title_rect.contains(pos)) {
+                node_response.label_double_clicked = true;
+            } else {
+                node_response.body_double_clicked = true;
+            }
+            node_response.dragged = false;
+            node_response.drag_delta = egui::Vec2::ZERO;
+        }
+        
         node_response.hovered = response.hovered();
         
         // Draw the parent node (with editing support)
         self.draw_parent_node_with_editing(ui, rect, title_rect, name, entity_id, is_selected, is_editing, editing_text, should_focus, first_focus, custom_color, dotted_border);
         
+        // Collapse toggle in the title bar, left of the + button
+        let toggle_size = 16.0;
+        let toggle_rect = egui::Rect::from_min_size(
+            egui::Pos2::new(rect.max.x - toggle_size * 2.0 - 8.0, title_rect.center().y - toggle_size / 2.0),
+            egui::Vec2::splat(toggle_size),
+        );
+        let toggle_response = ui.allocate_rect(toggle_rect, egui::Sense::click());
+        if toggle_response.clicked() {
+            node_response.collapse_toggled = true;
+        }
+        let title_text_color = self.entity_node.theme.text_color_for_bg(self.title_bg_color(custom_color));
+        let toggle_color = if toggle_response.hovered() {
+            title_text_color
+        } else {
+            title_text_color.gamma_multiply(0.63)
+        };
+        ui.painter().text(
+            toggle_rect.center(),
+            egui::Align2::CENTER_CENTER,
+            if self.collapsed { "▸" } else { "▾" },
+            egui::FontId::proportional(14.0),
+            toggle_color,
+        );
+        
+        // Badge with the number of hidden descendants, left of the toggle
+        if self.collapsed && self.hidden_descendant_count > 0 {
+            let badge_text = self.hidden_descendant_count.to_string();
+            let badge_fill = Color32::from_rgb(80, 120, 200);
+            let badge_text_color = self.entity_node.theme.text_color_for_bg(badge_fill);
+            let badge_galley = ui.fonts(|f| f.layout_no_wrap(badge_text, self.entity_node.subscript_font_id(), badge_text_color));
+            let badge_size = Vec2::new((badge_galley.size().x + 10.0).max(18.0), 16.0);
+            let badge_rect = egui::Rect::from_min_size(
+                egui::Pos2::new(toggle_rect.min.x - badge_size.x - 4.0, title_rect.center().y - badge_size.y / 2.0),
+                badge_size,
+            );
+            let painter = ui.painter();
+            painter.rect_filled(badge_rect, egui::CornerRadius::same(8), badge_fill);
+            painter.galley(badge_rect.center() - badge_galley.size() / 2.0, badge_galley, badge_text_color);
+        }
+        
         // Add the + button for transitions (show for selected nodes, including root for global transitions)
         if is_selected {
             let button_size = 16.0;
@@ -185,6 +275,7 @@ impl ParentNode {
             painter.circle_filled(button_rect.center(), button_size / 2.0, button_color);
             
             // Draw the + symbol
+            let cross_color = self.entity_node.theme.text_color_for_bg(button_color);
             let line_width = 1.5;
             let cross_size = 6.0;
             painter.line_segment(
@@ -192,14 +283,14 @@ impl ParentNode {
                     button_rect.center() - egui::Vec2::new(cross_size / 2.0, 0.0),
                     button_rect.center() + egui::Vec2::new(cross_size / 2.0, 0.0),
                 ],
-                egui::Stroke::new(line_width, egui::Color32::WHITE),
+                egui::Stroke::new(line_width, cross_color),
             );
             painter.line_segment(
                 [
                     button_rect.center() - egui::Vec2::new(0.0, cross_size / 2.0),
                     button_rect.center() + egui::Vec2::new(0.0, cross_size / 2.0),
                 ],
-                egui::Stroke::new(line_width, egui::Color32::WHITE),
+                egui::Stroke::new(line_width, cross_color),
             );
         }
         
@@ -229,6 +320,20 @@ impl ParentNode {
         }
     }
 
+    /// Title bar fill: `custom_color` (the active highlight) if set, otherwise the body color slightly darkened
+    fn title_bg_color(&self, custom_color: Option<Color32>) -> Color32 {
+        if let Some(active_color) = custom_color {
+            return active_color;
+        }
+        let bg_color = self.entity_node.current_bg_color();
+        Color32::from_rgba_unmultiplied(
+            bg_color.r().saturating_sub(10),
+            bg_color.g().saturating_sub(10),
+            bg_color.b().saturating_sub(10),
+            bg_color.a(),
+        )
+    }
+
     /// Draw the parent node with title bar and content area
     fn draw_parent_node_normal(
         &self,
@@ -254,7 +359,7 @@ impl ParentNode {
         
         // Draw container border (dotted optional)
         let selected_border = Color32::from_rgb(100, 150, 255);
-        let border_color = if is_selected { selected_border } else { self.entity_node.border_color };
+        let border_color = if is_selected { selected_border } else { self.entity_node.theme.node_border };
         if dotted_border {
             super::draw_dotted_rect(
                 painter,
@@ -274,40 +379,35 @@ impl ParentNode {
         }
         
         // Title bar background: use custom_color (gold/bright gold) if active, otherwise slightly darker normal
-        let title_bg_color = if let Some(active_color) = custom_color {
-            active_color
-        } else {
-            Color32::from_rgba_unmultiplied(
-                bg_color.r().saturating_sub(10),
-                bg_color.g().saturating_sub(10),
-                bg_color.b().saturating_sub(10),
-                bg_color.a(),
-            )
-        };
+        let title_bg_color = self.title_bg_color(custom_color);
         
+        // While collapsed the title bar is the whole node
+        let bottom_radius = if self.collapsed { 8 } else { 0 };
         painter.rect_filled(
             title_rect,
             egui::CornerRadius {
                 nw: 8,
                 ne: 8,
-                sw: 0,
-                se: 0,
+                sw: bottom_radius,
+                se: bottom_radius,
             },
             title_bg_color,
         );
         
         // Draw title bar separator line
-        let separator_y = title_rect.max.y;
-        painter.line_segment(
-            [
-                egui::Pos2::new(rect.min.x + 5.0, separator_y),
-                egui::Pos2::new(rect.max.x - 5.0, separator_y),
-            ],
-            egui::Stroke::new(1.0, self.entity_node.border_color),
-        );
+        if !self.collapsed {
+            let separator_y = title_rect.max.y;
+            painter.line_segment(
+                [
+                    egui::Pos2::new(rect.min.x + 5.0, separator_y),
+                    egui::Pos2::new(rect.max.x - 5.0, separator_y),
+                ],
+                egui::Stroke::new(1.0, self.entity_node.theme.node_border),
+            );
+        }
         
         // Determine text color based on title bar background color (smooth interpolation)
-        let text_color = crate::editor_state::compute_text_color_for_bg(title_bg_color);
+        let text_color = self.entity_node.theme.text_color_for_bg(title_bg_color);
         
         // Draw title text (name and entity ID side by side)
         let font_id = self.entity_node.main_font_id();
@@ -320,14 +420,12 @@ impl ParentNode {
         
         painter.galley(name_pos, name_galley.clone(), text_color);
         
+        let mut title_text_end_x = name_pos.x + name_galley.size().x;
+        
         // Draw entity ID if provided (to the right of the name)
         if let Some(entity_id) = entity_id {
             let entity_font_id = self.entity_node.subscript_font_id();
-            let entity_id_color = if crate::editor_state::prefers_dark_text(text_color) {
-                Color32::from_rgba_unmultiplied(0, 0, 0, 180) // Semi-transparent black
-            } else {
-                Color32::from_rgba_unmultiplied(255, 255, 255, 180) // Semi-transparent white
-            };
+            let entity_id_color = text_color.gamma_multiply(0.7);
             let entity_galley = ui.fonts(|f| f.layout_no_wrap(
                 format!(" ({})", entity_id),
                 entity_font_id,
@@ -339,11 +437,37 @@ impl ParentNode {
                 text_y + (name_galley.size().y - entity_galley.size().y) * 0.5,
             );
             
+            title_text_end_x = entity_pos.x + entity_galley.size().x;
             painter.galley(entity_pos, entity_galley, entity_id_color);
         }
         
+        // Parallel badge after the title text
+        if self.parallel {
+            painter.text(
+                egui::Pos2::new(title_text_end_x + 8.0, title_rect.center().y),
+                egui::Align2::LEFT_CENTER,
+                "‖",
+                egui::FontId::proportional(16.0),
+                text_color,
+            );
+        }
+        
         // Draw content area outline (for debugging/visualization)
+        if self.collapsed {
+            return;
+        }
         let content_rect = self.content_rect();
+        
+        // Stripe the zone of parallel states so their AND-semantics stand out
+        if self.parallel {
+            super::draw_diagonal_stripes(
+                painter,
+                content_rect.shrink(2.0),
+                egui::Stroke::new(1.0, Color32::from_rgba_unmultiplied(255, 255, 255, 18)),
+                12.0,
+            );
+        }
+        
         painter.rect_stroke(
             content_rect.shrink(2.0),
             egui::CornerRadius::same(4),
@@ -378,7 +502,7 @@ impl ParentNode {
             
             // Draw container border with selection highlight if selected
             let selected_border = Color32::from_rgb(100, 150, 255);
-            let border_color = if is_selected { selected_border } else { self.entity_node.border_color };
+            let border_color = if is_selected { selected_border } else { self.entity_node.theme.node_border };
             painter.rect_stroke(
                 rect,
                 egui::CornerRadius::same(8),
@@ -387,12 +511,7 @@ impl ParentNode {
             );
             
             // Draw title bar background (slightly darker)
-            let title_bg_color = Color32::from_rgba_unmultiplied(
-                bg_color.r().saturating_sub(10),
-                bg_color.g().saturating_sub(10),
-                bg_color.b().saturating_sub(10),
-                bg_color.a(),
-            );
+            let title_bg_color = self.title_bg_color(None);
             
             painter.rect_filled(
                 title_rect,
@@ -412,7 +531,7 @@ impl ParentNode {
                     egui::Pos2::new(rect.min.x + 5.0, separator_y),
                     egui::Pos2::new(rect.max.x - 5.0, separator_y),
                 ],
-                egui::Stroke::new(1.0, self.entity_node.border_color),
+                egui::Stroke::new(1.0, self.entity_node.theme.node_border),
             );
         }
         
@@ -432,7 +551,7 @@ impl ParentNode {
             let text_edit = egui::TextEdit::singleline(editing_text)
                 .id(text_edit_id)
                 .font(self.entity_node.main_font_id())
-                .text_color(egui::Color32::WHITE)
+                .text_color(self.entity_node.theme.text_color_for_bg(self.title_bg_color(None)))
                 .desired_width(text_input_rect.width())
                 .margin(egui::Vec2::ZERO);
             
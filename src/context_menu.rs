@@ -3,16 +3,27 @@
 //! This module handles:
 //! - Right-click context menu rendering and interaction
 //! - Node action processing (Inspect, Add Child)
+//! - Adding children at a position (double-click on a parent's body)
 //! - Entity creation and hierarchy management
 
 use bevy::prelude::*;
 use bevy_gearbox::{StateMachine};
 use bevy_egui::egui;
 
-use crate::editor_state::{EditorState, NodeAction, NodeActionTriggered, NodeContextMenuRequested, TransitionContextMenuRequested, DeleteNode, SetInitialStateRequested, DeleteTransitionByEdge, SaveStateMachine, CloseMachineRequested};
+use crate::editor_state::{EditorConfig, EditorState, EntityNamesQuery, entity_display_name, entity_raw_name, AddChildAt, PendingDeletion, NodeAction, NodeActionTriggered, NodeContextMenuRequested, TransitionContextMenuRequested, DeleteNode, SetInitialStateRequested, DeleteTransitionByEdge, ReorderTransition, ToggleTransitionEnabled, SaveStateMachine, CloseMachineRequested, ValidateMachine, ExportMachineSvg, ExportMachineDot, ExportMachineJson, ToggleOrthogonalEdges, AlignSelection, SetNodeColor, EdgeLabel, RenameTransitionLabelRequested};
 use crate::components::{NodeType, LeafNode};
 use crate::{StateMachinePersistentData, StateMachineTransientData};
-use crate::node_kind::{AddChildClicked, MakeParallelClicked, MakeParentClicked, MakeLeafClicked};
+use crate::node_kind::{AddChildClicked, MakeParallelClicked, MakeParentClicked, MakeLeafClicked, NodeKind};
+
+/// Base colors offered by the node context menu
+const NODE_COLOR_PALETTE: [egui::Color32; 6] = [
+    egui::Color32::from_rgb(150, 60, 60),
+    egui::Color32::from_rgb(170, 110, 50),
+    egui::Color32::from_rgb(70, 130, 70),
+    egui::Color32::from_rgb(60, 110, 150),
+    egui::Color32::from_rgb(110, 80, 150),
+    egui::Color32::from_rgb(150, 70, 120),
+];
 
 /// Observer to handle context menu requests
 /// 
@@ -51,6 +62,102 @@ pub fn handle_transition_context_menu_request(
     editor_state.suppress_background_context_menu_once = true;
 }
 
+/// Observer to start inline editing of a transition's display label
+pub fn handle_rename_transition_label_request(
+    rename_requested: On<RenameTransitionLabelRequested>,
+    mut q_sm: Query<&mut StateMachineTransientData, With<StateMachine>>,
+    q_source: Query<&bevy_gearbox::transitions::Source>,
+    q_child_of: Query<&bevy_gearbox::StateChildOf>,
+    q_edge_label: Query<&EdgeLabel>,
+    q_entities: EntityNamesQuery,
+) {
+    let edge_entity = rename_requested.edge_entity;
+    let Ok(source) = q_source.get(edge_entity) else {
+        warn!("⚠️ RenameTransitionLabelRequested: edge {:?} has no Source", edge_entity);
+        return;
+    };
+    let root = q_child_of.root_ancestor(source.0);
+    let Ok(mut transient_data) = q_sm.get_mut(root) else {
+        return;
+    };
+
+    let current_label = match q_edge_label.get(edge_entity) {
+        Ok(label) => label.0.clone(),
+        Err(_) => entity_raw_name(edge_entity, &q_entities),
+    };
+    transient_data.text_editing.start_editing_edge_label(edge_entity, &current_label);
+}
+
+/// Observer to set or clear a node's custom base color
+pub fn handle_set_node_color(
+    set_color: On<SetNodeColor>,
+    mut q_sm: Query<&mut StateMachinePersistentData, With<StateMachine>>,
+    q_child_of: Query<&bevy_gearbox::StateChildOf>,
+) {
+    let root = q_child_of.root_ancestor(set_color.entity);
+    let Ok(mut persistent_data) = q_sm.get_mut(root) else {
+        return;
+    };
+    match set_color.color {
+        Some([r, g, b, a]) => {
+            persistent_data.node_colors.insert(set_color.entity, egui::Color32::from_rgba_unmultiplied(r, g, b, a));
+        }
+        None => {
+            persistent_data.node_colors.remove(&set_color.entity);
+        }
+    }
+}
+
+/// Observer to switch a machine between elbow and two-segment transition routing
+pub fn handle_toggle_orthogonal_edges(
+    toggle: On<ToggleOrthogonalEdges>,
+    mut q_sm: Query<&mut StateMachinePersistentData, With<StateMachine>>,
+) {
+    let Ok(mut persistent_data) = q_sm.get_mut(toggle.entity) else {
+        return;
+    };
+    persistent_data.orthogonal_edges = !persistent_data.orthogonal_edges;
+    info!("✅ {:?} now uses {} transitions", toggle.entity, if persistent_data.orthogonal_edges { "elbow" } else { "two-segment" });
+}
+
+/// Observer to add a leaf child at a specific position (double-clicking a parent's body)
+pub fn handle_add_child_at(
+    add_child_at: On<AddChildAt>,
+    mut commands: Commands,
+    mut q_sm: Query<(&mut StateMachinePersistentData, &StateMachineTransientData), With<StateMachine>>,
+    q_child_of: Query<&bevy_gearbox::StateChildOf>,
+) {
+    let root = q_child_of.root_ancestor(add_child_at.parent);
+    let Ok((mut persistent_data, transient_data)) = q_sm.get_mut(root) else {
+        return;
+    };
+    let child = add_leaf_child(&mut commands, &mut persistent_data, transient_data, add_child_at.parent, add_child_at.position, "New State");
+    commands.trigger(crate::Select { selected: Some(child) });
+}
+
+/// Spawn a leaf named `name` under `parent` at `position` and notify the parent's NodeKind machine
+fn add_leaf_child(
+    commands: &mut Commands,
+    persistent_data: &mut StateMachinePersistentData,
+    transient_data: &StateMachineTransientData,
+    parent: Entity,
+    position: egui::Pos2,
+    name: &str,
+) -> Entity {
+    let child_entity = commands.spawn((
+        bevy_gearbox::StateChildOf(parent),
+        Name::new(name.to_string()),
+    )).id();
+    persistent_data.nodes.insert(child_entity, NodeType::Leaf(LeafNode::new(position)));
+    
+    // A leaf parent becomes a Parent via its NodeKind machine
+    if let Some(&nk_root) = transient_data.node_kind_roots.get(&parent) {
+        commands.trigger(AddChildClicked::new(nk_root));
+        commands.trigger(crate::node_kind::ChildAdded::new(nk_root));
+    }
+    child_entity
+}
+
 /// Observer to handle node actions triggered from context menus
 /// 
 /// Processes actions like Inspect and Add Child, performing the necessary
@@ -61,7 +168,9 @@ pub fn handle_node_action(
     mut editor_state: ResMut<EditorState>,
     mut q_sm: Query<(&mut StateMachinePersistentData, &mut StateMachineTransientData), With<StateMachine>>,
     q_child_of: Query<&bevy_gearbox::StateChildOf>,
-    q_name: Query<&Name>,
+    q_children: Query<&bevy_gearbox::StateChildren>,
+    q_entities: EntityNamesQuery,
+    config: Res<EditorConfig>,
 ) {
     // Resolve the state machine root that contains this entity
     let selected_machine = q_child_of.root_ancestor(node_action_triggered.entity);
@@ -76,34 +185,85 @@ pub fn handle_node_action(
             editor_state.inspected_entity = Some(node_action_triggered.entity);
         }
         NodeAction::AddChild => {
-            // Create a new child entity
-            let child_entity = commands.spawn((
-                bevy_gearbox::StateChildOf(node_action_triggered.entity),
-                Name::new("New State"),
-            )).id();
-        
-            // Add the child as a leaf node in the editor at an offset position
-            if let Some(parent_node) = persistent_data.nodes.get(&node_action_triggered.entity) {
-                let parent_pos = match parent_node {
-                    NodeType::Leaf(leaf_node) => leaf_node.entity_node.position,
-                    NodeType::Parent(parent_node) => parent_node.entity_node.position,
-                };
-            
-                // Position the child at an offset from the parent
-                let child_pos = parent_pos + egui::Vec2::new(50.0, 50.0);
-                let leaf_node = LeafNode::new(child_pos);
-                persistent_data.nodes.insert(child_entity, NodeType::Leaf(leaf_node));
-            }
-
-            // Notify NodeKind machine for this parent
+            // Place the child at an offset from the parent
             let parent_entity = node_action_triggered.entity;
-            if let Some(&nk_root) = transient_data.node_kind_roots.get(&parent_entity) {
-                commands.trigger(AddChildClicked::new(nk_root));
-                commands.trigger(crate::node_kind::ChildAdded::new(nk_root));
+            let parent_pos = persistent_data.nodes.get(&parent_entity).map_or(egui::Pos2::ZERO, |parent_node| parent_node.position());
+            add_leaf_child(&mut commands, &mut persistent_data, &transient_data, parent_entity, parent_pos + config.child_spawn_offset, "New State");
+        }
+        NodeAction::AddSibling => {
+            // Place the sibling just below this node and start renaming it right away
+            let original = node_action_triggered.entity;
+            let Ok(child_of) = q_child_of.get(original) else {
+                warn!("⚠️ Cannot add a sibling to a state machine root");
+                return;
+            };
+            let sibling_pos = persistent_data
+                .nodes
+                .get(&original)
+                .map_or(egui::Pos2::ZERO, |node| node.current_rect().left_bottom() + egui::Vec2::new(0.0, 20.0));
+            let sibling = add_leaf_child(&mut commands, &mut persistent_data, &transient_data, child_of.0, sibling_pos, "New State");
+            commands.trigger(crate::Select { selected: Some(sibling) });
+            transient_data.text_editing.start_editing(sibling, "New State");
+        }
+        NodeAction::BringToFront | NodeAction::SendToBack => {
+            // Stack just above (or below) every sibling
+            let entity = node_action_triggered.entity;
+            let Ok(child_of) = q_child_of.get(entity) else {
+                warn!("⚠️ Cannot restack a state machine root");
+                return;
+            };
+            let sibling_biases = q_children
+                .get(child_of.0)
+                .map(|siblings| {
+                    siblings
+                        .iter()
+                        .filter(|sibling| *sibling != entity)
+                        .map(|sibling| persistent_data.z_bias.get(&sibling).copied().unwrap_or_default())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            let bias = if matches!(node_action_triggered.action, NodeAction::BringToFront) {
+                sibling_biases.into_iter().max().map_or(0, |max| max + 1)
+            } else {
+                sibling_biases.into_iter().min().map_or(0, |min| min - 1)
+            };
+            if bias == 0 {
+                persistent_data.z_bias.remove(&entity);
+            } else {
+                persistent_data.z_bias.insert(entity, bias);
             }
         }
+        NodeAction::Duplicate => {
+            // Spawn a lone sibling; children and transitions are not copied
+            let original = node_action_triggered.entity;
+            let Ok(child_of) = q_child_of.get(original) else {
+                warn!("⚠️ Cannot duplicate a state machine root");
+                return;
+            };
+            let Some(original_pos) = persistent_data.nodes.get(&original).map(|node| node.position()) else {
+                return;
+            };
+            let name = Some(entity_raw_name(original, &q_entities)).filter(|name| !name.is_empty()).unwrap_or_else(|| "New State".to_string());
+            // Named like pasted copies
+            let duplicate = add_leaf_child(
+                &mut commands,
+                &mut persistent_data,
+                &transient_data,
+                child_of.0,
+                original_pos + config.duplicate_offset,
+                &format!("{} (copy)", name),
+            );
+            commands.trigger(crate::Select { selected: Some(duplicate) });
+        }
+        NodeAction::SelectSubtree => {
+            // Multi-select the parent and all of its descendants
+            let parent = node_action_triggered.entity;
+            editor_state.view.selected_nodes = std::iter::once(parent)
+                .chain(q_children.iter_descendants(parent))
+                .collect();
+        }
         NodeAction::Rename => {
-            let entity_name = q_name.get(node_action_triggered.entity).unwrap().to_string();
+            let entity_name = entity_raw_name(node_action_triggered.entity, &q_entities);
             transient_data.text_editing.start_editing(node_action_triggered.entity, &entity_name);
         }
         NodeAction::MakeParallel => {
@@ -123,6 +283,10 @@ pub fn handle_node_action(
         NodeAction::MakeLeaf => {
             // Ask NK to become Leaf from any current kind
             let state_entity = node_action_triggered.entity;
+            if q_children.get(state_entity).is_ok_and(|children| children.into_iter().next().is_some()) {
+                warn!("⚠️ Cannot make {:?} a leaf while it has children", state_entity);
+                return;
+            }
             if let Some(&nk_root) = transient_data.node_kind_roots.get(&state_entity) {
                 commands.trigger(MakeLeafClicked::new(nk_root));
             }
@@ -133,18 +297,101 @@ pub fn handle_node_action(
             commands.trigger(SetInitialStateRequested { child_entity });
         }
         NodeAction::ResetRegion => {
-            // Call into core: fire ResetMachine on the selected machine root
-            commands.trigger(bevy_gearbox::ResetRegion::new(selected_machine));
+            let region = node_action_triggered.entity;
+            if region == selected_machine {
+                // Call into core: fire ResetMachine on the selected machine root
+                commands.trigger(bevy_gearbox::ResetRegion::new(selected_machine));
+            } else {
+                commands.queue(move |world: &mut World| reset_region_to_initial(world, region));
+            }
+        }
+        NodeAction::ToggleBreakpoint => {
+            let entity = node_action_triggered.entity;
+            if editor_state.breakpoints.remove(&entity) {
+                info!("Removed breakpoint on {:?}", entity);
+            } else {
+                editor_state.breakpoints.insert(entity);
+                info!("🔴 Breakpoint set on {:?}", entity);
+            }
         }
         NodeAction::Delete => {
-            // Trigger the delete node event
-            commands.trigger(DeleteNode {
-                entity: node_action_triggered.entity,
-            });
+            // Parents take their whole subtree with them, so ask first; leaves are deleted right away
+            let entity = node_action_triggered.entity;
+            let descendant_count = q_children.iter_descendants(entity).count();
+            if descendant_count > 0 {
+                editor_state.pending_deletion = Some(PendingDeletion { entity, descendant_count });
+            } else {
+                commands.trigger(DeleteNode { entity });
+            }
         }
     }
 }
 
+/// Exit every active descendant of `region` and re-enter it from its `InitialState`
+///
+/// Descendants are exited deepest first, then entered top-down following `InitialState`
+/// (all children of a `Parallel` state), firing gearbox's `ExitState` / `EnterState` so
+/// the live machine and any listeners see the reset. The region itself stays active.
+pub fn reset_region_to_initial(world: &mut World, region: Entity) {
+    if world.get::<bevy_gearbox::active::Active>(region).is_none() {
+        warn!("⚠️ Cannot reset region {:?}: it is not active", region);
+        return;
+    }
+
+    // Exit: active descendants, children before their parents
+    let mut active_descendants = Vec::new();
+    let mut to_visit: Vec<Entity> = world
+        .get::<bevy_gearbox::StateChildren>(region)
+        .map(|children| children.into_iter().copied().collect())
+        .unwrap_or_default();
+    while let Some(entity) = to_visit.pop() {
+        if world.get::<bevy_gearbox::active::Active>(entity).is_some() {
+            active_descendants.push(entity);
+        }
+        if let Some(children) = world.get::<bevy_gearbox::StateChildren>(entity) {
+            to_visit.extend(children.into_iter().copied());
+        }
+    }
+    for &entity in active_descendants.iter().rev() {
+        world.entity_mut(entity).remove::<bevy_gearbox::active::Active>();
+        world.trigger(bevy_gearbox::ExitState { target: entity });
+    }
+
+    // Enter: follow InitialState down from the region, fanning out at parallel states
+    let mut to_enter = region_entry_children(world, region);
+    let mut entered = 0;
+    while let Some(entity) = to_enter.pop() {
+        world.entity_mut(entity).insert(bevy_gearbox::active::Active);
+        world.trigger(bevy_gearbox::EnterState { target: entity });
+        to_enter.extend(region_entry_children(world, entity));
+        entered += 1;
+    }
+    info!("↺ Reset region {:?}: exited {} state(s), entered {}", region, active_descendants.len(), entered);
+}
+
+/// Children entered along with `state`: all of them for a parallel state, else its `InitialState`
+fn region_entry_children(world: &World, state: Entity) -> Vec<Entity> {
+    if world.get::<bevy_gearbox::Parallel>(state).is_some() {
+        world
+            .get::<bevy_gearbox::StateChildren>(state)
+            .map(|children| children.into_iter().copied().collect())
+            .unwrap_or_default()
+    } else {
+        world
+            .get::<bevy_gearbox::InitialState>(state)
+            .map(|initial_state| vec![initial_state.0])
+            .unwrap_or_default()
+    }
+}
+
+/// Context menu entry for a node action; greyed out with `disabled_reason` as its tooltip when set
+fn node_action_button(ui: &mut egui::Ui, label: &str, action: &NodeAction, disabled_reason: Option<&str>) -> bool {
+    ui.add_enabled(disabled_reason.is_none(), egui::Button::new(label))
+        .on_hover_text(action.description())
+        .on_disabled_hover_text(disabled_reason.unwrap_or_default())
+        .clicked()
+}
+
 /// Render context menu UI if one is requested
 /// 
 /// This function should be called during UI rendering to display context menus.
@@ -152,9 +399,14 @@ pub fn render_context_menu(
     ctx: &egui::Context,
     editor_state: &mut EditorState,
     commands: &mut Commands,
-    all_entities: &Query<(Entity, Option<&Name>, Option<&bevy_gearbox::InitialState>)>,
+    all_entities: &EntityNamesQuery,
     q_child_of: &Query<&bevy_gearbox::StateChildOf>,
     q_parallel: &Query<&bevy_gearbox::Parallel>,
+    multi_selection_len: usize,
+    edge_order_badge: Option<(usize, usize)>,
+    edge_disabled: bool,
+    node_kind: Option<NodeKind>,
+    child_count: usize,
 ) {
     if let (Some(entity), Some(position)) = (editor_state.context_menu_entity, editor_state.context_menu_position) {
         let menu_id = egui::Id::new("context_menu").with(entity);
@@ -169,7 +421,7 @@ pub fn render_context_menu(
                     .show(ui, |ui| {
                         ui.set_min_width(120.0);
                         
-                        if ui.button("Inspect").clicked() {
+                        if ui.button("Inspect").on_hover_text(NodeAction::Inspect.description()).clicked() {
                             commands.trigger(NodeActionTriggered {
                                 entity,
                                 action: NodeAction::Inspect,
@@ -179,7 +431,7 @@ pub fn render_context_menu(
                             ui.close();
                         }
 
-                        if ui.button("Rename").clicked() {
+                        if ui.button("Rename").on_hover_text(NodeAction::Rename.description()).clicked() {
                             commands.trigger(NodeActionTriggered {
                                 entity,
                                 action: NodeAction::Rename,
@@ -189,125 +441,199 @@ pub fn render_context_menu(
                             ui.close();
                         }
 
-                        // Determine type of node (Leaf/Parent/Parallel/Root)
-                        let is_parent = all_entities.get(entity).ok().and_then(|(_,_,init)| init.map(|_|())).is_some();
-                        let is_parallel = q_parallel.get(entity).is_ok();
-                        let is_leaf = !is_parent && !is_parallel;
-                        let is_root = editor_state.open_machines.iter().any(|m| m.entity == entity);
+                        // Determine type of node (Leaf/Parent/Parallel/Root); the NodeKind machine is
+                        // authoritative, the gearbox components are the fallback until it exists
+                        let kind = node_kind.unwrap_or_else(|| {
+                            if q_parallel.get(entity).is_ok() {
+                                NodeKind::Parallel
+                            } else if all_entities.get(entity).is_ok_and(|(_, _, initial_state, _)| initial_state.is_some()) {
+                                NodeKind::Parent
+                            } else {
+                                NodeKind::Leaf
+                            }
+                        });
+                        let is_root = editor_state.view.open_machines.iter().any(|m| m.entity == entity);
 
                         // Common options already added: Inspect, Rename
 
-                        // Leaf-specific options: Make Parallel, Make Parent
-                        if is_leaf {
-                            if ui.button("Make Parallel").clicked() {
-                                commands.trigger(NodeActionTriggered { entity, action: NodeAction::MakeParallel });
+                        // Multi-selection: alignment tools for the selected nodes
+                        if multi_selection_len > 1 {
+                            ui.menu_button(format!("Arrange {} selected", multi_selection_len), |ui| {
+                                for mode in crate::alignment::AlignMode::ALL {
+                                    if ui.button(mode.label()).clicked() {
+                                        let machine = q_child_of.root_ancestor(entity);
+                                        commands.trigger(AlignSelection { machine, mode });
+                                        editor_state.context_menu_entity = None;
+                                        editor_state.context_menu_position = None;
+                                        ui.close();
+                                    }
+                                }
+                            });
+                        }
+
+                        // Root-specific actions (save, close, reset)
+                        if is_root {
+                            if ui.button("💾 Save Machine").on_hover_text("Save this machine and its editor layout to a scene file in assets/.").clicked() {
+                                commands.trigger(SaveStateMachine { entity });
                                 editor_state.context_menu_entity = None;
                                 editor_state.context_menu_position = None;
                                 ui.close();
                             }
-                            if ui.button("Make Parent").clicked() {
-                                commands.trigger(NodeActionTriggered { entity, action: NodeAction::MakeParent });
+                            
+                            if ui.button("⤷ Toggle elbow transitions").on_hover_text("Draw this machine's transitions as orthogonal connectors instead of straight arrows.").clicked() {
+                                commands.trigger(ToggleOrthogonalEdges { entity });
                                 editor_state.context_menu_entity = None;
                                 editor_state.context_menu_position = None;
                                 ui.close();
                             }
-                        }
-
-                        // Root-specific actions (save, close, reset)
-                        if is_root {
-                            if ui.button("💾 Save Machine").clicked() {
-                                commands.trigger(SaveStateMachine { entity });
+                            
+                            if ui.button("🖼 Export SVG").on_hover_text("Write the machine diagram as an SVG image to assets/.").clicked() {
+                                let file_name = crate::editor_state::entity_display_name(entity, all_entities).replace(" ", "_").to_lowercase();
+                                commands.trigger(ExportMachineSvg { entity, path: format!("assets/{}.svg", file_name).into() });
                                 editor_state.context_menu_entity = None;
                                 editor_state.context_menu_position = None;
                                 ui.close();
                             }
                             
-                            if ui.button("✕ Close Machine").clicked() {
-                                commands.trigger(CloseMachineRequested { entity });
+                            if ui.button("🗎 Export JSON").on_hover_text("Write the machine graph (states, transitions, layout) as JSON to assets/.").clicked() {
+                                let file_name = crate::editor_state::entity_display_name(entity, all_entities).replace(" ", "_").to_lowercase();
+                                commands.trigger(ExportMachineJson { entity, path: format!("assets/{}.graph.json", file_name).into() });
                                 editor_state.context_menu_entity = None;
                                 editor_state.context_menu_position = None;
                                 ui.close();
                             }
                             
-                            ui.separator();
-                            
-                            if ui.button("↺ Reset Machine").clicked() {
-                                commands.trigger(NodeActionTriggered { entity, action: NodeAction::ResetRegion });
+                            if ui.button("📋 Copy as DOT").on_hover_text("Copy the machine graph to the clipboard in Graphviz DOT format.").clicked() {
+                                commands.trigger(ExportMachineDot { entity });
                                 editor_state.context_menu_entity = None;
                                 editor_state.context_menu_position = None;
                                 ui.close();
                             }
-                        }
-
-                        // Parent-specific: Make Parallel, Make Leaf, Add child, Reset (if not already shown as root)
-                        if is_parent {
-                            if !is_root {
-                                if ui.button("↺ Reset Region").clicked() {
-                                    commands.trigger(NodeActionTriggered { entity, action: NodeAction::ResetRegion });
-                                    editor_state.context_menu_entity = None;
-                                    editor_state.context_menu_position = None;
-                                    ui.close();
-                                }
-                            }
-                            if ui.button("Make Parallel").clicked() {
-                                commands.trigger(NodeActionTriggered { entity, action: NodeAction::MakeParallel });
+                            
+                            if ui.button("✔ Validate Machine").on_hover_text("Check the machine for problems unreachable states and parents with children but no initial state; problems are outlined in red.").clicked() {
+                                commands.trigger(ValidateMachine { entity });
                                 editor_state.context_menu_entity = None;
                                 editor_state.context_menu_position = None;
                                 ui.close();
                             }
-                            if ui.button("Make Leaf").clicked() {
-                                commands.trigger(NodeActionTriggered { entity, action: NodeAction::MakeLeaf });
+                            
+                            if ui.button("✕ Close Machine").on_hover_text("Remove this machine from the canvas. The machine itself keeps running.").clicked() {
+                                commands.trigger(CloseMachineRequested { entity });
                                 editor_state.context_menu_entity = None;
                                 editor_state.context_menu_position = None;
                                 ui.close();
                             }
-                            if ui.button("Add child").clicked() {
-                                commands.trigger(NodeActionTriggered { entity, action: NodeAction::AddChild });
+                            
+                            ui.separator();
+                            
+                            if ui.button("↺ Reset Machine").on_hover_text(NodeAction::ResetRegion.description()).clicked() {
+                                commands.trigger(NodeActionTriggered { entity, action: NodeAction::ResetRegion });
                                 editor_state.context_menu_entity = None;
                                 editor_state.context_menu_position = None;
                                 ui.close();
                             }
                         }
 
-                        // Parallel-specific: Make Leaf, Make Parent, Add child
-                        if is_parallel {
-                            if !is_root {
-                                if ui.button("↺ Reset Region").clicked() {
-                                    commands.trigger(NodeActionTriggered { entity, action: NodeAction::ResetRegion });
-                                    editor_state.context_menu_entity = None;
-                                    editor_state.context_menu_position = None;
-                                    ui.close();
-                                }
-                            }
-                            if ui.button("Make Leaf").clicked() {
-                                commands.trigger(NodeActionTriggered { entity, action: NodeAction::MakeLeaf });
+                        // Kind-specific actions are always listed, greyed out with a reason when they don't apply
+                        let has_children = child_count > 0;
+                        if node_action_button(ui, "Select subtree", &NodeAction::SelectSubtree, (!has_children).then_some("This state has no children.")) {
+                            commands.trigger(NodeActionTriggered { entity, action: NodeAction::SelectSubtree });
+                            editor_state.context_menu_entity = None;
+                            editor_state.context_menu_position = None;
+                            ui.close();
+                        }
+                        if !is_root {
+                            let reason = (kind == NodeKind::Leaf).then_some("Only parent and parallel states are regions.");
+                            if node_action_button(ui, "↺ Reset Region", &NodeAction::ResetRegion, reason) {
+                                commands.trigger(NodeActionTriggered { entity, action: NodeAction::ResetRegion });
                                 editor_state.context_menu_entity = None;
                                 editor_state.context_menu_position = None;
                                 ui.close();
                             }
-                            if ui.button("Make Parent").clicked() {
-                                commands.trigger(NodeActionTriggered { entity, action: NodeAction::MakeParent });
+                        }
+                        if node_action_button(ui, "Make Parallel", &NodeAction::MakeParallel, (kind == NodeKind::Parallel).then_some("Already parallel.")) {
+                            commands.trigger(NodeActionTriggered { entity, action: NodeAction::MakeParallel });
+                            editor_state.context_menu_entity = None;
+                            editor_state.context_menu_position = None;
+                            ui.close();
+                        }
+                        if node_action_button(ui, "Make Parent", &NodeAction::MakeParent, (kind == NodeKind::Parent).then_some("Already a parent.")) {
+                            commands.trigger(NodeActionTriggered { entity, action: NodeAction::MakeParent });
+                            editor_state.context_menu_entity = None;
+                            editor_state.context_menu_position = None;
+                            ui.close();
+                        }
+                        let make_leaf_reason = if kind == NodeKind::Leaf {
+                            Some("Already a leaf.")
+                        } else if has_children {
+                            Some("Delete or move its children out first.")
+                        } else {
+                            None
+                        };
+                        if node_action_button(ui, "Make Leaf", &NodeAction::MakeLeaf, make_leaf_reason) {
+                            commands.trigger(NodeActionTriggered { entity, action: NodeAction::MakeLeaf });
+                            editor_state.context_menu_entity = None;
+                            editor_state.context_menu_position = None;
+                            ui.close();
+                        }
+                        let add_child_hover = if kind == NodeKind::Leaf {
+                            format!("{}\n⚠ This leaf becomes a parent.", NodeAction::AddChild.description())
+                        } else {
+                            NodeAction::AddChild.description().to_string()
+                        };
+                        if ui.button("Add child").on_hover_text(add_child_hover).clicked() {
+                            commands.trigger(NodeActionTriggered { entity, action: NodeAction::AddChild });
+                            editor_state.context_menu_entity = None;
+                            editor_state.context_menu_position = None;
+                            ui.close();
+                        }
+
+                        let breakpoint_label = if editor_state.breakpoints.contains(&entity) { "Remove breakpoint" } else { "🔴 Set breakpoint" };
+                        if ui.button(breakpoint_label).on_hover_text(NodeAction::ToggleBreakpoint.description()).clicked() {
+                            commands.trigger(NodeActionTriggered { entity, action: NodeAction::ToggleBreakpoint });
+                            editor_state.context_menu_entity = None;
+                            editor_state.context_menu_position = None;
+                            ui.close();
+                        }
+
+                        // Child of a parent: Add Sibling, stacking, Duplicate, Set as Initial State
+                        if let Ok(child_of) = q_child_of.get(entity) {
+                            if ui.button("Add Sibling").on_hover_text(NodeAction::AddSibling.description()).clicked() {
+                                commands.trigger(NodeActionTriggered { entity, action: NodeAction::AddSibling });
                                 editor_state.context_menu_entity = None;
                                 editor_state.context_menu_position = None;
                                 ui.close();
                             }
-                            if ui.button("Add child").clicked() {
-                                commands.trigger(NodeActionTriggered { entity, action: NodeAction::AddChild });
+
+                            ui.horizontal(|ui| {
+                                if ui.button("Bring to Front").on_hover_text(NodeAction::BringToFront.description()).clicked() {
+                                    commands.trigger(NodeActionTriggered { entity, action: NodeAction::BringToFront });
+                                    editor_state.context_menu_entity = None;
+                                    editor_state.context_menu_position = None;
+                                    ui.close();
+                                }
+                                if ui.button("Send to Back").on_hover_text(NodeAction::SendToBack.description()).clicked() {
+                                    commands.trigger(NodeActionTriggered { entity, action: NodeAction::SendToBack });
+                                    editor_state.context_menu_entity = None;
+                                    editor_state.context_menu_position = None;
+                                    ui.close();
+                                }
+                            });
+
+                            if ui.button("Duplicate").on_hover_text(NodeAction::Duplicate.description()).clicked() {
+                                commands.trigger(NodeActionTriggered { entity, action: NodeAction::Duplicate });
                                 editor_state.context_menu_entity = None;
                                 editor_state.context_menu_position = None;
                                 ui.close();
                             }
-                        }
 
-                        // Child of a parent: Set as Initial State
-                        if let Ok(child_of) = q_child_of.get(entity) {
                             let parent_has_initial = all_entities
                                 .get(child_of.0)
                                 .ok()
-                                .and_then(|(_,_,init)| init.map(|_| ()))
+                                .and_then(|(_, _, init, _)| init.map(|_| ()))
                                 .is_some();
                             if parent_has_initial {
-                                if ui.button("Set as Initial State").clicked() {
+                                if ui.button("Set as Initial State").on_hover_text(NodeAction::SetAsInitialState.description()).clicked() {
                                     commands.trigger(NodeActionTriggered { entity, action: NodeAction::SetAsInitialState });
                                     editor_state.context_menu_entity = None;
                                     editor_state.context_menu_position = None;
@@ -316,7 +642,25 @@ pub fn render_context_menu(
                             }
                         }
                         
-                        if ui.button("🗑 Delete Node").clicked() {
+                        // Custom node color: a row of swatches plus a reset button
+                        ui.horizontal(|ui| {
+                            ui.label("🎨");
+                            for color in NODE_COLOR_PALETTE {
+                                let (rect, response) = ui.allocate_exact_size(egui::vec2(16.0, 16.0), egui::Sense::click());
+                                ui.painter().rect_filled(rect, egui::CornerRadius::same(3), color);
+                                if response.hovered() {
+                                    ui.painter().rect_stroke(rect, egui::CornerRadius::same(3), egui::Stroke::new(1.5, ui.visuals().strong_text_color()), egui::StrokeKind::Outside);
+                                }
+                                if response.clicked() {
+                                    commands.trigger(SetNodeColor { entity, color: Some(color.to_srgba_unmultiplied()) });
+                                }
+                            }
+                            if ui.small_button("✖").on_hover_text("Reset to the default color").clicked() {
+                                commands.trigger(SetNodeColor { entity, color: None });
+                            }
+                        });
+
+                        if ui.button("🗑 Delete Node").on_hover_text(NodeAction::Delete.description()).clicked() {
                             commands.trigger(NodeActionTriggered {
                                 entity,
                                 action: NodeAction::Delete,
@@ -358,14 +702,55 @@ pub fn render_context_menu(
                     .show(ui, |ui| {
                         ui.set_min_width(120.0);
                         
-                        if ui.button("Inspect").clicked() {
+                        if ui.button("Inspect").on_hover_text("Open this transition's edge entity in the entity inspector.").clicked() {
                             editor_state.inspected_entity = Some(edge_entity);
                             editor_state.transition_context_menu = None;
                             editor_state.transition_context_menu_position = None;
                             ui.close();
                         }
                         
-                        if ui.button("🗑 Delete Transition").clicked() {
+                        if ui.button("Rename label").on_hover_text("Edit the text shown on this transition's pill (stored as an EdgeLabel).").clicked() {
+                            commands.trigger(RenameTransitionLabelRequested { edge_entity });
+                            editor_state.transition_context_menu = None;
+                            editor_state.transition_context_menu_position = None;
+                            ui.close();
+                        }
+                        
+                        // Edges sharing a source and event are evaluated in order
+                        if let Some((order, count)) = edge_order_badge {
+                            if ui.add_enabled(order > 1, egui::Button::new("⬆ Move up"))
+                                .on_hover_text("Evaluate this transition before the previous one with the same source and event.")
+                                .on_disabled_hover_text("Already evaluated first.")
+                                .clicked() {
+                                commands.trigger(ReorderTransition { edge_entity, up: true });
+                                editor_state.transition_context_menu = None;
+                                editor_state.transition_context_menu_position = None;
+                                ui.close();
+                            }
+                            if ui.add_enabled(order < count, egui::Button::new("⬇ Move down"))
+                                .on_hover_text("Evaluate this transition after the next one with the same source and event.")
+                                .on_disabled_hover_text("Already evaluated last.")
+                                .clicked() {
+                                commands.trigger(ReorderTransition { edge_entity, up: false });
+                                editor_state.transition_context_menu = None;
+                                editor_state.transition_context_menu_position = None;
+                                ui.close();
+                            }
+                        }
+                        
+                        let (toggle_label, toggle_hint) = if edge_disabled {
+                            ("Enable", "Put this transition back into its source's Transitions so it can fire again.")
+                        } else {
+                            ("Disable", "Keep this transition and its layout but stop it from firing (adds DisabledEdge).")
+                        };
+                        if ui.button(toggle_label).on_hover_text(toggle_hint).clicked() {
+                            commands.trigger(ToggleTransitionEnabled { edge_entity });
+                            editor_state.transition_context_menu = None;
+                            editor_state.transition_context_menu_position = None;
+                            ui.close();
+                        }
+                        
+                        if ui.button("🗑 Delete Transition").on_hover_text("Despawn this transition's edge entity.").clicked() {
                             commands.trigger(DeleteTransitionByEdge { edge_entity });
                             editor_state.transition_context_menu = None;
                             editor_state.transition_context_menu_position = None;
@@ -388,3 +773,51 @@ pub fn render_context_menu(
     }
 }
 
+/// Render the confirmation dialog for a pending subtree deletion
+pub fn render_delete_confirmation(
+    ctx: &egui::Context,
+    editor_state: &mut EditorState,
+    commands: &mut Commands,
+    all_entities: &EntityNamesQuery,
+) {
+    let Some(pending) = editor_state.pending_deletion else {
+        return;
+    };
+    // The node may have been removed some other way while the dialog was open
+    if !all_entities.contains(pending.entity) {
+        editor_state.pending_deletion = None;
+        return;
+    }
+    let name = entity_display_name(pending.entity, all_entities);
+
+    let mut confirmed = false;
+    let mut cancelled = ctx.input(|i| i.key_pressed(egui::Key::Escape));
+    egui::Window::new("Delete state?")
+        .id(egui::Id::new("delete_confirmation"))
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .collapsible(false)
+        .resizable(false)
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            let plural = if pending.descendant_count == 1 { "" } else { "s" };
+            ui.label(format!(
+                "Deleting '{}' also removes its {} descendant state{}.",
+                name, pending.descendant_count, plural
+            ));
+            ui.horizontal(|ui| {
+                if ui.button("🗑 Delete").clicked() {
+                    confirmed = true;
+                }
+                if ui.button("Cancel").clicked() {
+                    cancelled = true;
+                }
+            });
+        });
+
+    if confirmed {
+        commands.trigger(DeleteNode { entity: pending.entity });
+        editor_state.pending_deletion = None;
+    } else if cancelled {
+        editor_state.pending_deletion = None;
+    }
+}
@@ -0,0 +1,102 @@
+//! Custom node decorations
+//!
+//! This module handles:
+//! - Registering host-app decorators (`add_node_decorator`) that paint badges on nodes
+//! - Running them over every visible node after the canvas has been drawn
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext, PrimaryEguiContext};
+
+use crate::editor_state::{EditorState, EditorWindow, StateMachinePersistentData};
+use crate::window_management::CurrentEditorWindow;
+
+/// Paints on or around a node: `(state, world, painter, node rect)`
+///
+/// The painter works in the machine's canvas coordinates, the same space as the rect.
+pub type NodeDecorator = Box<dyn Fn(Entity, &World, &egui::Painter, egui::Rect) + Send + Sync>;
+
+/// Decorators registered with `add_node_decorator`, run in registration order
+#[derive(Resource, Default)]
+pub struct NodeDecorators {
+    decorators: Vec<NodeDecorator>,
+}
+
+impl NodeDecorators {
+    /// Add a decorator that runs after the base node drawing
+    pub fn add(&mut self, decorator: impl Fn(Entity, &World, &egui::Painter, egui::Rect) + Send + Sync + 'static) {
+        self.decorators.push(Box::new(decorator));
+    }
+
+    /// Whether no decorators are registered
+    pub fn is_empty(&self) -> bool {
+        self.decorators.is_empty()
+    }
+}
+
+/// App extension for decorating editor nodes
+pub trait NodeDecoratorAppExt {
+    /// Paint extra badges or outlines on every visible node, e.g. an icon for states with a timer
+    fn add_node_decorator(&mut self, decorator: impl Fn(Entity, &World, &egui::Painter, egui::Rect) + Send + Sync + 'static) -> &mut Self;
+}
+
+impl NodeDecoratorAppExt for App {
+    fn add_node_decorator(&mut self, decorator: impl Fn(Entity, &World, &egui::Painter, egui::Rect) + Send + Sync + 'static) -> &mut Self {
+        self.init_resource::<NodeDecorators>();
+        self.world_mut().resource_mut::<NodeDecorators>().add(decorator);
+        self
+    }
+}
+
+/// Exclusive system running the registered decorators over the current editor window's machines
+///
+/// Paints into each machine's canvas layer, so decorations pan, zoom and clip with the nodes.
+pub fn paint_node_decorations(world: &mut World) {
+    if world.get_resource::<NodeDecorators>().is_none_or(NodeDecorators::is_empty) {
+        return;
+    }
+    // Clone the egui Context to end the borrow before reading the world
+    let ctx_opt = {
+        let camera = world.resource::<CurrentEditorWindow>().camera;
+        let mut query = world.query_filtered::<&mut EguiContext, (With<EditorWindow>, Without<PrimaryEguiContext>)>();
+        query.get_mut(world, camera).ok().map(|mut egui_context| egui_context.get_mut().clone())
+    };
+    let Some(ctx) = ctx_opt else {
+        return;
+    };
+
+    let world: &World = world;
+    let editor_state = world.resource::<EditorState>();
+    let Some(canvas_rect) = editor_state.view.canvas_rect else {
+        return;
+    };
+    let decorators = world.resource::<NodeDecorators>();
+    for open_machine in &editor_state.view.open_machines {
+        let Some(persistent_data) = world.get::<StateMachinePersistentData>(open_machine.entity) else {
+            continue;
+        };
+        // Same layer the machine was rendered into: a sublayer of the CentralPanel (see `editor_ui_system`)
+        let transform = open_machine.layer_transform(canvas_rect.min);
+        let layer_id = egui::LayerId::new(egui::Order::Background, egui::Id::new("machine_canvas_layer").with(open_machine.entity));
+        let painter = egui::Painter::new(ctx.clone(), layer_id, transform.inverse() * canvas_rect);
+        for (&entity, node) in &persistent_data.nodes {
+            if is_hidden_by_collapse(world, persistent_data, entity) {
+                continue;
+            }
+            for decorator in &decorators.decorators {
+                decorator(entity, world, &painter, node.current_rect());
+            }
+        }
+    }
+}
+
+/// Whether any ancestor of `entity` is collapsed (so the node isn't drawn)
+fn is_hidden_by_collapse(world: &World, persistent_data: &StateMachinePersistentData, entity: Entity) -> bool {
+    let mut current = entity;
+    while let Some(child_of) = world.get::<bevy_gearbox::StateChildOf>(current) {
+        if persistent_data.collapsed.contains(&child_of.0) {
+            return true;
+        }
+        current = child_of.0;
+    }
+    false
+}
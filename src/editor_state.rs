@@ -19,6 +19,8 @@ pub struct TextEditingState {
     pub should_focus: bool,
     /// Whether this is the first focus (to trigger select all)
     pub first_focus: bool,
+    /// Whether the entity being edited is a transition edge whose `EdgeLabel` is being renamed
+    pub editing_edge_label: bool,
 }
 
 impl TextEditingState {
@@ -28,6 +30,13 @@ impl TextEditingState {
         self.current_text = current_name.to_string();
         self.should_focus = true;
         self.first_focus = true;
+        self.editing_edge_label = false;
+    }
+    
+    /// Start editing the display label of a transition edge
+    pub fn start_editing_edge_label(&mut self, edge_entity: Entity, current_label: &str) {
+        self.start_editing(edge_entity, current_label);
+        self.editing_edge_label = true;
     }
     
     /// Stop editing and return the final text if editing was active
@@ -38,6 +47,7 @@ impl TextEditingState {
             self.current_text.clear();
             self.should_focus = false;
             self.first_focus = false;
+            self.editing_edge_label = false;
             Some((entity, text))
         } else {
             None
@@ -50,6 +60,7 @@ impl TextEditingState {
         self.current_text.clear();
         self.should_focus = false;
         self.first_focus = false;
+        self.editing_edge_label = false;
     }
     
     /// Check if currently editing a specific entity
@@ -73,6 +84,16 @@ pub struct TransitionCreationState {
     pub show_event_dropdown: bool,
     /// Available event types for EventEdge
     pub available_event_types: Vec<String>,
+    /// Event types already used by edges in the source's machine, most used first (listed before the rest)
+    pub machine_event_types: Vec<String>,
+    /// Substring typed into the dropdown's filter box
+    pub event_filter: String,
+    /// Index of the event type picked by Enter, moved with the arrow keys
+    pub highlighted_event: usize,
+    /// Whether the filter box should grab keyboard focus on the next frame
+    pub event_filter_should_focus: bool,
+    /// Whether the new transition is internal (`EdgeKind::Internal`) rather than external
+    pub internal: bool,
 }
 
 impl TransitionConnection {
@@ -104,21 +125,81 @@ impl TransitionConnection {
     
     /// Update the event node position based on current source/target positions and stored offset
     pub fn update_event_node_position(&mut self) {
-        let midpoint = egui::Pos2::new(
+        let midpoint = self.event_node_anchor();
+        // Fan out sibling transitions only while the user hasn't placed this pill manually
+        let offset = if self.event_node_offset == egui::Vec2::ZERO {
+            self.sibling_fan_offset()
+        } else {
+            self.event_node_offset
+        };
+        self.event_node_position = midpoint + offset;
+    }
+    
+    /// Whether this transition loops back to its own source
+    pub fn is_self_transition(&self) -> bool {
+        self.source_entity == self.target_entity
+    }
+    
+    /// Reference point the event node offset is measured from
+    ///
+    /// This is the midpoint between source and target, or a point just outside the
+    /// top-right corner of the node for self-transitions.
+    pub fn event_node_anchor(&self) -> egui::Pos2 {
+        if self.is_self_transition() {
+            return self.source_rect.right_top() + egui::Vec2::new(30.0, -20.0);
+        }
+        egui::Pos2::new(
             (self.source_rect.center().x + self.target_rect.center().x) / 2.0,
             (self.source_rect.center().y + self.target_rect.center().y) / 2.0,
-        );
-        self.event_node_position = midpoint + self.event_node_offset;
+        )
+    }
+    
+    /// Offset along the perpendicular of the connection line that separates sibling pills
+    pub fn sibling_fan_offset(&self) -> egui::Vec2 {
+        if self.sibling_count <= 1 {
+            return egui::Vec2::ZERO;
+        }
+        let spacing = 28.0;
+        // Self-transitions have no direction; stack their pills down the right edge instead
+        if self.is_self_transition() {
+            return egui::Vec2::new(0.0, self.sibling_index as f32 * spacing);
+        }
+        // Use a canonical direction so transitions in both directions fan out consistently
+        let mut direction = self.target_rect.center() - self.source_rect.center();
+        if self.source_entity > self.target_entity {
+            direction = -direction;
+        }
+        if direction.length() < 1e-3 {
+            return egui::Vec2::ZERO;
+        }
+        let direction = direction.normalized();
+        let perpendicular = egui::Vec2::new(-direction.y, direction.x);
+        let centered_index = self.sibling_index as f32 - (self.sibling_count - 1) as f32 / 2.0;
+        perpendicular * centered_index * spacing
     }
     
     /// Update the offset based on current event node position relative to source/target midpoint
     pub fn update_event_node_offset(&mut self) {
-        let midpoint = egui::Pos2::new(
-            (self.source_rect.center().x + self.target_rect.center().x) / 2.0,
-            (self.source_rect.center().y + self.target_rect.center().y) / 2.0,
-        );
+        let midpoint = self.event_node_anchor();
         self.event_node_offset = self.event_node_position - midpoint;
     }
+
+    /// Move the event node onto the source→target line, keeping how far along it the node sits
+    ///
+    /// Only the along-line component of the offset is kept. Self-transitions have no line and are left alone.
+    pub fn snap_event_node_to_line(&mut self) {
+        if self.is_self_transition() {
+            return;
+        }
+        let direction = self.target_rect.center() - self.source_rect.center();
+        if direction.length() < 1e-3 {
+            return;
+        }
+        let direction = direction.normalized();
+        let along = (self.event_node_position - self.event_node_anchor()).dot(direction);
+        self.event_node_offset = direction * along;
+        self.update_event_node_position();
+    }
 }
 
 impl TransitionCreationState {
@@ -137,6 +218,9 @@ impl TransitionCreationState {
         self.awaiting_target_selection = false;
         self.show_event_dropdown = true;
         self.dropdown_position = Some(dropdown_pos);
+        self.event_filter.clear();
+        self.highlighted_event = 0;
+        self.event_filter_should_focus = true;
     }
     
     /// Cancel the current transition creation
@@ -144,6 +228,15 @@ impl TransitionCreationState {
         *self = Default::default();
     }
     
+    /// Cancel an active creation whose source or target is `gone`; returns whether it was cancelled
+    pub fn cancel_if_endpoint(&mut self, gone: impl Fn(Entity) -> bool) -> bool {
+        let stale = self.is_active() && self.source_entity.into_iter().chain(self.target_entity).any(gone);
+        if stale {
+            self.cancel();
+        }
+        stale
+    }
+    
     /// Complete the transition creation
     pub fn complete(&mut self) {
         *self = Default::default();
@@ -163,6 +256,57 @@ pub struct StateMachinePersistentData {
     pub nodes: HashMap<Entity, NodeType>,
     /// Visual transitions with custom layouts (draggable event nodes)
     pub visual_transitions: Vec<TransitionConnection>,
+    /// Parent nodes that are collapsed, hiding all of their descendants
+    pub collapsed: HashSet<Entity>,
+    /// Draw transitions as orthogonal elbow connectors instead of two segments through the event node
+    pub orthogonal_edges: bool,
+    /// User-chosen base colors for individual nodes
+    pub node_colors: HashMap<Entity, egui::Color32>,
+    /// Manual stacking among siblings (Bring to Front / Send to Back); higher draws on top
+    pub z_bias: HashMap<Entity, i32>,
+    /// Set when node positions or sizes may have changed; parents are only resized while dirty
+    pub layout_dirty: bool,
+    /// Node and transition counts at the last parent resize (a different count also triggers one)
+    pub sized_counts: (usize, usize),
+    /// State or edge last shown in the inspector for this machine, restored when it reopens
+    pub inspected_entity: Option<Entity>,
+}
+
+impl StateMachinePersistentData {
+    /// Request a parent size recalculation on the next update
+    pub fn mark_layout_dirty(&mut self) {
+        self.layout_dirty = true;
+    }
+
+    /// Top-left canvas position of a state's node, if it has one
+    pub fn node_position(&self, entity: Entity) -> Option<Pos2> {
+        self.nodes.get(&entity).map(|node| node.position())
+    }
+
+    /// Canvas rectangle of a state's node, if it has one
+    pub fn node_rect(&self, entity: Entity) -> Option<egui::Rect> {
+        self.nodes.get(&entity).map(|node| node.current_rect())
+    }
+
+    /// Every node as `(entity, top-left position, is_parent)`
+    pub fn iter_nodes(&self) -> impl Iterator<Item = (Entity, Pos2, bool)> + '_ {
+        self.nodes.iter().map(|(entity, node)| (*entity, node.position(), node.is_parent()))
+    }
+
+    /// The collapsed ancestor that stands in for `entity` on the canvas, if it is hidden
+    ///
+    /// Returns the outermost collapsed ancestor so nested collapses resolve to the visible node.
+    pub fn collapsed_proxy(&self, entity: Entity, q_child_of: &Query<&bevy_gearbox::StateChildOf>) -> Option<Entity> {
+        let mut proxy = None;
+        let mut current = entity;
+        while let Ok(child_of) = q_child_of.get(current) {
+            if self.collapsed.contains(&child_of.0) {
+                proxy = Some(child_of.0);
+            }
+            current = child_of.0;
+        }
+        proxy
+    }
 }
 
 /// Component that holds transient state machine editor data
@@ -171,6 +315,8 @@ pub struct StateMachinePersistentData {
 pub struct StateMachineTransientData {
     /// Currently selected node for z-ordering
     pub selected_node: Option<Entity>,
+    /// Descendants of collapsed parents as of the last render; not drawn and skipped by keyboard navigation
+    pub hidden_nodes: HashSet<Entity>,
     /// Transition creation state
     pub transition_creation: TransitionCreationState,
     /// Text editing state for renaming nodes
@@ -181,6 +327,55 @@ pub struct StateMachineTransientData {
     pub node_pulses: Vec<NodePulse>,
     /// Mapping from editor state entity -> NodeKind machine root entity (editor-internal)
     pub node_kind_roots: std::collections::HashMap<Entity, Entity>,
+    /// Unsnapped position of the node currently being dragged with grid snapping
+    pub drag_unsnapped_position: Option<(Entity, Pos2)>,
+    /// Position of the node currently being dragged when the drag started
+    pub drag_origin: Option<(Entity, Pos2)>,
+    /// Parent zone under the pointer while a single node is dragged; dropping reparents into it
+    pub reparent_target: Option<Entity>,
+    /// Issues found by the last `ValidateMachine` run, drawn as red outlines
+    pub validation_issues: std::collections::HashMap<Entity, Vec<ValidationIssue>>,
+    /// Most recent transitions fired in this machine, oldest first (capped at `TRANSITION_HISTORY_LEN`)
+    pub transition_history: std::collections::VecDeque<TransitionRecord>,
+    /// Transitions observed while the machine is paused, released one at a time by Step
+    pub paused_transitions: std::collections::VecDeque<TransitionRecord>,
+    /// Short names of the user-facing reflected components on each node's entity (badge on the node)
+    pub node_components: std::collections::HashMap<Entity, Vec<String>>,
+    /// Last parent layout of states that are currently leaves, restored if they become parents again
+    pub parent_layout_cache: std::collections::HashMap<Entity, crate::components::ParentNode>,
+}
+
+impl StateMachineTransientData {
+    /// Whether `entity` moves with the drag in progress (the grabbed node or a member of its multi-selection)
+    pub fn is_being_dragged(&self, entity: Entity, selected_nodes: &HashSet<Entity>) -> bool {
+        self.drag_origin.is_some_and(|(dragged, _)| {
+            dragged == entity || (selected_nodes.contains(&dragged) && selected_nodes.contains(&entity))
+        })
+    }
+
+    /// Node being dragged as a reparent candidate; hierarchy systems leave it unconstrained
+    pub fn reparent_drag(&self) -> Option<Entity> {
+        self.reparent_target.and(self.drag_origin).map(|(entity, _)| entity)
+    }
+}
+
+/// A problem found when validating a state machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// No initial state or transition can ever enter this state
+    Unreachable,
+    /// A (non-parallel) parent with children but no `InitialState`
+    MissingInitialState,
+}
+
+impl ValidationIssue {
+    /// Human readable description of the issue
+    pub fn description(&self) -> &'static str {
+        match self {
+            ValidationIssue::Unreachable => "state can never be entered",
+            ValidationIssue::MissingInitialState => "parent has children but no initial state",
+        }
+    }
 }
 
 /// Represents an open state machine on the canvas
@@ -192,16 +387,89 @@ pub struct OpenMachine {
     pub display_name: String,
     /// Canvas offset for positioning this machine
     pub canvas_offset: egui::Vec2,
+    /// Scale factor applied when rendering this machine (see `MIN_CANVAS_ZOOM`/`MAX_CANVAS_ZOOM`)
+    pub canvas_zoom: f32,
+}
+
+/// Smallest allowed canvas zoom
+pub const MIN_CANVAS_ZOOM: f32 = 0.25;
+/// Largest allowed canvas zoom
+pub const MAX_CANVAS_ZOOM: f32 = 3.0;
+/// How quickly "Follow active" pans toward the newest active state (higher is snappier, per second)
+pub const FOLLOW_ACTIVE_SPEED: f32 = 8.0;
+
+impl OpenMachine {
+    /// Pan and zoom used to draw this machine
+    pub fn canvas_transform(&self) -> CanvasTransform {
+        CanvasTransform {
+            offset: self.canvas_offset,
+            zoom: self.canvas_zoom,
+        }
+    }
+    
+    /// Layer transform mapping this machine's stored node positions to the screen
+    pub fn layer_transform(&self, canvas_origin: Pos2) -> egui::emath::TSTransform {
+        self.canvas_transform().layer_transform(canvas_origin)
+    }
+}
+
+/// Maps stored node coordinates to the screen: pan by `offset`, then scale by `zoom` about the canvas origin
+/// 
+/// Applied only when drawing and hit-testing; stored node positions never include it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CanvasTransform {
+    /// Pan applied before zooming, in node coordinates
+    pub offset: egui::Vec2,
+    /// Scale factor about the canvas origin
+    pub zoom: f32,
+}
+
+impl Default for CanvasTransform {
+    fn default() -> Self {
+        Self {
+            offset: egui::Vec2::ZERO,
+            zoom: 1.0,
+        }
+    }
+}
+
+impl CanvasTransform {
+    /// Equivalent egui layer transform (`origin + zoom * (pos + offset - origin)`)
+    pub fn layer_transform(&self, canvas_origin: Pos2) -> egui::emath::TSTransform {
+        egui::emath::TSTransform::new(canvas_origin.to_vec2() * (1.0 - self.zoom) + self.offset * self.zoom, self.zoom)
+    }
+    
+    /// Screen position of a point in node coordinates
+    pub fn to_screen(&self, pos: Pos2, canvas_origin: Pos2) -> Pos2 {
+        self.layer_transform(canvas_origin) * pos
+    }
+    
+    /// Screen rectangle of a rectangle in node coordinates
+    pub fn rect_to_screen(&self, rect: egui::Rect, canvas_origin: Pos2) -> egui::Rect {
+        self.layer_transform(canvas_origin) * rect
+    }
+    
+    /// Node-coordinate position of a screen point
+    pub fn from_screen(&self, pos: Pos2, canvas_origin: Pos2) -> Pos2 {
+        self.layer_transform(canvas_origin).inverse() * pos
+    }
+}
+
+/// A node deletion awaiting confirmation because it would remove a subtree
+#[derive(Debug, Clone, Copy)]
+pub struct PendingDeletion {
+    /// Node to delete
+    pub entity: Entity,
+    /// Number of descendants that will be removed along with it
+    pub descendant_count: usize,
 }
 
 /// Resource that holds the editor's UI/window state
 /// This manages multiple state machines open on the same canvas
 #[derive(Resource, Default)]
 pub struct EditorState {
-    /// Multiple open machines on the canvas
-    pub open_machines: Vec<OpenMachine>,
-    /// Currently selected entity in the editor (None clears selection)
-    pub selected_entity: Option<Entity>,
+    /// View of the active editor window: open machines, selection and pending canvas requests
+    pub view: WindowView,
     /// Entity for which a context menu is requested
     pub context_menu_entity: Option<Entity>,
     /// Position where the context menu should appear
@@ -220,17 +488,23 @@ pub struct EditorState {
     pub inspected_entity: Option<Entity>,
     /// Current inspector tab
     pub inspector_tab: InspectorTab,
+    /// Whether the inspectors float or are docked to a side of the canvas
+    pub inspector_layout: InspectorLayout,
     /// Component addition UI state
     pub component_addition: ComponentAdditionState,
-    /// Tracks relationships between origin entities and their related entities
-    /// Key: origin entity, Value: list of related entities
-    pub related_entities: std::collections::HashMap<Entity, Vec<Entity>>,
-    /// Canvas (CentralPanel) top-left origin in screen coordinates for coordinate conversion
-    pub canvas_origin: Option<Pos2>,
+    /// Whether a text field had keyboard focus before this frame's widgets ran
+    ///
+    /// A single-line `TextEdit` drops focus on Enter without consuming the key, so hotkeys
+    /// checked after the UI must use this rather than `wants_keyboard_input()`.
+    pub text_focus_at_frame_start: bool,
     /// Desired top-left positions for newly opened machines (applied on scaffold ready)
     pub desired_open_positions: std::collections::HashMap<Entity, Pos2>,
     /// Whether the world inspector window should be visible
     pub show_world_inspector: bool,
+    /// Last on-screen rectangle of the World Inspector window, saved with the layout
+    pub world_inspector_rect: Option<egui::Rect>,
+    /// Whether the World Inspector only lists the selected machine's states and edges
+    pub scope_inspector_to_machine: bool,
     /// Whether the top Open menu is visible
     pub show_open_menu: bool,
     /// Screen position for the top Open menu popup
@@ -241,10 +515,56 @@ pub struct EditorState {
     pub machine_search_text: String,
     /// One-shot: focus the search field when opening the Open menu
     pub machine_search_should_focus: bool,
+    /// Search text for jumping to a node by name
+    pub node_search_text: String,
+    /// States that pause their machine's feedback and get selected and centered when entered
+    pub breakpoints: HashSet<Entity>,
+    /// Whether the canvas pans to keep the most recently entered leaf state centered
+    pub follow_active: bool,
+    /// Whether the onboarding overlay is currently visible
+    pub show_onboarding: bool,
+    /// Whether the onboarding overlay has been dismissed (persisted in the layout sidecar)
+    pub onboarding_seen: bool,
+    /// Grid size to snap dragged nodes to (None disables snapping)
+    pub grid_snap: Option<f32>,
+    /// Whether dropped transition pills snap back onto their connection line (hold Alt to place freely)
+    pub snap_pills_to_line: bool,
+    /// Whether to render the snapping grid as faint dots behind the nodes
+    pub show_grid: bool,
+    /// Whether the transition timeline window is visible
+    pub show_transition_timeline: bool,
+    /// Whether the "Send event" window is visible
+    pub show_send_event_panel: bool,
+    /// Machine picked as the target in the "Send event" window
+    pub send_event_machine: Option<Entity>,
+    /// Event type hovered in the "Send event" window; edges listening for it are highlighted
+    pub send_event_preview: Option<String>,
+    /// Whether to show each node's `Entity` id next to its name
+    pub show_entity_ids: bool,
+    /// Denser nodes and pills: smaller padding and fonts (persisted in the layout sidecar)
+    pub compact: bool,
+    /// Whether initial states get the circle-and-arrow indicator (persisted in the layout sidecar)
+    pub show_initial_indicators: bool,
+    /// Whether `ViewRelated` opens related machines immediately (otherwise a chip near the origin offers to)
+    pub auto_open_related: bool,
+    /// How parents react to children dragged past their edges
+    pub contain_mode: ContainMode,
+    /// Whether overlapping sibling parent zones are pushed apart after sizing
+    pub separate_sibling_zones: bool,
+    /// Path of the layout sidecar this state is saved to and restored from
+    pub layout_path: std::path::PathBuf,
+    /// Machines from the loaded layout, re-opened when the editor window opens
+    pub pending_layout_machines: Vec<crate::layout::LayoutOpenMachine>,
+    /// Undo/redo stacks for node moves and deletions
+    pub history: crate::history::EditorHistory,
+    /// Deletion of a node with children, waiting for the user to confirm
+    pub pending_deletion: Option<PendingDeletion>,
+    /// Subtree copied with Ctrl+C, pasted with Ctrl+V
+    pub clipboard: Option<crate::clipboard::SubtreeClipboard>,
 }
 
 /// Inspector tabs
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum InspectorTab {
     Inspect,
     Remove,
@@ -257,6 +577,29 @@ impl Default for InspectorTab {
     }
 }
 
+/// Where the entity and world inspectors are shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum InspectorLayout {
+    /// Movable windows over the canvas
+    #[default]
+    Floating,
+    /// Side panel on the right; the canvas shrinks to make room
+    DockedRight,
+    /// Side panel on the left; the canvas shrinks to make room
+    DockedLeft,
+}
+
+impl InspectorLayout {
+    /// Label shown in the top bar picker
+    pub fn label(&self) -> &'static str {
+        match self {
+            InspectorLayout::Floating => "Floating",
+            InspectorLayout::DockedRight => "Docked right",
+            InspectorLayout::DockedLeft => "Docked left",
+        }
+    }
+}
+
 /// State for the component addition UI
 #[derive(Debug, Default)]
 pub struct ComponentAdditionState {
@@ -291,17 +634,18 @@ impl ComponentAdditionState {
 
 impl EditorState {
     /// Add a new machine to the canvas
-    pub fn add_machine(&mut self, entity: Entity, display_name: String) {
+    pub fn add_machine(&mut self, entity: Entity, display_name: String, config: &EditorConfig) {
         // Calculate position to avoid overlaps
-        let canvas_offset = self.calculate_next_machine_position();
+        let canvas_offset = self.calculate_next_machine_position(config);
         
         let open_machine = OpenMachine {
             entity,
             display_name,
             canvas_offset,
+            canvas_zoom: self.current_canvas_zoom(),
         };
         
-        self.open_machines.push(open_machine);
+        self.view.open_machines.push(open_machine);
     }
     
     /// Add a new machine to the canvas with a specific offset
@@ -310,61 +654,121 @@ impl EditorState {
             entity,
             display_name,
             canvas_offset,
+            canvas_zoom: self.current_canvas_zoom(),
         };
         
-        self.open_machines.push(open_machine);
+        self.view.open_machines.push(open_machine);
     }
     
-    /// Remove a machine from the canvas and all its related entities
+    /// Open `target` next to its `origin` machine and track the relationship for cleanup
+    pub fn open_related_machine(&mut self, origin: Entity, target: Entity, display_name: String, config: &EditorConfig) {
+        // Offset the related machine slightly to the right and down from the origin
+        let origin_offset = self.view.open_machines.iter()
+            .find(|m| m.entity == origin)
+            .map(|m| m.canvas_offset)
+            .unwrap_or(egui::Vec2::ZERO);
+        self.add_machine_with_offset(target, display_name, origin_offset + config.related_machine_offset);
+        self.track_related(origin, target);
+    }
+
+    /// Record that `target` is related to `origin`
+    pub fn track_related(&mut self, origin: Entity, target: Entity) {
+        let related = self.view.related_entities.entry(origin).or_insert_with(Vec::new);
+        if !related.contains(&target) {
+            related.push(target);
+        }
+    }
+
+    /// Remove a machine from the canvas and, recursively, the related machines it opened
+    ///
+    /// Related machines the user interacted with stay open. Each machine is visited once,
+    /// so cyclic relationships terminate.
     pub fn remove_machine(&mut self, entity: Entity) {
-        // First, collect all related entities that should be removed
-        let mut entities_to_remove = vec![entity];
-        
-        if let Some(related) = self.related_entities.get(&entity) {
-            entities_to_remove.extend(related.iter().copied());
+        let mut to_remove = vec![entity];
+        let mut visited = std::collections::HashSet::new();
+        while let Some(current) = to_remove.pop() {
+            if !visited.insert(current) {
+                continue;
+            }
+            self.view.open_machines.retain(|machine| machine.entity != current);
+            self.view.interacted_related.remove(&current);
+            
+            // Cascade into this machine's related entities and clear its tracking entry
+            if let Some(related) = self.view.related_entities.remove(&current) {
+                to_remove.extend(related.into_iter().filter(|related| !self.view.interacted_related.contains(related)));
+            }
+            
+            // Also remove this entity from being a related entity of others
+            for related_list in self.view.related_entities.values_mut() {
+                related_list.retain(|&related_entity| related_entity != current);
+            }
         }
         
-        // Remove all entities (origin and related) from the canvas
-        for entity_to_remove in &entities_to_remove {
-            self.open_machines.retain(|machine| machine.entity != *entity_to_remove);
+        // Remove empty relationship entries; machines left open are no longer related to anything
+        self.view.related_entities.retain(|_, related_list| !related_list.is_empty());
+        let related_entities = &self.view.related_entities;
+        self.view.interacted_related.retain(|entity| related_entities.values().any(|related| related.contains(entity)));
+    }
+    
+    /// Pan a machine so that a point in its (un-offset) node coordinates lands on `viewport_center`
+    pub fn center_machine_on(&mut self, machine: Entity, point: Pos2, viewport_center: Pos2) {
+        self.ease_machine_toward(machine, point, viewport_center, 1.0);
+    }
+
+    /// Move a machine's offset a fraction `t` of the way toward centering `point` on `viewport_center`
+    ///
+    /// Returns true once the remaining distance is under half a point (the offset is then snapped).
+    pub fn ease_machine_toward(&mut self, machine: Entity, point: Pos2, viewport_center: Pos2, t: f32) -> bool {
+        let origin = self.view.canvas_origin.unwrap_or(Pos2::ZERO);
+        let Some(open_machine) = self.view.open_machines.iter_mut().find(|m| m.entity == machine) else {
+            return true;
+        };
+        let centered = (viewport_center - origin) / open_machine.canvas_zoom + (origin - point);
+        let remaining = centered - open_machine.canvas_offset;
+        if remaining.length() < 0.5 {
+            open_machine.canvas_offset = centered;
+            return true;
         }
-        
-        // Clean up the relationship tracking
-        self.related_entities.remove(&entity);
-        
-        // Also remove this entity from being a related entity of others
-        for (_, related_list) in self.related_entities.iter_mut() {
-            related_list.retain(|&related_entity| related_entity != entity);
+        open_machine.canvas_offset += remaining * t.clamp(0.0, 1.0);
+        false
+    }
+    
+    /// Zoom of the canvas, taken from the first open machine (1.0 when nothing is open)
+    pub fn current_canvas_zoom(&self) -> f32 {
+        self.view.open_machines.first().map(|m| m.canvas_zoom).unwrap_or(1.0)
+    }
+    
+    /// Zoom every open machine by `factor`, keeping the point under `screen_pos` fixed
+    pub fn zoom_canvas_at(&mut self, factor: f32, screen_pos: Pos2, canvas_origin: Pos2) {
+        for open_machine in &mut self.view.open_machines {
+            let old_zoom = open_machine.canvas_zoom;
+            let new_zoom = (old_zoom * factor).clamp(MIN_CANVAS_ZOOM, MAX_CANVAS_ZOOM);
+            open_machine.canvas_offset += (screen_pos - canvas_origin) * (1.0 / new_zoom - 1.0 / old_zoom);
+            open_machine.canvas_zoom = new_zoom;
         }
-        
-        // Remove empty relationship entries
-        self.related_entities.retain(|_, related_list| !related_list.is_empty());
     }
     
     /// Check if a machine is currently open
     pub fn is_machine_open(&self, entity: Entity) -> bool {
-        self.open_machines.iter().any(|machine| machine.entity == entity)
+        self.view.open_machines.iter().any(|machine| machine.entity == entity)
     }
     
     /// Get all open machine entities
     pub fn get_open_machine_entities(&self) -> Vec<Entity> {
-        self.open_machines.iter().map(|machine| machine.entity).collect()
+        self.view.open_machines.iter().map(|machine| machine.entity).collect()
     }
     
     /// Calculate the next position for a new machine to avoid overlaps
-    fn calculate_next_machine_position(&self) -> egui::Vec2 {
+    fn calculate_next_machine_position(&self, config: &EditorConfig) -> egui::Vec2 {
         // Simple grid-based positioning
-        let machines_per_row = 2;
-        let machine_spacing = egui::Vec2::new(600.0, 400.0);
-        let start_offset = egui::Vec2::new(50.0, 50.0);
-        
-        let machine_count = self.open_machines.len();
+        let machines_per_row = config.machines_per_row.max(1);
+        let machine_count = self.view.open_machines.len();
         let row = machine_count / machines_per_row;
         let col = machine_count % machines_per_row;
         
-        start_offset + egui::Vec2::new(
-            col as f32 * machine_spacing.x,
-            row as f32 * machine_spacing.y,
+        config.machine_start_offset + egui::Vec2::new(
+            col as f32 * config.machine_spacing.x,
+            row as f32 * config.machine_spacing.y,
         )
     }
 }
@@ -373,6 +777,52 @@ impl EditorState {
 #[derive(Component)]
 pub struct EditorWindow;
 
+/// The camera carrying an editor window's egui context, stored on the window entity
+#[derive(Component, Clone, Copy, Debug)]
+pub struct EditorWindowCamera(pub Entity);
+
+/// View state owned by one editor window (open machines, selection, pending canvas requests)
+#[derive(Default)]
+pub struct WindowView {
+    /// Multiple open machines on the canvas
+    pub open_machines: Vec<OpenMachine>,
+    /// Currently selected entity in the editor (None clears selection)
+    pub selected_entity: Option<Entity>,
+    /// Multi-selection across this window's machines; dragging any member moves the whole set
+    pub selected_nodes: HashSet<Entity>,
+    /// Tracks relationships between origin entities and their related entities
+    /// Key: origin entity, Value: list of related entities
+    pub related_entities: std::collections::HashMap<Entity, Vec<Entity>>,
+    /// Related machines the user has interacted with; these stay open when their origin closes
+    pub interacted_related: std::collections::HashSet<Entity>,
+    /// Canvas (CentralPanel) top-left origin in screen coordinates for coordinate conversion
+    pub canvas_origin: Option<Pos2>,
+    /// Canvas (CentralPanel) rectangle in screen coordinates, as of the last frame drawn
+    pub canvas_rect: Option<egui::Rect>,
+    /// One-shot: (machine, node) to center on the canvas once the machine's nodes exist
+    pub pending_node_focus: Option<(Entity, Entity)>,
+    /// (machine, leaf) the canvas is easing toward while following active states
+    pub follow_target: Option<(Entity, Entity)>,
+    /// One-shot: frame all open machines in the canvas next frame
+    pub fit_to_view_requested: bool,
+    /// Rubber-band selection in progress (`min` is the drag start, `max` the pointer)
+    pub selection_box: Option<egui::Rect>,
+}
+
+/// Stashed view of an editor window that is not the active one
+///
+/// `EditorState::view` holds the view of the active (most recently focused) editor window; every
+/// other window keeps its view here and has it swapped into `EditorState` for its egui pass.
+#[derive(Component, Default)]
+pub struct EditorWindowView(pub WindowView);
+
+impl EditorWindowView {
+    /// Exchange this view with the one currently in `editor_state`
+    pub fn swap_with(&mut self, editor_state: &mut EditorState) {
+        std::mem::swap(&mut self.0, &mut editor_state.view);
+    }
+}
+
 /// Event fired when a context menu is requested for a node
 #[derive(Event)]
 pub struct NodeContextMenuRequested {
@@ -380,6 +830,49 @@ pub struct NodeContextMenuRequested {
     pub position: Pos2,
 }
 
+/// Marker on a machine root whose transition feedback is paused in the editor
+///
+/// Not reflected, so it is never saved with the machine.
+#[derive(Component, Debug, Default)]
+pub struct EditorPaused;
+
+/// Event: pause (or resume) a machine's transition feedback
+#[derive(Event)]
+pub struct TogglePauseMachine {
+    pub entity: Entity,
+}
+
+/// Event: release one buffered transition of a paused machine
+#[derive(Event)]
+pub struct StepMachine {
+    pub entity: Entity,
+}
+
+/// Human-readable label for a transition edge, shown instead of its `Name` (the event type)
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component, Default)]
+pub struct EdgeLabel(pub String);
+
+/// Marks a transition edge switched off in the editor
+///
+/// The edge's `Source` is removed while disabled, so its source's `Transitions` (which gearbox
+/// evaluates) no longer lists it; the source and the edge's place in that list are kept here so
+/// re-enabling restores its priority.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component)]
+pub struct DisabledEdge {
+    #[entities]
+    pub source: Entity,
+    /// Position the edge had in its source's `Transitions`
+    #[reflect(default)]
+    pub index: usize,
+}
+
+/// Evaluation order of an edge among edges sharing its source and event (lower goes first)
+#[derive(Component, Reflect, Debug, Clone, Copy, Default)]
+#[reflect(Component, Default)]
+pub struct EdgeOrder(pub u32);
+
 /// Event fired when a context menu is requested for a transition
 #[derive(Event)]
 pub struct TransitionContextMenuRequested {
@@ -395,6 +888,9 @@ pub struct TransitionContextMenuRequested {
 pub enum NodeAction {
     Inspect,
     AddChild,
+    AddSibling,
+    Duplicate,
+    SelectSubtree,
     Rename,
     SetAsInitialState,
     MakeParallel,
@@ -402,6 +898,32 @@ pub enum NodeAction {
     MakeLeaf,
     Delete,
     ResetRegion,
+    ToggleBreakpoint,
+    BringToFront,
+    SendToBack,
+}
+
+impl NodeAction {
+    /// Hover text for the context menu entry, including any preconditions
+    pub fn description(&self) -> &'static str {
+        match self {
+            NodeAction::Inspect => "Open this state in the entity inspector to view and edit its components.",
+            NodeAction::AddChild => "Add a new leaf state inside this one.",
+            NodeAction::AddSibling => "Add a new leaf state below this one under the same parent and rename it. Shortcut: Enter.",
+            NodeAction::Duplicate => "Add a copy of this state next to it under the same parent. Children and transitions are not copied.",
+            NodeAction::SelectSubtree => "Select this state and all of its descendants so they can be dragged together.",
+            NodeAction::Rename => "Edit this state's name in place.",
+            NodeAction::SetAsInitialState => "Make this the state its parent enters first.",
+            NodeAction::MakeParallel => "Turn this state into a parallel region: all of its children are active at once. A leaf gets a first child.",
+            NodeAction::MakeParent => "Turn this state into a compound state that enters one child at a time. A leaf gets a first child.",
+            NodeAction::MakeLeaf => "Turn this state into a leaf. Only possible once it has no children.",
+            NodeAction::Delete => "Delete this state and its subtree (asks first if it has children). Transitions into it are removed.",
+            NodeAction::ResetRegion => "Exit every active state inside this region and re-enter it from its initial state. On a machine root, resets the whole machine.",
+            NodeAction::ToggleBreakpoint => "When this state is entered, pause the machine's feedback and select and center the state.",
+            NodeAction::BringToFront => "Draw this state (and its children) above overlapping siblings.",
+            NodeAction::SendToBack => "Draw this state (and its children) below overlapping siblings.",
+        }
+    }
 }
 
 /// Event fired when a node action is triggered
@@ -411,6 +933,13 @@ pub struct NodeActionTriggered {
     pub action: NodeAction,
 }
 
+/// Event fired to add a new leaf child to a state at a given node-space position
+#[derive(Event)]
+pub struct AddChildAt {
+    pub parent: Entity,
+    pub position: Pos2,
+}
+
 /// Event fired when a node is dragged
 #[derive(Event, Debug)]
 pub struct NodeDragged {
@@ -418,6 +947,20 @@ pub struct NodeDragged {
     pub drag_delta: egui::Vec2,
 }
 
+/// Event fired when a node drag finishes, carrying the total movement for undo
+#[derive(Event, Debug)]
+pub struct NodeMoved {
+    pub entity: Entity,
+    pub delta: egui::Vec2,
+}
+
+/// Event fired when a dragged node is dropped into a different parent zone
+#[derive(Event, Debug)]
+pub struct ReparentNode {
+    pub entity: Entity,
+    pub new_parent: Entity,
+}
+
 /// Event fired when a transition creation is requested (+ button clicked)
 #[derive(Event)]
 pub struct TransitionCreationRequested {
@@ -430,6 +973,8 @@ pub struct CreateTransition {
     pub source_entity: Entity,
     pub target_entity: Entity,
     pub event_type: String,
+    /// Create an `EdgeKind::Internal` edge (the source is not exited and re-entered)
+    pub internal: bool,
 }
 
 /// Event fired when a state machine should be saved
@@ -438,6 +983,57 @@ pub struct SaveStateMachine {
     pub entity: Entity,
 }
 
+/// Event fired when a state machine should be checked for unreachable states
+#[derive(Event)]
+pub struct ValidateMachine {
+    pub entity: Entity,
+}
+
+/// Event fired when a state machine's diagram should be written to an SVG file
+#[derive(Event)]
+pub struct ExportMachineSvg {
+    pub entity: Entity,
+    pub path: std::path::PathBuf,
+}
+
+/// Event fired when a state machine should be converted to Graphviz DOT (copied to the clipboard)
+#[derive(Event)]
+pub struct ExportMachineDot {
+    pub entity: Entity,
+}
+
+/// Event fired when a state machine's logical structure should be written to a JSON file
+#[derive(Event)]
+pub struct ExportMachineJson {
+    pub entity: Entity,
+    pub path: std::path::PathBuf,
+}
+
+/// Event fired when a machine should switch between elbow and two-segment transition routing
+#[derive(Event)]
+pub struct ToggleOrthogonalEdges {
+    pub entity: Entity,
+}
+
+/// Event fired when a machine's multi-selection should be aligned or distributed
+#[derive(Event)]
+pub struct AlignSelection {
+    pub machine: Entity,
+    pub mode: crate::alignment::AlignMode,
+}
+
+/// Event fired when a machine's nodes should be arranged automatically along its hierarchy
+#[derive(Event)]
+pub struct AutoLayoutMachine {
+    pub entity: Entity,
+}
+
+/// Event fired when a state machine should be loaded from a scene file and opened
+#[derive(Event)]
+pub struct LoadStateMachine {
+    pub path: std::path::PathBuf,
+}
+
 /// Event fired when a transition should be deleted
 #[derive(Event)]
 pub struct DeleteTransition {
@@ -446,6 +1042,32 @@ pub struct DeleteTransition {
     pub event_type: String,
 }
 
+/// Event fired when a transition's display label should be edited inline
+#[derive(Event)]
+pub struct RenameTransitionLabelRequested {
+    pub edge_entity: Entity,
+}
+
+/// Event fired when an existing transition should point at a different target state
+#[derive(Event)]
+pub struct RetargetTransition {
+    pub edge_entity: Entity,
+    pub new_target: Entity,
+}
+
+/// Event fired when a transition should be disabled (or re-enabled if it already is)
+#[derive(Event)]
+pub struct ToggleTransitionEnabled {
+    pub edge_entity: Entity,
+}
+
+/// Event fired when an edge should move earlier (`up`) or later among edges sharing its source and event
+#[derive(Event)]
+pub struct ReorderTransition {
+    pub edge_entity: Entity,
+    pub up: bool,
+}
+
 /// Event fired when a transition should be deleted by its edge entity
 #[derive(Event)]
 pub struct DeleteTransitionByEdge {
@@ -458,6 +1080,13 @@ pub struct DeleteNode {
     pub entity: Entity,
 }
 
+/// Event: set (or clear with `None`) a node's custom base color
+#[derive(Event)]
+pub struct SetNodeColor {
+    pub entity: Entity,
+    pub color: Option<[u8; 4]>,
+}
+
 /// Event: request to set a child's parent InitialState to this child
 #[derive(Event)]
 pub struct SetInitialStateRequested {
@@ -475,6 +1104,8 @@ pub struct BackgroundContextMenuRequested {
 pub struct OpenMachineRequested {
     pub entity: Entity,
     pub position: Option<Pos2>,
+    /// State inside the machine to select and center once its nodes exist
+    pub focus_child: Option<Entity>,
 }
 
 /// Event: change selection in the editor (None to clear)
@@ -508,7 +1139,8 @@ pub struct MachineReadyForEditing {
 }
 
 /// Event fired when an entity wants to show a related entity in the editor
-/// If the origin entity is currently being viewed, the target entity will be automatically loaded
+/// If the origin entity is currently being viewed, the target entity will be loaded
+/// (or offered as a chip next to the origin when `EditorState::auto_open_related` is off)
 #[derive(Event)]
 pub struct ViewRelated {
     /// The origin entity (e.g., firebreath mantra ability)
@@ -517,6 +1149,32 @@ pub struct ViewRelated {
     pub target: Entity,
 }
 
+/// Event fired when a state inside a machine open in the editor is entered
+///
+/// Mirrors gearbox's `EnterState`, scoped to what the editor is showing; observe it to
+/// play a sound or log while debugging.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct EditorStateEntered {
+    /// Root of the open machine
+    pub machine: Entity,
+    /// The entered state
+    pub state: Entity,
+}
+
+/// Maximum number of transitions kept per machine for the timeline
+pub const TRANSITION_HISTORY_LEN: usize = 200;
+
+/// A transition observed firing, listed in the timeline
+#[derive(Debug, Clone)]
+pub struct TransitionRecord {
+    /// Seconds since startup when the transition fired
+    pub time: f32,
+    pub source: Entity,
+    pub target: Entity,
+    pub edge: Entity,
+    pub event_type: String,
+}
+
 /// Data to track transition pulse animation
 #[derive(Clone)]
 pub struct TransitionPulse {
@@ -524,15 +1182,21 @@ pub struct TransitionPulse {
     pub target_entity: Entity,
     pub edge_entity: Entity,
     pub timer: Timer,
+    /// Distance the marching-ants dashes have travelled along the line
+    pub phase: f32,
 }
 
+/// Speed of the marching-ants dashes along a firing transition (pixels per second)
+pub const MARCHING_ANTS_SPEED: f32 = 120.0;
+
 impl TransitionPulse {
-    pub fn new(source_entity: Entity, target_entity: Entity, edge_entity: Entity) -> Self {
+    pub fn new(source_entity: Entity, target_entity: Entity, edge_entity: Entity, seconds: f32) -> Self {
         Self {
             source_entity,
             target_entity,
             edge_entity,
-            timer: Timer::from_seconds(0.4, TimerMode::Once),
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+            phase: 0.0,
         }
     }
     
@@ -542,18 +1206,233 @@ impl TransitionPulse {
     }
 }
 
-/// Colors for visual feedback
+/// How a parent keeps its children inside
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContainMode {
+    /// Parents keep their size while a child is dragged; the child stops at the edge
+    Clamp,
+    /// Parents grow right and down to fit their children up to `MAX_PARENT_SIZE`, then clamp
+    #[default]
+    Grow,
+}
+
+/// Largest size a parent grows to in `ContainMode::Grow`
+pub const MAX_PARENT_SIZE: egui::Vec2 = egui::vec2(4000.0, 3000.0);
+
+/// Grid size used when snapping is enabled from the toolbar
+pub const DEFAULT_GRID_SIZE: f32 = 20.0;
+
+/// Round a position to the nearest multiple of the grid size
+pub fn snap_to_grid(position: Pos2, grid_size: f32) -> Pos2 {
+    Pos2::new(
+        (position.x / grid_size).round() * grid_size,
+        (position.y / grid_size).round() * grid_size,
+    )
+}
+
+/// Colors for visual feedback (the dark theme's defaults; rendering reads `EditorTheme`)
 pub const ACTIVE_STATE_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 215, 0); // Gold
 pub const BRIGHT_ACTIVE_STATE_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 245, 120); // Brighter gold
 pub const NORMAL_NODE_COLOR: egui::Color32 = egui::Color32::from_rgb(60, 60, 60); // Dark grey
 pub const TRANSITION_COLOR: egui::Color32 = egui::Color32::WHITE;
+pub const DISABLED_TRANSITION_COLOR: egui::Color32 = egui::Color32::from_rgb(110, 110, 110); // Grey
+pub const TRANSITION_SOURCE_COLOR: egui::Color32 = egui::Color32::from_rgb(100, 150, 255); // Selection blue
+pub const TARGET_CANDIDATE_COLOR: egui::Color32 = egui::Color32::from_rgb(80, 200, 120); // Dim green
+
+/// Palette used to draw nodes, transitions and the canvas
+///
+/// Insert it as a resource to restyle the editor; `EditorTheme::dark()` and `EditorTheme::light()`
+/// are built in and can be tweaked field by field. The default follows egui's visuals.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct EditorTheme {
+    /// Idle node fill (leaves and parent title bars)
+    pub node_fill: egui::Color32,
+    /// Body of a parent node
+    pub zone_fill: egui::Color32,
+    /// Body of a selected parent node
+    pub zone_selected_fill: egui::Color32,
+    /// Node border when not selected
+    pub node_border: egui::Color32,
+    /// Fill of active states
+    pub active: egui::Color32,
+    /// Flash for recently entered states and fired transitions
+    pub bright_active: egui::Color32,
+    /// Idle transition lines
+    pub transition: egui::Color32,
+    /// Disabled transition lines and pills
+    pub disabled_transition: egui::Color32,
+    /// Text drawn on dark backgrounds
+    pub text_light: egui::Color32,
+    /// Text drawn on light backgrounds
+    pub text_dark: egui::Color32,
+    /// Backgrounds at or below this luminance get `text_light`
+    pub min_luminance_color: egui::Color32,
+    /// Backgrounds at or above this luminance get `text_dark`
+    pub max_luminance_color: egui::Color32,
+    /// Grid dots
+    pub grid: egui::Color32,
+    /// Derive the base node, text, line and grid colors from egui's current visuals each frame
+    ///
+    /// Highlight colors (active, flash, disabled) are kept from this palette.
+    pub follow_egui_visuals: bool,
+}
+
+impl Default for EditorTheme {
+    fn default() -> Self {
+        Self {
+            follow_egui_visuals: true,
+            ..Self::dark()
+        }
+    }
+}
 
-/// Calculate the color for a node based on its state
+impl EditorTheme {
+    /// The editor's original dark palette
+    pub fn dark() -> Self {
+        Self {
+            node_fill: NORMAL_NODE_COLOR,
+            zone_fill: egui::Color32::from_rgb(45, 45, 55),
+            zone_selected_fill: egui::Color32::from_rgb(65, 65, 85),
+            node_border: egui::Color32::from_rgb(80, 80, 90),
+            active: ACTIVE_STATE_COLOR,
+            bright_active: BRIGHT_ACTIVE_STATE_COLOR,
+            transition: TRANSITION_COLOR,
+            disabled_transition: DISABLED_TRANSITION_COLOR,
+            text_light: egui::Color32::WHITE,
+            text_dark: egui::Color32::BLACK,
+            min_luminance_color: NORMAL_NODE_COLOR,
+            max_luminance_color: BRIGHT_ACTIVE_STATE_COLOR,
+            grid: egui::Color32::from_rgba_unmultiplied(255, 255, 255, 25),
+            follow_egui_visuals: false,
+        }
+    }
+    
+    /// A light palette for apps with light backgrounds
+    pub fn light() -> Self {
+        Self {
+            node_fill: egui::Color32::from_rgb(225, 225, 230),
+            zone_fill: egui::Color32::from_rgb(242, 242, 246),
+            zone_selected_fill: egui::Color32::from_rgb(218, 224, 245),
+            node_border: egui::Color32::from_rgb(160, 160, 170),
+            active: egui::Color32::from_rgb(235, 185, 0),
+            bright_active: egui::Color32::from_rgb(255, 220, 80),
+            transition: egui::Color32::from_rgb(90, 90, 100),
+            disabled_transition: egui::Color32::from_rgb(200, 200, 200),
+            text_light: egui::Color32::WHITE,
+            text_dark: egui::Color32::from_rgb(20, 20, 25),
+            min_luminance_color: egui::Color32::from_rgb(90, 90, 90),
+            max_luminance_color: egui::Color32::from_rgb(170, 170, 170),
+            grid: egui::Color32::from_rgba_unmultiplied(0, 0, 0, 30),
+            follow_egui_visuals: false,
+        }
+    }
+    
+    /// The palette to draw with under the given egui visuals
+    ///
+    /// Returns `self` unchanged unless `follow_egui_visuals` is set, in which case the base
+    /// colors come from the visuals and text contrast uses the matching built-in preset.
+    pub fn resolved(&self, visuals: &egui::Visuals) -> Self {
+        if !self.follow_egui_visuals {
+            return *self;
+        }
+        let preset = if visuals.dark_mode { Self::dark() } else { Self::light() };
+        let text = visuals.strong_text_color();
+        Self {
+            node_fill: visuals.widgets.inactive.bg_fill,
+            zone_fill: visuals.window_fill,
+            zone_selected_fill: visuals.widgets.hovered.bg_fill,
+            node_border: visuals.widgets.noninteractive.bg_stroke.color,
+            transition: text,
+            text_light: if visuals.dark_mode { text } else { preset.text_light },
+            text_dark: if visuals.dark_mode { preset.text_dark } else { text },
+            min_luminance_color: preset.min_luminance_color,
+            max_luminance_color: preset.max_luminance_color,
+            grid: text.gamma_multiply(0.1),
+            ..*self
+        }
+    }
+    
+    /// Calculate the color for a node based on its state
+    pub fn node_color(&self, entity: Entity, q_active: &Query<&Active>) -> egui::Color32 {
+        if q_active.contains(entity) {
+            self.active
+        } else {
+            self.node_fill
+        }
+    }
+    
+    /// Compute a smoothly interpolated text color for a given background.
+    /// As background brightens from `min_luminance_color` to `max_luminance_color`,
+    /// text lerps from `text_light` to `text_dark`.
+    pub fn text_color_for_bg(&self, bg: egui::Color32) -> egui::Color32 {
+        let l_bg = color_luminance(bg);
+        let l_min = color_luminance(self.min_luminance_color);
+        let l_max = color_luminance(self.max_luminance_color);
+        let denom = (l_max - l_min).abs().max(1.0); // avoid div by zero
+        let t = ((l_bg - l_min) / denom).clamp(0.0, 1.0);
+        lerp_color(self.text_light, self.text_dark, t)
+    }
+}
+
+/// Calculate the color for a node based on its state, using the dark palette
+#[deprecated(note = "use `EditorTheme::node_color` so the node follows the active theme")]
 pub fn get_node_color(entity: Entity, q_active: &Query<&Active>) -> egui::Color32 {
-    if q_active.contains(entity) {
-        ACTIVE_STATE_COLOR
-    } else {
-        NORMAL_NODE_COLOR
+    EditorTheme::dark().node_color(entity, q_active)
+}
+
+/// Text color for a background, using the dark palette
+#[deprecated(note = "use `EditorTheme::text_color_for_bg` so text follows the active theme")]
+pub fn compute_text_color_for_bg(bg: egui::Color32) -> egui::Color32 {
+    EditorTheme::dark().text_color_for_bg(bg)
+}
+
+/// Tunable layout and animation constants
+///
+/// Insert it as a resource before adding `GearboxEditorPlugin` to override the defaults.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct EditorConfig {
+    /// Screen position of a machine opened without an explicit position
+    pub default_open_position: egui::Pos2,
+    /// Canvas offset of the first machine opened without an explicit position
+    pub machine_start_offset: egui::Vec2,
+    /// Distance between machines laid out on the open-machine grid
+    pub machine_spacing: egui::Vec2,
+    /// Machines per row of the open-machine grid
+    pub machines_per_row: usize,
+    /// Offset of a related machine from the machine it was opened from
+    pub related_machine_offset: egui::Vec2,
+    /// Offset of a new child from its parent's position
+    pub child_spawn_offset: egui::Vec2,
+    /// Offset of a duplicated state from the original
+    pub duplicate_offset: egui::Vec2,
+    /// Horizontal offset of the "Open" submenu from the background context menu
+    pub background_submenu_offset: f32,
+    /// How long a fired transition stays highlighted, in seconds
+    pub transition_pulse_seconds: f32,
+    /// How long an entered state flashes, in seconds
+    pub node_pulse_seconds: f32,
+    /// Reduced motion: no transition or state pulses, and follow-active jumps instead of easing
+    pub disable_animations: bool,
+    /// Let a state be picked as the target of its own transition (drawn as a loop)
+    pub allow_self_transitions: bool,
+}
+
+impl Default for EditorConfig {
+    fn default() -> Self {
+        Self {
+            default_open_position: egui::Pos2::new(100.0, 100.0),
+            machine_start_offset: egui::Vec2::new(50.0, 50.0),
+            machine_spacing: egui::Vec2::new(600.0, 400.0),
+            machines_per_row: 2,
+            related_machine_offset: egui::Vec2::new(300.0, 100.0),
+            child_spawn_offset: egui::Vec2::new(50.0, 50.0),
+            duplicate_offset: egui::Vec2::new(30.0, 30.0),
+            background_submenu_offset: 130.0,
+            transition_pulse_seconds: 0.4,
+            node_pulse_seconds: 0.6,
+            disable_animations: false,
+            allow_self_transitions: true,
+        }
     }
 }
 
@@ -565,42 +1444,69 @@ pub struct NodePulse {
 }
 
 impl NodePulse {
-    pub fn new(entity: Entity) -> Self {
-        Self { entity, timer: Timer::from_seconds(0.6, TimerMode::Once) }
+    pub fn new(entity: Entity, seconds: f32) -> Self {
+        Self { entity, timer: Timer::from_seconds(seconds, TimerMode::Once) }
     }
     pub fn intensity(&self) -> f32 { 1.0 - self.timer.fraction() }
 }
 
 /// Calculate the display color for a node, blending recent activity pulses
+///
+/// A custom `base_color` replaces the grey idle color and tints the active/pulse colors,
+/// so active feedback still shows on colored nodes.
 pub fn get_node_display_color(
     entity: Entity,
     q_active: &Query<&Active>,
     pulses: &[NodePulse],
+    base_color: Option<egui::Color32>,
+    theme: &EditorTheme,
 ) -> egui::Color32 {
+    let (normal, active, bright) = match base_color {
+        Some(base) => (
+            base,
+            lerp_color(base, theme.active, 0.65),
+            lerp_color(base, theme.bright_active, 0.85),
+        ),
+        None => (theme.node_fill, theme.active, theme.bright_active),
+    };
     let is_active = q_active.contains(entity);
     if let Some(pulse) = pulses.iter().find(|p| p.entity == entity) {
         let t = pulse.intensity(); // 1.0 at enter, down to 0.0
         if is_active {
             // Recently activated and still active: lerp from bright gold to gold
-            return lerp_color(ACTIVE_STATE_COLOR, BRIGHT_ACTIVE_STATE_COLOR, t);
+            return lerp_color(active, bright, t);
         } else {
-            // Entered then became inactive quickly: flash bright then fade to grey
-            return lerp_color(BRIGHT_ACTIVE_STATE_COLOR, NORMAL_NODE_COLOR, 1.0 - t);
+            // Entered then became inactive quickly: flash bright then fade to the base color
+            return lerp_color(bright, normal, 1.0 - t);
         }
     }
-    if is_active { ACTIVE_STATE_COLOR } else { NORMAL_NODE_COLOR }
+    if is_active { active } else { normal }
+}
+
+/// Tint a node while a transition target is being picked
+///
+/// The source is tinted blue and every other node a dim green, so the "pick a target" mode is obvious.
+pub fn tint_for_target_selection(color: egui::Color32, entity: Entity, creation: &TransitionCreationState) -> egui::Color32 {
+    if !creation.awaiting_target_selection {
+        return color;
+    }
+    if creation.source_entity == Some(entity) {
+        lerp_color(color, TRANSITION_SOURCE_COLOR, 0.45)
+    } else {
+        lerp_color(color, TARGET_CANDIDATE_COLOR, 0.25)
+    }
 }
 
 /// Calculate the color for a transition line/pill based on pulse state
-pub fn get_transition_color(edge_entity: Entity, pulses: &[TransitionPulse]) -> egui::Color32 {
-    // Base grey color for transitions (same as normal nodes)
-    let base_transition_color = NORMAL_NODE_COLOR;
+pub fn get_transition_color(edge_entity: Entity, pulses: &[TransitionPulse], theme: &EditorTheme) -> egui::Color32 {
+    // Idle pills share the node fill
+    let base_transition_color = theme.node_fill;
     
     // Find if there's an active pulse for this transition
     if let Some(pulse) = pulses.iter().find(|p| p.edge_entity == edge_entity) {
         let intensity = pulse.intensity();
-        // Lerp between normal grey and gold based on pulse intensity
-        lerp_color(base_transition_color, ACTIVE_STATE_COLOR, intensity)
+        // Lerp between the idle color and the active color based on pulse intensity
+        lerp_color(base_transition_color, theme.active, intensity)
     } else {
         base_transition_color
     }
@@ -622,19 +1528,6 @@ pub fn color_luminance(c: egui::Color32) -> f32 {
     0.2126 * c.r() as f32 + 0.7152 * c.g() as f32 + 0.0722 * c.b() as f32
 }
 
-/// Compute a smoothly interpolated text color for a given background.
-/// As background brightens from NORMAL_NODE_COLOR to BRIGHT_ACTIVE_STATE_COLOR,
-/// text lerps from white to black.
-pub fn compute_text_color_for_bg(bg: egui::Color32) -> egui::Color32 {
-    let l_bg = color_luminance(bg);
-    let l_min = color_luminance(NORMAL_NODE_COLOR);
-    let l_max = color_luminance(BRIGHT_ACTIVE_STATE_COLOR);
-    let denom = (l_max - l_min).abs().max(1.0); // avoid div by zero
-    let mut t = (l_bg - l_min) / denom;
-    t = t.clamp(0.0, 1.0);
-    lerp_color(egui::Color32::WHITE, egui::Color32::BLACK, t)
-}
-
 /// Helper to decide if text color is closer to black (for subscript contrast)
 pub fn prefers_dark_text(text: egui::Color32) -> bool {
     color_luminance(text) < 128.0
@@ -646,15 +1539,16 @@ pub fn draw_interactive_pill_label(
     position: egui::Pos2,
     text: &str,
     font_id: egui::FontId,
+    padding: egui::Vec2,
     is_dragging: bool,
     color: egui::Color32,
+    text_color: egui::Color32,
 ) -> egui::Response {
     // Calculate text dimensions
-    let galley = ui.fonts(|f| f.layout_no_wrap(text.to_string(), font_id, egui::Color32::WHITE));
+    let galley = ui.fonts(|f| f.layout_no_wrap(text.to_string(), font_id, text_color));
     let text_size = galley.size();
     
     // Calculate pill size with padding
-    let padding = egui::Vec2::new(8.0, 4.0);
     let pill_size = text_size + padding * 2.0;
     
     // Create the pill rectangle centered on the position
@@ -688,17 +1582,26 @@ pub fn draw_interactive_pill_label(
     painter.rect_stroke(
         pill_rect,
         egui::CornerRadius::same((pill_size.y / 2.0) as u8),
-        egui::Stroke::new(1.0, egui::Color32::WHITE),
+        egui::Stroke::new(1.0, text_color),
         egui::StrokeKind::Outside,
     );
     
     // Draw text
     let text_pos = pill_rect.center() - text_size * 0.5;
-    painter.galley(text_pos, galley, egui::Color32::WHITE);
+    painter.galley(text_pos, galley, text_color);
     
     response
 }
 
+/// Padding around a transition pill's text, tighter in compact mode
+pub fn pill_padding(compact: bool) -> egui::Vec2 {
+    if compact {
+        egui::Vec2::new(5.0, 2.0)
+    } else {
+        egui::Vec2::new(8.0, 4.0)
+    }
+}
+
 /// Item to be rendered in the node editor, with z-order information
 pub struct RenderItem {
     pub entity: Entity,
@@ -718,32 +1621,133 @@ pub struct TransitionConnection {
     pub is_dragging_event_node: bool,
     /// Offset from the midpoint between source and target nodes
     pub event_node_offset: egui::Vec2,
+    /// Index among transitions connecting the same pair of nodes (used to fan out overlapping pills)
+    pub sibling_index: usize,
+    /// Number of transitions connecting the same pair of nodes
+    pub sibling_count: usize,
+    /// Type paths of guard components found on the edge entity
+    pub guards: Vec<String>,
+    /// Whether the edge is an eventless `AlwaysEdge` (drawn dashed with an "ε" pill)
+    pub is_always: bool,
+    /// Whether the edge is `EdgeKind::Internal` (drawn with a "↻" on its pill)
+    pub is_internal: bool,
+    /// 1-based evaluation order and group size when several edges share this source and event
+    pub order_badge: Option<(usize, usize)>,
+    /// Event type the edge listens for (its `Name`, which the pill label may override)
+    pub listener_event: String,
+    /// Whether the edge carries `DisabledEdge` (drawn greyed and dashed)
+    pub is_disabled: bool,
+}
+
+/// Looks up a node's label provider result, `Name` and `InitialState`
+pub type EntityNamesQuery<'w, 's> = Query<'w, 's, (Entity, Option<&'static Name>, Option<&'static InitialState>, Option<&'static EditorLabel>)>;
+
+/// Label a custom `LabelProvider` returned for a state, shown instead of its `Name`
+///
+/// Maintained by the editor while a provider is set; not reflected, so it is never saved.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct EditorLabel(pub String);
+
+/// Computes a state's label from domain components; `None` falls back to the `Name`
+pub type LabelProvider = fn(Entity, &World) -> Option<String>;
+
+/// The label provider set on `GearboxEditorPlugin`
+#[derive(Resource, Clone, Copy)]
+pub struct EditorLabelProvider(pub LabelProvider);
+
+/// Format an entity's display name: its `EditorLabel`, else its `Name`, else `Entity {id}`
+///
+/// Shared by the query and world variants so every panel, menu and window title agrees.
+pub fn format_entity_name(entity: Entity, label: Option<&EditorLabel>, name: Option<&Name>) -> String {
+    if let Some(label) = label {
+        label.0.clone()
+    } else if let Some(name) = name {
+        name.as_str().to_string()
+    } else {
+        format!("Entity {:?}", entity)
+    }
+}
+
+/// The entity's own `Name` (empty if it has none), ignoring editor labels
+///
+/// Use this where the name is edited, such as renaming, so provider labels never leak into `Name`.
+pub fn entity_raw_name(entity: Entity, all_entities: &EntityNamesQuery) -> String {
+    all_entities
+        .get(entity)
+        .ok()
+        .and_then(|(_, name, _, _)| name.map(|name| name.as_str().to_string()))
+        .unwrap_or_default()
 }
 
 /// Get a human-readable name for an entity
-pub fn get_entity_name(entity: Entity, all_entities: &Query<(Entity, Option<&Name>, Option<&InitialState>)>) -> String {
-    if let Ok((_, name_opt, _)) = all_entities.get(entity) {
-        if let Some(name) = name_opt {
-            name.as_str().to_string()
-        } else {
-            format!("Entity {:?}", entity)
+pub fn entity_display_name(entity: Entity, all_entities: &EntityNamesQuery) -> String {
+    match all_entities.get(entity) {
+        Ok((_, name, _, label)) => format_entity_name(entity, label, name),
+        Err(_) => format!("Unknown Entity {:?}", entity),
+    }
+}
+
+/// Get a human-readable name for an entity using world access
+///
+/// Asks the label provider directly, so labels are fresh even before `refresh_editor_labels` runs.
+pub fn entity_display_name_from_world(entity: Entity, world: &World) -> String {
+    if let Some(provider) = world.get_resource::<EditorLabelProvider>().copied() {
+        if let Some(label) = provider.0(entity, world) {
+            return label;
         }
-    } else {
-        format!("Unknown Entity {:?}", entity)
+    }
+    match world.get_entity(entity) {
+        Ok(entity_ref) => format_entity_name(entity, entity_ref.get::<EditorLabel>(), entity_ref.get::<Name>()),
+        Err(_) => format!("Unknown Entity {:?}", entity),
+    }
+}
+
+/// The entity's own `Name` using world access (empty if it has none), ignoring editor labels
+pub fn entity_raw_name_from_world(entity: Entity, world: &World) -> String {
+    world.get::<Name>(entity).map(|name| name.as_str().to_string()).unwrap_or_default()
+}
+
+/// Get a human-readable name for an entity
+///
+/// Keeps its original query shape, so editor labels are not consulted; use `entity_display_name` for those.
+#[deprecated(note = "use `entity_display_name` with `EntityNamesQuery`")]
+pub fn get_entity_name(entity: Entity, all_entities: &Query<(Entity, Option<&Name>, Option<&InitialState>)>) -> String {
+    match all_entities.get(entity) {
+        Ok((_, name, _)) => format_entity_name(entity, None, name),
+        Err(_) => format!("Unknown Entity {:?}", entity),
     }
 }
 
 /// Get a human-readable name for an entity using world access
+#[deprecated(note = "renamed to `entity_display_name_from_world`")]
 pub fn get_entity_name_from_world(entity: Entity, world: &mut World) -> String {
-    let mut query = world.query::<(Entity, Option<&Name>)>();
-    if let Ok((_, name_opt)) = query.get(world, entity) {
-        if let Some(name) = name_opt {
-            name.as_str().to_string()
-        } else {
-            format!("Entity {:?}", entity)
+    entity_display_name_from_world(entity, world)
+}
+
+/// Exclusive system keeping `EditorLabel` in sync with the label provider for every state
+///
+/// Only changed labels are written, so the components don't trigger change detection every frame.
+pub fn refresh_editor_labels(world: &mut World) {
+    let Some(provider) = world.get_resource::<EditorLabelProvider>().copied() else {
+        return;
+    };
+    let mut q_states = world.query_filtered::<(Entity, Option<&EditorLabel>), Or<(With<bevy_gearbox::StateMachine>, With<bevy_gearbox::StateChildOf>)>>();
+    let changes: Vec<(Entity, Option<String>)> = q_states
+        .iter(world)
+        .filter_map(|(entity, current)| {
+            let label = provider.0(entity, world);
+            (label.as_deref() != current.map(|current| current.0.as_str())).then_some((entity, label))
+        })
+        .collect();
+    for (entity, label) in changes {
+        match label {
+            Some(label) => {
+                world.entity_mut(entity).insert(EditorLabel(label));
+            }
+            None => {
+                world.entity_mut(entity).remove::<EditorLabel>();
+            }
         }
-    } else {
-        format!("Unknown Entity {:?}", entity)
     }
 }
 
@@ -798,8 +1802,11 @@ pub fn closest_point_on_rect_edge(rect: egui::Rect, point: egui::Pos2) -> egui::
 
 /// Draw an arrow from start to end point
 pub fn draw_arrow(painter: &egui::Painter, start: egui::Pos2, end: egui::Pos2, color: egui::Color32) {
-    let stroke = egui::Stroke::new(2.0, color);
-    
+    draw_arrow_with_stroke(painter, start, end, egui::Stroke::new(2.0, color));
+}
+
+/// Draw an arrow from start to end point with a custom stroke
+pub fn draw_arrow_with_stroke(painter: &egui::Painter, start: egui::Pos2, end: egui::Pos2, stroke: egui::Stroke) {
     // Draw the main line
     painter.line_segment([start, end], stroke);
     
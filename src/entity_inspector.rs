@@ -4,6 +4,9 @@
 //! - Rendering the entity inspector UI
 //! - Integration with bevy-inspector-egui
 //! - Managing inspector state
+//! - Remembering the inspected entity per machine so it can be restored on reopen
+//! - Showing inspectors as floating windows or docked side panels
+//! - Jumping from `Entity` fields to their nodes on the canvas
 
 use bevy::prelude::*;
 use bevy::ecs::reflect::ReflectComponent;
@@ -14,7 +17,8 @@ use bevy_inspector_egui::{
 };
 
 
-use crate::editor_state::{EditorState, EditorWindow, InspectorTab, get_entity_name_from_world};
+use crate::editor_state::{EditorState, EditorWindow, InspectorLayout, InspectorTab, Select, StateMachinePersistentData, entity_display_name_from_world};
+use crate::window_management::CurrentEditorWindow;
 
 /// Helper function to try adding components via reflection
 fn try_add_component_via_reflection(world: &mut World, entity: Entity, component_type_name: &str) -> bool {
@@ -173,25 +177,26 @@ pub fn entity_inspector_system(world: &mut World) {
 
     if let Some(inspected_entity) = inspected_entity {
         // Get the entity name
-        let entity_name = get_entity_name_from_world(inspected_entity, world);
+        let entity_name = entity_display_name_from_world(inspected_entity, world);
         
-        // Get the egui context from editor windows only
+        // Get the egui context of the editor window being drawn
+        let camera = world.resource::<CurrentEditorWindow>().camera;
         let Ok(egui_context) = world
             .query_filtered::<&mut EguiContext, (With<EditorWindow>, Without<bevy_egui::PrimaryEguiContext>)>()
-            .single(world)
+            .get(world, camera)
         else {
             return;
         };
         let mut ctx = egui_context.clone();
         
-        let mut keep_open = true;
-        egui::Window::new("Inspector")
+        let inspector_layout = world.resource::<EditorState>().inspector_layout;
+        let window = egui::Window::new("Inspector")
             .id(egui::Id::new("entity_inspector_window"))
             .default_width(300.0)
-            .resizable(true)
-            .vscroll(true)
-            .open(&mut keep_open)
-            .show(ctx.get_mut(), |ui| {
+            .resizable(true);
+        let mut keep_open = true;
+        show_inspector_frame(ctx.get_mut(), inspector_layout, window, "entity_inspector_panel", "Inspector", &mut keep_open, |ui| {
+            egui::ScrollArea::vertical().auto_shrink([false, true]).show(ui, |ui| {
                 ui.label(format!("Inspector: {}", entity_name));
                 ui.separator();
                 if world.entities().contains(inspected_entity) {
@@ -200,6 +205,7 @@ pub fn entity_inspector_system(world: &mut World) {
                     ui.label("Entity no longer exists");
                 }
             });
+        });
         
         // If the user closed the window, clear the inspected entity
         if !keep_open {
@@ -210,6 +216,77 @@ pub fn entity_inspector_system(world: &mut World) {
     }
 }
 
+/// Show inspector contents in `window`, or in a side panel when the layout is docked
+///
+/// Docked panels are shown before the canvas's `CentralPanel`, so the canvas shrinks around them.
+/// Returns the window's rectangle while floating.
+pub fn show_inspector_frame<'open>(
+    ctx: &egui::Context,
+    layout: InspectorLayout,
+    window: egui::Window<'open>,
+    panel_id: &str,
+    title: &str,
+    open: &'open mut bool,
+    add_contents: impl FnOnce(&mut egui::Ui),
+) -> Option<egui::Rect> {
+    let side = match layout {
+        InspectorLayout::Floating => {
+            return window.open(open).show(ctx, add_contents).map(|response| response.response.rect);
+        }
+        InspectorLayout::DockedRight => egui::panel::Side::Right,
+        InspectorLayout::DockedLeft => egui::panel::Side::Left,
+    };
+    egui::SidePanel::new(side, egui::Id::new(panel_id))
+        .resizable(true)
+        .default_width(320.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.strong(title);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.small_button("✕").on_hover_text("Close").clicked() {
+                        *open = false;
+                    }
+                });
+            });
+            ui.separator();
+            add_contents(ui);
+        });
+    None
+}
+
+/// System that records the inspected state or edge on the machine it belongs to
+///
+/// Closing the inspector forgets the entity for its machine, so reopening the machine doesn't bring it back.
+pub fn remember_inspected_entity(
+    editor_state: Res<EditorState>,
+    mut q_sm: Query<&mut StateMachinePersistentData>,
+    mut previous: Local<Option<Entity>>,
+) {
+    if !editor_state.is_changed() || editor_state.inspected_entity == *previous {
+        return;
+    }
+    let closed = previous.take();
+    *previous = editor_state.inspected_entity;
+
+    for open_machine in &editor_state.view.open_machines {
+        let Ok(mut persistent) = q_sm.get_mut(open_machine.entity) else { continue };
+        match editor_state.inspected_entity {
+            Some(inspected) => {
+                let belongs = persistent.nodes.contains_key(&inspected)
+                    || persistent.visual_transitions.iter().any(|transition| transition.edge_entity == inspected);
+                if belongs && persistent.inspected_entity != Some(inspected) {
+                    persistent.inspected_entity = Some(inspected);
+                }
+            }
+            None => {
+                if closed.is_some() && persistent.inspected_entity == closed {
+                    persistent.inspected_entity = None;
+                }
+            }
+        }
+    }
+}
+
 /// Render the inspector tabs interface
 fn render_inspector_tabs(world: &mut World, entity: Entity, ui: &mut egui::Ui) {
     // We need to temporarily extract the editor state to avoid borrowing issues
@@ -239,6 +316,7 @@ fn render_inspector_tabs(world: &mut World, entity: Entity, ui: &mut egui::Ui) {
         InspectorTab::Inspect => {
             // Use bevy-inspector-egui to render the entity
             ui_for_entity(world, entity, ui);
+            render_entity_references(world, entity, ui);
         }
         InspectorTab::Remove => {
             render_component_removal_ui(world, entity, ui);
@@ -425,6 +503,131 @@ fn render_component_removal_ui(world: &mut World, entity: Entity, ui: &mut egui:
     }
 }
 
+/// List `Entity` values held by the entity's components, with a button to frame each one on the canvas
+fn render_entity_references(world: &mut World, entity: Entity, ui: &mut egui::Ui) {
+    let references = collect_entity_references(world, entity);
+    if references.is_empty() {
+        return;
+    }
+
+    let mut jump_to = None;
+    ui.separator();
+    egui::CollapsingHeader::new("Referenced entities")
+        .id_salt(("entity_references", entity))
+        .default_open(true)
+        .show(ui, |ui| {
+            for (field_path, referenced) in &references {
+                let on_canvas = canvas_machine_for(world, *referenced).is_some();
+                ui.horizontal(|ui| {
+                    ui.label(format!("{}: {}", field_path, entity_display_name_from_world(*referenced, world)));
+                    let button = ui.add_enabled(on_canvas, egui::Button::new("⤴ Show").small());
+                    if button.on_disabled_hover_text("Not part of a machine open on the canvas").clicked() {
+                        jump_to = Some(*referenced);
+                    }
+                });
+            }
+        });
+
+    // Select the node and center it next frame
+    if let Some(target) = jump_to {
+        if let Some(machine) = canvas_machine_for(world, target) {
+            world.resource_mut::<EditorState>().view.pending_node_focus = Some((machine, target));
+            world.trigger(Select { selected: Some(target) });
+        }
+    }
+}
+
+/// The open machine whose canvas shows `entity` as a node, if any
+fn canvas_machine_for(world: &World, entity: Entity) -> Option<Entity> {
+    if !world.entities().contains(entity) {
+        return None;
+    }
+    let machine = crate::history::root_ancestor(world, entity);
+    let shown = world.resource::<EditorState>().is_machine_open(machine)
+        && world.get::<StateMachinePersistentData>(machine).is_some_and(|data| data.nodes.contains_key(&entity));
+    shown.then_some(machine)
+}
+
+/// Collect every `Entity` stored in the entity's reflected components, labelled `Component.field`
+fn collect_entity_references(world: &World, entity: Entity) -> Vec<(String, Entity)> {
+    let type_registry = world.resource::<AppTypeRegistry>();
+    let registry = type_registry.read();
+    let entity_ref = world.entity(entity);
+
+    let mut references = Vec::new();
+    for registration in registry.iter() {
+        let Some(component) = registration.data::<ReflectComponent>().and_then(|rc| rc.reflect(entity_ref)) else {
+            continue;
+        };
+        let component_name = registration.type_info().type_path_table().short_path();
+        collect_entities_in_value(component.as_partial_reflect(), component_name.to_string(), &mut references);
+    }
+    references.retain(|(_, referenced)| *referenced != entity);
+    references.sort_by(|a, b| a.0.cmp(&b.0));
+    references
+}
+
+/// Walk a reflected value, recording the path of every `Entity` found
+fn collect_entities_in_value(value: &dyn PartialReflect, path: String, out: &mut Vec<(String, Entity)>) {
+    if let Some(entity) = value.try_downcast_ref::<Entity>() {
+        out.push((path, *entity));
+        return;
+    }
+    match value.reflect_ref() {
+        bevy::reflect::ReflectRef::Struct(value) => {
+            for index in 0..value.field_len() {
+                if let (Some(name), Some(field)) = (value.name_at(index), value.field_at(index)) {
+                    collect_entities_in_value(field, format!("{}.{}", path, name), out);
+                }
+            }
+        }
+        bevy::reflect::ReflectRef::TupleStruct(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field(index) {
+                    collect_entities_in_value(field, format!("{}.{}", path, index), out);
+                }
+            }
+        }
+        bevy::reflect::ReflectRef::Tuple(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field(index) {
+                    collect_entities_in_value(field, format!("{}.{}", path, index), out);
+                }
+            }
+        }
+        bevy::reflect::ReflectRef::List(value) => {
+            for (index, item) in value.iter().enumerate() {
+                collect_entities_in_value(item, format!("{}[{}]", path, index), out);
+            }
+        }
+        bevy::reflect::ReflectRef::Array(value) => {
+            for (index, item) in value.iter().enumerate() {
+                collect_entities_in_value(item, format!("{}[{}]", path, index), out);
+            }
+        }
+        bevy::reflect::ReflectRef::Set(value) => {
+            for item in value.iter() {
+                collect_entities_in_value(item, format!("{}[]", path), out);
+            }
+        }
+        bevy::reflect::ReflectRef::Map(value) => {
+            for (key, item) in value.iter() {
+                collect_entities_in_value(key, format!("{}.key", path), out);
+                collect_entities_in_value(item, format!("{}[]", path), out);
+            }
+        }
+        bevy::reflect::ReflectRef::Enum(value) => {
+            for index in 0..value.field_len() {
+                if let Some(field) = value.field_at(index) {
+                    let name = value.name_at(index).map(str::to_string).unwrap_or_else(|| index.to_string());
+                    collect_entities_in_value(field, format!("{}::{}.{}", path, value.variant_name(), name), out);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
 /// Get all components on an entity with their type information
 fn get_entity_components(world: &World, entity: Entity) -> Vec<(String, std::any::TypeId)> {
     let mut components = Vec::new();
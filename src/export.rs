@@ -0,0 +1,656 @@
+//! Exporting state machine diagrams
+//!
+//! This module handles:
+//! - Snapshotting a machine's hierarchy and names into a `MachineGraph`
+//! - Rendering the editor layout of a machine to SVG, independent of egui
+//! - Converting a machine to Graphviz DOT for docs and diagram tooling
+//! - Serializing the logical machine structure (no layout) to JSON for external tooling
+
+use std::fmt::Write as _;
+
+use bevy::prelude::*;
+use bevy::platform::collections::HashMap;
+use bevy_egui::{egui, EguiContext, PrimaryEguiContext};
+use bevy_gearbox::{InitialState, Parallel, StateChildOf, StateChildren};
+use bevy_gearbox::transitions::{AlwaysEdge, Target, Transitions as EdgeTransitions};
+use serde::Serialize;
+
+use crate::components::NodeType;
+use crate::editor_state::{entity_display_name_from_world, EditorWindow, ExportMachineDot, ExportMachineJson, ExportMachineSvg, StateMachinePersistentData, TransitionConnection};
+use crate::window_management::ActiveEditorWindow;
+
+/// Names and hierarchy of a machine, detached from the ECS so exporters can run headless
+#[derive(Debug, Clone, Default)]
+pub struct MachineGraph {
+    /// The machine root
+    pub root: Entity,
+    /// Display name of each state
+    pub names: HashMap<Entity, String>,
+    /// Direct children of each compound state
+    pub children: HashMap<Entity, Vec<Entity>>,
+    /// Initial state of each compound state
+    pub initial_states: HashMap<Entity, Entity>,
+}
+
+impl MachineGraph {
+    /// Collect the hierarchy under `root` from the ECS
+    pub fn from_queries(
+        root: Entity,
+        q_children: &Query<&StateChildren>,
+        q_names: &Query<&Name>,
+        q_initial: &Query<&InitialState>,
+    ) -> Self {
+        let mut graph = MachineGraph { root, ..default() };
+        for state in std::iter::once(root).chain(q_children.iter_descendants(root)) {
+            if let Ok(name) = q_names.get(state) {
+                graph.names.insert(state, name.as_str().to_string());
+            }
+            if let Ok(children) = q_children.get(state) {
+                graph.children.insert(state, children.into_iter().copied().collect());
+            }
+            if let Ok(initial) = q_initial.get(state) {
+                graph.initial_states.insert(state, initial.0);
+            }
+        }
+        graph
+    }
+
+    /// Display name of a state, falling back to its entity id
+    pub fn name(&self, entity: Entity) -> String {
+        self.names.get(&entity).cloned().unwrap_or_else(|| format!("{:?}", entity))
+    }
+}
+
+/// Render a machine's layout as a standalone SVG document
+///
+/// Parents are drawn before (behind) their children, and transitions are drawn on top with
+/// their arrowheads and event pills at the positions stored in the editor.
+pub fn machine_to_svg(graph: &MachineGraph, persistent_data: &StateMachinePersistentData) -> String {
+    const MARGIN: f32 = 40.0;
+    const PILL_CHAR_WIDTH: f32 = 7.0;
+
+    let node_rect = |entity: Entity| persistent_data.nodes.get(&entity).map(|node| node.current_rect());
+
+    // Bounds of everything drawn, so the document has a tight viewBox
+    let mut bounds = egui::Rect::NOTHING;
+    for node in persistent_data.nodes.values() {
+        bounds = bounds.union(node.current_rect());
+    }
+    for transition in &persistent_data.visual_transitions {
+        bounds.extend_with(transition.event_node_position);
+    }
+    if !bounds.is_positive() {
+        bounds = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::Vec2::splat(1.0));
+    }
+    let bounds = bounds.expand(MARGIN);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{}" height="{}" font-family="sans-serif">"#,
+        bounds.min.x, bounds.min.y, bounds.width(), bounds.height(), bounds.width(), bounds.height(),
+    );
+    svg.push_str(r##"<defs><marker id="arrowhead" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="8" markerHeight="8" orient="auto-start-reverse"><path d="M 0 0 L 10 5 L 0 10 z" fill="#ffffff"/></marker></defs>"##);
+    svg.push('\n');
+    let _ = writeln!(
+        svg,
+        r##"<rect x="{}" y="{}" width="{}" height="{}" fill="#1b1b1b"/>"##,
+        bounds.min.x, bounds.min.y, bounds.width(), bounds.height(),
+    );
+
+    // Larger rects first so parent zones sit behind their children
+    let mut nodes: Vec<(&Entity, &NodeType)> = persistent_data.nodes.iter().collect();
+    nodes.sort_by(|(_, a), (_, b)| b.current_rect().area().total_cmp(&a.current_rect().area()));
+    for (entity, node) in nodes {
+        let rect = node.current_rect();
+        let name = xml_escape(&graph.name(*entity));
+        match node {
+            NodeType::Parent(parent_node) => {
+                let title_bottom = rect.min.y + parent_node.title_bar_height;
+                let _ = writeln!(
+                    svg,
+                    r##"<rect x="{}" y="{}" width="{}" height="{}" rx="8" fill="#2a2a2a" stroke="#808080" stroke-width="1.5"/>"##,
+                    rect.min.x, rect.min.y, rect.width(), rect.height(),
+                );
+                let _ = writeln!(
+                    svg,
+                    r##"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="#808080"/>"##,
+                    rect.min.x, title_bottom, rect.max.x, title_bottom,
+                );
+                let _ = writeln!(
+                    svg,
+                    r##"<text x="{}" y="{}" font-size="14" fill="#ffffff" dominant-baseline="middle">{}</text>"##,
+                    rect.min.x + parent_node.entity_node.padding.x, rect.min.y + parent_node.title_bar_height * 0.5, name,
+                );
+            }
+            NodeType::Leaf(_) => {
+                let _ = writeln!(
+                    svg,
+                    r##"<rect x="{}" y="{}" width="{}" height="{}" rx="10" fill="#3c3c3c" stroke="#808080" stroke-width="1.5"/>"##,
+                    rect.min.x, rect.min.y, rect.width(), rect.height(),
+                );
+                let _ = writeln!(
+                    svg,
+                    r##"<text x="{}" y="{}" font-size="14" fill="#ffffff" text-anchor="middle" dominant-baseline="middle">{}</text>"##,
+                    rect.center().x, rect.center().y, name,
+                );
+            }
+        }
+    }
+
+    // Initial state markers: a dot left of the initial child with a short arrow into it
+    for initial in graph.initial_states.values() {
+        let Some(rect) = node_rect(*initial) else { continue; };
+        let dot = rect.left_center() - egui::vec2(24.0, 0.0);
+        let _ = writeln!(svg, r##"<circle cx="{}" cy="{}" r="5" fill="#ffffff"/>"##, dot.x, dot.y);
+        let _ = writeln!(
+            svg,
+            r##"<path d="M {} {} L {} {}" stroke="#ffffff" stroke-width="1.5" fill="none" marker-end="url(#arrowhead)"/>"##,
+            dot.x + 5.0, dot.y, rect.min.x, dot.y,
+        );
+    }
+
+    // Transitions with their event pills
+    for transition in &persistent_data.visual_transitions {
+        let (Some(source_rect), Some(target_rect)) = (node_rect(transition.source_entity), node_rect(transition.target_entity)) else {
+            continue;
+        };
+        let transition = TransitionConnection {
+            source_rect,
+            target_rect,
+            ..transition.clone()
+        };
+        let event_pos = transition.event_node_position;
+        let path = if transition.is_self_transition() {
+            let corner_inset = (source_rect.width().min(source_rect.height()) * 0.25).min(15.0);
+            let exit = egui::pos2(source_rect.max.x - corner_inset, source_rect.min.y);
+            let entry = egui::pos2(source_rect.max.x, source_rect.min.y + corner_inset);
+            let bulge = 20.0;
+            format!(
+                "M {} {} C {} {} {} {} {} {} C {} {} {} {} {} {}",
+                exit.x, exit.y, exit.x, exit.y - bulge, event_pos.x - bulge, event_pos.y, event_pos.x, event_pos.y,
+                event_pos.x, event_pos.y + bulge, entry.x + bulge, entry.y, entry.x, entry.y,
+            )
+        } else {
+            let (source_start, source_end, target_start, target_end) = transition.calculate_two_segment_points();
+            format!(
+                "M {} {} L {} {} L {} {} L {} {}",
+                source_start.x, source_start.y, source_end.x, source_end.y,
+                target_start.x, target_start.y, target_end.x, target_end.y,
+            )
+        };
+        let _ = writeln!(
+            svg,
+            r##"<path d="{}" stroke="#ffffff" stroke-width="2" fill="none" marker-end="url(#arrowhead)"/>"##,
+            path,
+        );
+
+        let pill_size = egui::vec2(transition.event_type.chars().count() as f32 * PILL_CHAR_WIDTH + 16.0, 22.0);
+        let pill = egui::Rect::from_center_size(event_pos, pill_size);
+        let _ = writeln!(
+            svg,
+            r##"<rect x="{}" y="{}" width="{}" height="{}" rx="{}" fill="#505a6e"/>"##,
+            pill.min.x, pill.min.y, pill.width(), pill.height(), pill.height() * 0.5,
+        );
+        let _ = writeln!(
+            svg,
+            r##"<text x="{}" y="{}" font-size="12" fill="#ffffff" text-anchor="middle" dominant-baseline="middle">{}</text>"##,
+            event_pos.x, event_pos.y, xml_escape(&transition.event_type),
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Convert a machine to a Graphviz DOT digraph
+///
+/// Compound states become `subgraph cluster_*` blocks. Each compound state gets a point node
+/// with an arrow into its initial state (invisible when it has none), which also anchors
+/// transitions to and from the cluster via `lhead`/`ltail`.
+pub fn machine_to_dot(graph: &MachineGraph, persistent_data: &StateMachinePersistentData) -> String {
+    let mut dot = String::new();
+    let _ = writeln!(dot, "digraph {} {{", dot_quote(&graph.name(graph.root)));
+    dot.push_str("    compound=true;\n");
+    dot.push_str("    node [shape=box, style=rounded];\n");
+    let _ = writeln!(dot, "    label={};", dot_quote(&graph.name(graph.root)));
+    if is_compound(graph, graph.root) {
+        write_dot_contents(&mut dot, graph, graph.root, 1);
+    } else {
+        let _ = writeln!(dot, "    {} [label={}];", dot_id(graph.root), dot_quote(&graph.name(graph.root)));
+    }
+
+    // Parent lookup to avoid clipping edges at a cluster that contains the other endpoint
+    let mut parents: HashMap<Entity, Entity> = HashMap::new();
+    for (parent, children) in &graph.children {
+        for child in children {
+            parents.insert(*child, *parent);
+        }
+    }
+    let contains = |ancestor: Entity, mut entity: Entity| {
+        while let Some(parent) = parents.get(&entity) {
+            if *parent == ancestor {
+                return true;
+            }
+            entity = *parent;
+        }
+        false
+    };
+
+    for transition in &persistent_data.visual_transitions {
+        let (source, target) = (transition.source_entity, transition.target_entity);
+        let mut attributes = vec![format!("label={}", dot_quote(&transition.event_type))];
+        let clip = source != target && !contains(source, target) && !contains(target, source);
+        if clip && source != graph.root && is_compound(graph, source) {
+            attributes.push(format!("ltail={}", cluster_id(source)));
+        }
+        if clip && target != graph.root && is_compound(graph, target) {
+            attributes.push(format!("lhead={}", cluster_id(target)));
+        }
+        let _ = writeln!(
+            dot,
+            "    {} -> {} [{}];",
+            dot_endpoint(graph, source),
+            dot_endpoint(graph, target),
+            attributes.join(", "),
+        );
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// Write the start marker and children of a compound state
+fn write_dot_contents(dot: &mut String, graph: &MachineGraph, parent: Entity, depth: usize) {
+    let indent = "    ".repeat(depth);
+    let start = start_id(parent);
+    match graph.initial_states.get(&parent) {
+        Some(initial) => {
+            let _ = writeln!(dot, "{}{} [shape=point, width=0.12, label=\"\"];", indent, start);
+            let lhead = if is_compound(graph, *initial) { format!(" [lhead={}]", cluster_id(*initial)) } else { String::new() };
+            let _ = writeln!(dot, "{}{} -> {}{};", indent, start, dot_endpoint(graph, *initial), lhead);
+        }
+        None => {
+            let _ = writeln!(dot, "{}{} [shape=point, style=invis, label=\"\"];", indent, start);
+        }
+    }
+    for child in graph.children.get(&parent).into_iter().flatten() {
+        if is_compound(graph, *child) {
+            let _ = writeln!(dot, "{}subgraph {} {{", indent, cluster_id(*child));
+            let _ = writeln!(dot, "{}    label={};", indent, dot_quote(&graph.name(*child)));
+            write_dot_contents(dot, graph, *child, depth + 1);
+            let _ = writeln!(dot, "{}}}", indent);
+        } else {
+            let _ = writeln!(dot, "{}{} [label={}];", indent, dot_id(*child), dot_quote(&graph.name(*child)));
+        }
+    }
+}
+
+/// Whether a state has children (and is drawn as a cluster)
+fn is_compound(graph: &MachineGraph, entity: Entity) -> bool {
+    graph.children.get(&entity).is_some_and(|children| !children.is_empty())
+}
+
+/// Node an edge to or from `entity` attaches to
+fn dot_endpoint(graph: &MachineGraph, entity: Entity) -> String {
+    if is_compound(graph, entity) { start_id(entity) } else { dot_id(entity) }
+}
+
+fn dot_id(entity: Entity) -> String {
+    format!("s{}", entity.to_bits())
+}
+
+fn start_id(entity: Entity) -> String {
+    format!("s{}_start", entity.to_bits())
+}
+
+fn cluster_id(entity: Entity) -> String {
+    format!("cluster_{}", entity.to_bits())
+}
+
+/// Quote a string as a DOT identifier
+///
+/// Line breaks become DOT's `\n` escape so multi-line names stay on one line of output.
+fn dot_quote(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace("\r\n", "\\n")
+        .replace(['\n', '\r'], "\\n");
+    format!("\"{}\"", escaped)
+}
+
+/// Logical structure of a machine, without any editor layout
+///
+/// Entities are written as their `Entity::to_bits` value so ids stay stable within one export.
+#[derive(Serialize, Debug, Clone)]
+pub struct MachineGraphExport {
+    /// The machine root
+    pub root: u64,
+    /// Every state in the machine, root first, in depth-first order
+    pub states: Vec<StateExport>,
+    /// Every transition leaving a state of the machine
+    pub transitions: Vec<TransitionExport>,
+}
+
+/// A single state in a `MachineGraphExport`
+#[derive(Serialize, Debug, Clone)]
+pub struct StateExport {
+    pub id: u64,
+    pub name: String,
+    /// Logical parent (None for the root)
+    pub parent: Option<u64>,
+    /// Initial child, for compound states
+    pub initial_state: Option<u64>,
+    /// Whether all children are entered together
+    pub parallel: bool,
+}
+
+/// A single transition in a `MachineGraphExport`
+#[derive(Serialize, Debug, Clone)]
+pub struct TransitionExport {
+    pub id: u64,
+    pub source: u64,
+    pub target: u64,
+    /// Short name of the listened event type, or "Always"
+    pub event_type: String,
+}
+
+impl MachineGraphExport {
+    /// Build the export by walking `StateChildren`, `InitialState` and each state's edges
+    pub fn from_world(world: &World, root: Entity) -> Self {
+        let mut states_to_visit = vec![root];
+        let mut states = Vec::new();
+        let mut transitions = Vec::new();
+        let event_edge_types = discover_event_edge_component_types(world.resource::<AppTypeRegistry>());
+
+        while let Some(state) = states_to_visit.pop() {
+            states.push(StateExport {
+                id: state.to_bits(),
+                name: entity_display_name_from_world(state, world),
+                parent: world.get::<StateChildOf>(state).filter(|_| state != root).map(|child_of| child_of.0.to_bits()),
+                initial_state: world.get::<InitialState>(state).map(|initial| initial.0.to_bits()),
+                parallel: world.get::<Parallel>(state).is_some(),
+            });
+
+            if let Some(edges) = world.get::<EdgeTransitions>(state) {
+                for edge in edges.into_iter().copied() {
+                    let Some(target) = world.get::<Target>(edge) else { continue; };
+                    transitions.push(TransitionExport {
+                        id: edge.to_bits(),
+                        source: state.to_bits(),
+                        target: target.0.to_bits(),
+                        event_type: edge_event_type(world, edge, &event_edge_types),
+                    });
+                }
+            }
+
+            // Push in reverse so children are visited in their stored order
+            if let Some(children) = world.get::<StateChildren>(state) {
+                let children: Vec<Entity> = children.into_iter().copied().collect();
+                states_to_visit.extend(children.into_iter().rev());
+            }
+        }
+
+        MachineGraphExport { root: root.to_bits(), states, transitions }
+    }
+
+    /// Serialize as pretty-printed JSON
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Discover `EventEdge<T>` component types from the type registry, keyed by the short event name
+fn discover_event_edge_component_types(type_registry: &AppTypeRegistry) -> Vec<(String, ReflectComponent)> {
+    let registry = type_registry.read();
+    registry
+        .iter()
+        .filter_map(|registration| {
+            let type_path = registration.type_info().type_path();
+            let start = type_path.find("EventEdge<")?;
+            let event_type = type_path[start + 10..].trim_end_matches('>'); // 10 = len("EventEdge<")
+            let short_name = event_type.split('<').next().unwrap_or(event_type).rsplit("::").next().unwrap_or(event_type);
+            let reflect_component = registration.data::<ReflectComponent>()?;
+            Some((short_name.to_string(), reflect_component.clone()))
+        })
+        .collect()
+}
+
+/// Event type an edge listens for, or "Always" for eventless edges
+fn edge_event_type(world: &World, edge: Entity, event_edge_types: &[(String, ReflectComponent)]) -> String {
+    if world.get::<AlwaysEdge>(edge).is_some() {
+        return "Always".to_string();
+    }
+    let Ok(edge_ref) = world.get_entity(edge) else {
+        return String::new();
+    };
+    event_edge_types
+        .iter()
+        .find(|(_, reflect_component)| reflect_component.contains(edge_ref))
+        .map(|(event_type, _)| event_type.clone())
+        .unwrap_or_default()
+}
+
+/// Escape text for use inside SVG elements
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Observer to write a machine's diagram to an SVG file
+pub fn handle_export_machine_svg(
+    export: On<ExportMachineSvg>,
+    q_persistent: Query<&StateMachinePersistentData>,
+    q_children: Query<&StateChildren>,
+    q_names: Query<&Name>,
+    q_initial: Query<&InitialState>,
+) {
+    let Ok(persistent_data) = q_persistent.get(export.entity) else {
+        warn!("⚠️ Cannot export {:?}: machine has no editor layout", export.entity);
+        return;
+    };
+    let graph = MachineGraph::from_queries(export.entity, &q_children, &q_names, &q_initial);
+    let svg = machine_to_svg(&graph, persistent_data);
+
+    if let Some(dir) = export.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            error!("❌ Failed to create {:?}: {}", dir, e);
+            return;
+        }
+    }
+    match std::fs::write(&export.path, svg) {
+        Ok(_) => info!("✅ Exported '{}' to {:?}", graph.name(export.entity), export.path),
+        Err(e) => error!("❌ Failed to export SVG to {:?}: {}", export.path, e),
+    }
+}
+
+/// Observer to convert a machine to DOT and copy it to the clipboard
+pub fn handle_export_machine_dot(
+    export: On<ExportMachineDot>,
+    q_persistent: Query<&StateMachinePersistentData>,
+    q_children: Query<&StateChildren>,
+    q_names: Query<&Name>,
+    q_initial: Query<&InitialState>,
+    mut q_editor_context: Query<&mut EguiContext, (With<EditorWindow>, Without<PrimaryEguiContext>)>,
+    active_window: Res<ActiveEditorWindow>,
+) {
+    let Ok(persistent_data) = q_persistent.get(export.entity) else {
+        warn!("⚠️ Cannot export {:?}: machine has no editor layout", export.entity);
+        return;
+    };
+    let graph = MachineGraph::from_queries(export.entity, &q_children, &q_names, &q_initial);
+    let dot = machine_to_dot(&graph, persistent_data);
+    debug!("{}", dot);
+
+    match active_window.0.and_then(|handle| q_editor_context.get_mut(handle.camera).ok()) {
+        Some(mut egui_context) => {
+            egui_context.get_mut().copy_text(dot);
+            info!("✅ Copied DOT for '{}' to the clipboard", graph.name(export.entity));
+        }
+        None => warn!("⚠️ No editor window to copy DOT from; see debug log for the output"),
+    }
+}
+
+/// Observer to write a machine's logical structure to a JSON file
+pub fn handle_export_machine_json(
+    export: On<ExportMachineJson>,
+    mut commands: Commands,
+) {
+    let entity = export.entity;
+    let path = export.path.clone();
+    commands.queue(move |world: &mut World| {
+        let json = match MachineGraphExport::from_world(world, entity).to_json() {
+            Ok(json) => json,
+            Err(e) => {
+                error!("❌ Failed to serialize {:?} to JSON: {}", entity, e);
+                return;
+            }
+        };
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            if let Err(e) = std::fs::create_dir_all(dir) {
+                error!("❌ Failed to create {:?}: {}", dir, e);
+                return;
+            }
+        }
+        match std::fs::write(&path, json) {
+            Ok(_) => info!("✅ Exported {:?} to {:?}", entity, path),
+            Err(e) => error!("❌ Failed to export JSON to {:?}: {}", path, e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::LeafNode;
+
+    /// A root with two leaves (the first one initial) and a transition from the first to the second
+    fn two_state_machine(world: &mut World, names: [&str; 3], event: &str) -> (MachineGraph, StateMachinePersistentData, [Entity; 3]) {
+        let [root, first, second] = [(); 3].map(|_| world.spawn_empty().id());
+        let edge = world.spawn_empty().id();
+        let graph = MachineGraph {
+            root,
+            names: [root, first, second].into_iter().zip(names.map(str::to_string)).collect(),
+            children: [(root, vec![first, second])].into_iter().collect(),
+            initial_states: [(root, first)].into_iter().collect(),
+        };
+
+        let mut persistent_data = StateMachinePersistentData::default();
+        persistent_data.nodes.insert(first, NodeType::Leaf(LeafNode::new(egui::pos2(0.0, 0.0))));
+        persistent_data.nodes.insert(second, NodeType::Leaf(LeafNode::new(egui::pos2(200.0, 0.0))));
+        let source_rect = persistent_data.nodes[&first].current_rect();
+        let target_rect = persistent_data.nodes[&second].current_rect();
+        persistent_data.visual_transitions.push(TransitionConnection {
+            source_entity: first,
+            edge_entity: edge,
+            target_entity: second,
+            event_type: event.to_string(),
+            source_rect,
+            target_rect,
+            event_node_position: source_rect.center().lerp(target_rect.center(), 0.5),
+            is_dragging_event_node: false,
+            event_node_offset: egui::Vec2::ZERO,
+            sibling_index: 0,
+            sibling_count: 1,
+            guards: Vec::new(),
+            is_always: false,
+            is_internal: false,
+            order_badge: None,
+            listener_event: event.to_string(),
+            is_disabled: false,
+        });
+        (graph, persistent_data, [root, first, second])
+    }
+
+    #[test]
+    fn dot_matches_golden_output() {
+        let mut world = World::new();
+        let (graph, persistent_data, [root, first, second]) =
+            two_state_machine(&mut world, ["Door", "Closed", "Say \"hi\"\nthen \\ wait"], "Open");
+        let (root, first, second) = (root.to_bits(), first.to_bits(), second.to_bits());
+
+        let expected = format!(
+            concat!(
+                "digraph \"Door\" {{\n",
+                "    compound=true;\n",
+                "    node [shape=box, style=rounded];\n",
+                "    label=\"Door\";\n",
+                "    s{root}_start [shape=point, width=0.12, label=\"\"];\n",
+                "    s{root}_start -> s{first};\n",
+                "    s{first} [label=\"Closed\"];\n",
+                "    s{second} [label=\"Say \\\"hi\\\"\\nthen \\\\ wait\"];\n",
+                "    s{first} -> s{second} [label=\"Open\"];\n",
+                "}}\n",
+            ),
+            root = root,
+            first = first,
+            second = second,
+        );
+        assert_eq!(machine_to_dot(&graph, &persistent_data), expected);
+    }
+
+    #[test]
+    fn dot_quote_escapes_quotes_backslashes_and_newlines() {
+        assert_eq!(dot_quote("plain"), "\"plain\"");
+        assert_eq!(dot_quote("a \"b\""), "\"a \\\"b\\\"\"");
+        assert_eq!(dot_quote("a\\b"), "\"a\\\\b\"");
+        assert_eq!(dot_quote("a\r\nb\nc"), "\"a\\nb\\nc\"");
+    }
+
+    #[test]
+    fn svg_escapes_state_and_event_names() {
+        let mut world = World::new();
+        let (graph, persistent_data, _) = two_state_machine(&mut world, ["Door", "Open & \"Shut\"", "<Locked>"], "A<B>");
+        let svg = machine_to_svg(&graph, &persistent_data);
+
+        assert!(svg.contains(">Open &amp; &quot;Shut&quot;</text>"));
+        assert!(svg.contains(">&lt;Locked&gt;</text>"));
+        assert!(svg.contains(">A&lt;B&gt;</text>"));
+        assert!(!svg.contains("<Locked>"));
+    }
+
+    #[test]
+    fn svg_draws_nodes_transitions_and_initial_marker() {
+        let mut world = World::new();
+        let (graph, persistent_data, _) = two_state_machine(&mut world, ["Door", "Closed", "Open"], "Push");
+        let svg = machine_to_svg(&graph, &persistent_data);
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        // Background plus two leaves plus one event pill
+        assert_eq!(svg.matches("<rect ").count(), 4);
+        // The transition and the initial-state arrow
+        assert_eq!(svg.matches("marker-end=\"url(#arrowhead)\"").count(), 2);
+        assert_eq!(svg.matches("<circle ").count(), 1);
+    }
+
+    #[test]
+    fn json_export_walks_hierarchy_and_edges() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        let root = world.spawn(Name::new("Door")).id();
+        let closed = world.spawn((Name::new("Closed"), StateChildOf(root))).id();
+        let open = world.spawn((Name::new("Say \"hi\"\nthen"), StateChildOf(root))).id();
+        world.entity_mut(root).insert(InitialState(closed));
+        let edge = world.spawn((bevy_gearbox::transitions::Source(closed), Target(open), AlwaysEdge)).id();
+
+        let export = MachineGraphExport::from_world(&world, root);
+        let ids: Vec<u64> = export.states.iter().map(|state| state.id).collect();
+        assert_eq!(ids, vec![root.to_bits(), closed.to_bits(), open.to_bits()]);
+        assert_eq!(export.states[0].parent, None);
+        assert_eq!(export.states[0].initial_state, Some(closed.to_bits()));
+        assert_eq!(export.states[2].parent, Some(root.to_bits()));
+        assert!(export.states.iter().all(|state| !state.parallel));
+        assert_eq!(export.transitions.len(), 1);
+        assert_eq!(export.transitions[0].id, edge.to_bits());
+        assert_eq!(export.transitions[0].event_type, "Always");
+
+        // Quotes and line breaks survive a round trip through the JSON text
+        let json: serde_json::Value = serde_json::from_str(&export.to_json().unwrap()).unwrap();
+        assert_eq!(json["states"][2]["name"], "Say \"hi\"\nthen");
+        assert_eq!(json["transitions"][0]["source"], closed.to_bits());
+    }
+}
@@ -4,19 +4,74 @@
 //! - Recursive parent-child movement when nodes are dragged
 //! - Constraining children to stay within parent bounds
 //! - Auto-resizing parents to fit their children
+//! - Pushing overlapping sibling parent zones apart (optional)
 //! - Managing InitialState components for parent entities
+//! - Reparenting nodes dropped into a different parent zone
+//! - Caching machine-root lookups until the hierarchy changes
+//! - Auto-layout of a machine's nodes along its state hierarchy
 
 use bevy::prelude::*;
 use bevy_gearbox::StateMachine;
 use std::collections::HashMap;
 use bevy::platform::collections::HashSet;
 
-use crate::editor_state::{EditorState, NodeDragged};
+use crate::editor_state::{AutoLayoutMachine, ContainMode, EditorState, NodeDragged, ReparentNode, StateMachineTransientData, MAX_PARENT_SIZE};
 use crate::components::NodeType;
 use crate::StateMachinePersistentData;
 
 
 
+/// Memoized `StateChildOf` root lookups, cleared whenever the state hierarchy changes
+#[derive(Resource, Default)]
+pub struct RootAncestorCache {
+    roots: HashMap<Entity, Entity>,
+}
+
+impl RootAncestorCache {
+    /// Root of `entity`'s state hierarchy, walking `StateChildOf` only on a cache miss
+    /// 
+    /// Every entity visited on the way up is cached too.
+    pub fn cached_root_ancestor(&mut self, entity: Entity, q_child_of: &Query<&bevy_gearbox::StateChildOf>) -> Entity {
+        if let Some(&root) = self.roots.get(&entity) {
+            return root;
+        }
+        let mut path = vec![entity];
+        let mut current = entity;
+        let root = loop {
+            if let Some(&root) = self.roots.get(&current) {
+                break root;
+            }
+            match q_child_of.get(current) {
+                Ok(child_of) => {
+                    current = child_of.0;
+                    path.push(current);
+                }
+                Err(_) => break current,
+            }
+        };
+        for visited in path {
+            self.roots.insert(visited, root);
+        }
+        root
+    }
+}
+
+/// Observer clearing the root cache when a state gets a (new) parent
+pub fn invalidate_root_cache_on_insert(
+    _insert: On<Insert, bevy_gearbox::StateChildOf>,
+    mut root_cache: ResMut<RootAncestorCache>,
+) {
+    root_cache.roots.clear();
+}
+
+/// Observer clearing the root cache when a state loses its parent (removal, replacement or despawn)
+pub fn invalidate_root_cache_on_replace(
+    _replace: On<Replace, bevy_gearbox::StateChildOf>,
+    mut root_cache: ResMut<RootAncestorCache>,
+) {
+    root_cache.roots.clear();
+}
+
 /// Observer to handle parent-child movement when nodes are dragged
 /// 
 /// This observer recursively moves all children when a parent is dragged,
@@ -35,6 +90,7 @@ pub fn handle_parent_child_movement(
     let Ok(mut machine_data) = q_sm.get_mut(selected_machine) else {
         return;
     };
+    machine_data.mark_layout_dirty();
     
     // Get all entities that have the dragged entity as their parent
     let Ok(children) = q_children.get(node_dragged.entity) else {
@@ -64,23 +120,86 @@ pub fn handle_parent_child_movement(
     }
 }
 
+/// Observer to move a dropped node under a different parent
+///
+/// The old parent's `InitialState` is handed to a remaining child (or dropped when none is
+/// left), and a leaf target is promoted to a parent through its NodeKind machine.
+pub fn handle_reparent_node(
+    reparent: On<ReparentNode>,
+    q_sm: Query<&StateMachineTransientData, With<StateMachine>>,
+    q_child_of: Query<&bevy_gearbox::StateChildOf>,
+    q_children: Query<&bevy_gearbox::StateChildren>,
+    q_initial_state: Query<&bevy_gearbox::InitialState>,
+    mut commands: Commands,
+) {
+    let entity = reparent.entity;
+    let new_parent = reparent.new_parent;
+    let Ok(old_parent) = q_child_of.get(entity).map(|child_of| child_of.0) else {
+        warn!("⚠️ Cannot reparent a state machine root");
+        return;
+    };
+    if old_parent == new_parent {
+        return;
+    }
+    // A node can't become a descendant of itself
+    let mut ancestor = Some(new_parent);
+    while let Some(current) = ancestor {
+        if current == entity {
+            warn!("⚠️ Cannot move {:?} into its own subtree", entity);
+            return;
+        }
+        ancestor = q_child_of.get(current).ok().map(|child_of| child_of.0);
+    }
+    let machine = q_child_of.root_ancestor(entity);
+    if q_child_of.root_ancestor(new_parent) != machine {
+        warn!("⚠️ Cannot move {:?} into a different state machine", entity);
+        return;
+    }
+
+    // Hand the old parent's initial state to a remaining sibling
+    if q_initial_state.get(old_parent).is_ok_and(|initial| initial.0 == entity) {
+        let sibling = q_children
+            .get(old_parent)
+            .ok()
+            .and_then(|children| children.into_iter().copied().find(|child| *child != entity));
+        match sibling {
+            Some(sibling) => { commands.entity(old_parent).insert(bevy_gearbox::InitialState(sibling)); }
+            None => { commands.entity(old_parent).remove::<bevy_gearbox::InitialState>(); }
+        }
+    }
+
+    commands.entity(entity).insert(bevy_gearbox::StateChildOf(new_parent));
+
+    // A leaf target becomes a parent; its NodeKind machine picks the initial state
+    if !q_children.contains(new_parent) {
+        if let Some(&nk_root) = q_sm.get(machine).ok().and_then(|transient| transient.node_kind_roots.get(&new_parent)) {
+            commands.trigger(crate::node_kind::ChildAdded::new(nk_root));
+        }
+    }
+
+    info!("✅ Moved {:?} from {:?} into {:?}", entity, old_parent, new_parent);
+}
+
 // NOTE: Removed automatic ensure_initial_states.
 
 /// System to constrain child nodes to stay within their parent's bounds
 /// 
-/// Children are prevented from moving left or up outside their parent,
-/// but can move right and down freely (which will trigger parent expansion).
+/// Children are prevented from moving left or up outside their parent. Moving right or down
+/// grows the parent (`ContainMode::Grow`, up to `MAX_PARENT_SIZE`) or, for a dragged child in
+/// `ContainMode::Clamp`, stops at the parent's current edge.
+/// A node being dragged towards another zone is left free until it is dropped.
 pub fn constrain_children_to_parents(
     editor_state: Res<EditorState>,
-    mut q_sm: Query<&mut StateMachinePersistentData, With<StateMachine>>,
+    mut q_sm: Query<(&mut StateMachinePersistentData, Option<&StateMachineTransientData>), With<StateMachine>>,
     q_child_of: Query<&bevy_gearbox::StateChildOf>,
     q_children: Query<&bevy_gearbox::StateChildren>,
 ) {
     // Process each open machine separately
-    for open_machine in &editor_state.open_machines {
-        let Ok(mut machine_data) = q_sm.get_mut(open_machine.entity) else {
+    for open_machine in &editor_state.view.open_machines {
+        let Ok((mut machine_data, transient_data)) = q_sm.get_mut(open_machine.entity) else {
             continue;
         };
+        let reparent_drag = transient_data.and_then(|transient| transient.reparent_drag());
     
         // Process all parent nodes in this machine
         let parent_entities: Vec<Entity> = machine_data.nodes.keys()
@@ -89,9 +208,17 @@ pub fn constrain_children_to_parents(
             .collect();
         
         for parent_entity in parent_entities {
+            // Hidden children keep their layout untouched until the parent is expanded again
+            if machine_data.collapsed.contains(&parent_entity) || machine_data.collapsed_proxy(parent_entity, &q_child_of).is_some() {
+                continue;
+            }
             if let Ok(children) = q_children.get(parent_entity) {
                 for child_entity in children.iter() {
-                    constrain_child_to_parent(child_entity, &mut machine_data, &q_child_of);
+                    if reparent_drag == Some(child_entity) {
+                        continue;
+                    }
+                    let dragged = transient_data.is_some_and(|transient| transient.is_being_dragged(child_entity, &editor_state.view.selected_nodes));
+                    constrain_child_to_parent(child_entity, editor_state.contain_mode, dragged, &mut machine_data, &q_child_of);
                 }
             }
         }
@@ -137,43 +264,37 @@ pub fn constrain_children_to_parents(
 
 /// Constrain a child node's position to stay within its parent's content area
 /// 
-/// Only constrains left and top edges - children can move right and down freely.
+/// Left and top edges are always constrained; right and bottom edges only once the parent
+/// can't grow any further under `mode`.
 fn constrain_child_to_parent(
     child_entity: Entity,
+    mode: ContainMode,
+    dragged: bool,
     machine_data: &mut StateMachinePersistentData,
     q_child_of: &Query<&bevy_gearbox::StateChildOf>,
 ) {
-    if let Ok(child_of) = q_child_of.get(child_entity) {
-        if let Some(parent_node) = machine_data.nodes.get(&child_of.0) {
-            if let NodeType::Parent(parent) = parent_node {
-                if let Some(child_node) = machine_data.nodes.get(&child_entity) {
-                    let child_rect = child_node.current_rect();
-                    let content_rect = parent.content_rect();
-                    let margin = egui::Vec2::new(10.0, 10.0);
-                    let constrained_min = content_rect.min + margin;
-                    
-                    // Only constrain left and top edges
-                    let constrained_pos = egui::Pos2::new(
-                        child_rect.min.x.max(constrained_min.x), // Only constrain left edge
-                        child_rect.min.y.max(constrained_min.y), // Only constrain top edge
-                    );
-                    
-                    // Update child position if it was constrained
-                    if constrained_pos != child_rect.min {
-                        if let Some(child_node) = machine_data.nodes.get_mut(&child_entity) {
-                            match child_node {
-                                NodeType::Leaf(leaf_node) => {
-                                    leaf_node.entity_node.position = constrained_pos;
-                                }
-                                NodeType::Parent(parent_node) => {
-                                    parent_node.entity_node.position = constrained_pos;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    let Ok(child_of) = q_child_of.get(child_entity) else { return; };
+    let Some(NodeType::Parent(parent)) = machine_data.nodes.get(&child_of.0) else { return; };
+    let Some(child_node) = machine_data.nodes.get(&child_entity) else { return; };
+    
+    let content_limit = match (mode, dragged) {
+        (ContainMode::Clamp, true) => parent.content_rect().size(),
+        _ => MAX_PARENT_SIZE - egui::Vec2::new(0.0, parent.title_bar_height),
+    };
+    let bounds = parent.child_bounds(content_limit);
+    let child_rect = child_node.current_rect();
+    
+    // Right/bottom first so the left/top constraint wins for children larger than the bounds
+    let constrained_pos = child_rect.min
+        .min(bounds.max - child_rect.size())
+        .max(bounds.min);
+    
+    // Update child position if it was constrained
+    if constrained_pos != child_rect.min {
+        if let Some(child_node) = machine_data.nodes.get_mut(&child_entity) {
+            child_node.set_position(constrained_pos);
         }
+        machine_data.mark_layout_dirty();
     }
 }
 
@@ -181,17 +302,35 @@ fn constrain_child_to_parent(
 /// 
 /// Parents automatically expand to contain all their children, with a margin.
 /// This uses a bottom-up approach, processing leaf nodes first, then their parents.
+/// Sizes are capped at `MAX_PARENT_SIZE`; in `ContainMode::Clamp` a parent keeps its size while
+/// one of its children is dragged. A node being dragged towards another zone does not expand
+/// its current parent.
+/// 
+/// Machines are skipped unless their layout is dirty, their node or transition count changed,
+/// or the state hierarchy changed since the last run.
 pub fn recalculate_parent_sizes(
     editor_state: Res<EditorState>,
-    mut q_sm: Query<&mut StateMachinePersistentData, With<StateMachine>>,
+    mut q_sm: Query<(&mut StateMachinePersistentData, Option<&StateMachineTransientData>), With<StateMachine>>,
     q_children: Query<&bevy_gearbox::StateChildren>,
     q_child_of: Query<&bevy_gearbox::StateChildOf>,
+    q_hierarchy_changed: Query<(), Or<(Changed<bevy_gearbox::StateChildOf>, Changed<bevy_gearbox::StateChildren>)>>,
+    mut removed_child_of: RemovedComponents<bevy_gearbox::StateChildOf>,
 ) {
+    let removed_any = removed_child_of.read().count() > 0;
+    let hierarchy_changed = removed_any || !q_hierarchy_changed.is_empty();
+    
     // Process each open machine separately
-    for open_machine in &editor_state.open_machines {
-        let Ok(mut machine_data) = q_sm.get_mut(open_machine.entity) else {
+    for open_machine in &editor_state.view.open_machines {
+        let Ok((mut machine_data, transient_data)) = q_sm.get_mut(open_machine.entity) else {
             continue;
         };
+        let counts = (machine_data.nodes.len(), machine_data.visual_transitions.len());
+        if !machine_data.layout_dirty && !hierarchy_changed && machine_data.sized_counts == counts {
+            continue;
+        }
+        machine_data.layout_dirty = false;
+        machine_data.sized_counts = counts;
+        let reparent_drag = transient_data.and_then(|transient| transient.reparent_drag());
         
         let mut processed_entities = HashSet::new();
         
@@ -225,6 +364,16 @@ pub fn recalculate_parent_sizes(
                     continue;
                 }
                 
+                // Collapsed parents shrink to a compact box regardless of their children
+                if machine_data.collapsed.contains(&parent_entity) {
+                    if let Some(NodeType::Parent(parent_node)) = machine_data.nodes.get_mut(&parent_entity) {
+                        parent_node.entity_node.current_size = parent_node.collapsed_size();
+                    }
+                    processed_entities.insert(parent_entity);
+                    made_progress = true;
+                    continue;
+                }
+                
                 if let Ok(children) = q_children.get(parent_entity) {
                     // Check if all children have been processed (or are leaf nodes)
                     let all_children_ready = children.into_iter().all(|&child| {
@@ -235,6 +384,7 @@ pub fn recalculate_parent_sizes(
                     if all_children_ready {
                         // Collect child rectangles using an immutable borrow
                         let mut child_rects: Vec<egui::Rect> = children.into_iter()
+                            .filter(|&&child| reparent_drag != Some(child))
                             .filter_map(|&child| machine_data.nodes.get(&child))
                             .map(|node| node.current_rect())
                             .collect();
@@ -244,8 +394,17 @@ pub fn recalculate_parent_sizes(
                         }
                         
                         // Now update the parent with a mutable borrow
+                        let child_dragged = transient_data.is_some_and(|transient| {
+                            children.into_iter().any(|&child| transient.is_being_dragged(child, &editor_state.view.selected_nodes))
+                        });
                         if let Some(NodeType::Parent(parent_node)) = machine_data.nodes.get_mut(&parent_entity) {
+                            let previous_size = parent_node.entity_node.current_size;
                             parent_node.calculate_size_for_children(&child_rects);
+                            let size = &mut parent_node.entity_node.current_size;
+                            *size = size.min(MAX_PARENT_SIZE);
+                            if editor_state.contain_mode == ContainMode::Clamp && child_dragged {
+                                *size = size.max(previous_size);
+                            }
                         }
                         
                         processed_entities.insert(parent_entity);
@@ -261,6 +420,94 @@ pub fn recalculate_parent_sizes(
     }
 }
 
+/// Gap left between sibling zones pushed apart by `separate_sibling_zones`
+const SIBLING_ZONE_GAP: f32 = 10.0;
+
+/// System to push overlapping sibling parent zones apart (opt-in via `EditorState::separate_sibling_zones`)
+/// 
+/// Only zones sharing the same `StateChildOf` parent are compared. Each overlapping pair is
+/// separated along the axis of least overlap by moving one zone, with its subtree, away from
+/// the other. A zone being dragged stays under the pointer and pushes its siblings instead.
+pub fn separate_sibling_zones(
+    editor_state: Res<EditorState>,
+    mut q_sm: Query<(&mut StateMachinePersistentData, Option<&StateMachineTransientData>), With<StateMachine>>,
+    q_child_of: Query<&bevy_gearbox::StateChildOf>,
+    q_children: Query<&bevy_gearbox::StateChildren>,
+) {
+    if !editor_state.separate_sibling_zones {
+        return;
+    }
+    for open_machine in &editor_state.view.open_machines {
+        let Ok((mut machine_data, transient_data)) = q_sm.get_mut(open_machine.entity) else {
+            continue;
+        };
+        let is_dragged = |entity: Entity| transient_data.is_some_and(|transient| transient.is_being_dragged(entity, &editor_state.view.selected_nodes));
+        
+        let parents: Vec<Entity> = std::iter::once(open_machine.entity)
+            .chain(q_children.iter_descendants(open_machine.entity))
+            .filter(|entity| q_children.contains(*entity))
+            .collect();
+        for parent in parents {
+            // Children of collapsed parents are hidden and keep their layout
+            if machine_data.collapsed.contains(&parent) || machine_data.collapsed_proxy(parent, &q_child_of).is_some() {
+                continue;
+            }
+            let Ok(children) = q_children.get(parent) else { continue; };
+            let zones: Vec<Entity> = children
+                .into_iter()
+                .copied()
+                .filter(|child| matches!(machine_data.nodes.get(child), Some(NodeType::Parent(_))))
+                .collect();
+            
+            for (index, &first) in zones.iter().enumerate() {
+                for &second in &zones[index + 1..] {
+                    let (Some(first_rect), Some(second_rect)) = (
+                        machine_data.nodes.get(&first).map(|node| node.current_rect()),
+                        machine_data.nodes.get(&second).map(|node| node.current_rect()),
+                    ) else {
+                        continue;
+                    };
+                    let half_gap = SIBLING_ZONE_GAP * 0.5;
+                    let overlap = first_rect.expand(half_gap).intersect(second_rect.expand(half_gap));
+                    if !overlap.is_positive() {
+                        continue;
+                    }
+                    
+                    // Move the second zone unless it's the one under the pointer
+                    let (mover, mover_rect, other_rect) = match (is_dragged(first), is_dragged(second)) {
+                        (true, true) => continue,
+                        (false, true) => (first, first_rect, second_rect),
+                        _ => (second, second_rect, first_rect),
+                    };
+                    let away = mover_rect.center() - other_rect.center();
+                    let push = if overlap.width() <= overlap.height() {
+                        egui::Vec2::new(if away.x >= 0.0 { overlap.width() } else { -overlap.width() }, 0.0)
+                    } else {
+                        egui::Vec2::new(0.0, if away.y >= 0.0 { overlap.height() } else { -overlap.height() })
+                    };
+                    move_subtree(mover, push, &mut machine_data, &q_children);
+                    machine_data.mark_layout_dirty();
+                }
+            }
+        }
+    }
+}
+
+/// Offset a node and all of its descendants
+fn move_subtree(
+    entity: Entity,
+    delta: egui::Vec2,
+    machine_data: &mut StateMachinePersistentData,
+    q_children: &Query<&bevy_gearbox::StateChildren>,
+) {
+    for member in std::iter::once(entity).chain(q_children.iter_descendants(entity)) {
+        if let Some(node) = machine_data.nodes.get_mut(&member) {
+            let position = node.position();
+            node.set_position(position + delta);
+        }
+    }
+}
+
 fn hierarchy_depth(mut entity: Entity, q_child_of: &Query<&bevy_gearbox::StateChildOf>) -> usize {
     let mut depth = 0;
     while let Ok(rel) = q_child_of.get(entity) {
@@ -269,3 +516,114 @@ fn hierarchy_depth(mut entity: Entity, q_child_of: &Query<&bevy_gearbox::StateCh
     }
     depth
 }
+
+
+/// Horizontal spacing between laid-out siblings
+const AUTO_LAYOUT_SIBLING_SPACING: f32 = 40.0;
+
+/// Observer to arrange a machine's nodes with a layered tree layout
+///
+/// Children of each parent are placed in a single row inside the parent's content area,
+/// evenly spaced, and parents are expanded to contain their laid-out children.
+/// The root keeps its current top-left position.
+pub fn handle_auto_layout_machine(
+    auto_layout: On<AutoLayoutMachine>,
+    mut q_sm: Query<&mut StateMachinePersistentData, With<StateMachine>>,
+    q_children: Query<&bevy_gearbox::StateChildren>,
+) {
+    let root = auto_layout.entity;
+    let Ok(mut machine_data) = q_sm.get_mut(root) else {
+        warn!("⚠️ AutoLayoutMachine: no editor data for machine {:?}", root);
+        return;
+    };
+    let Some(root_position) = machine_data.nodes.get(&root).map(|node| node.position()) else {
+        return;
+    };
+
+    // Bottom-up: measure the size every subtree needs
+    let mut subtree_sizes: HashMap<Entity, egui::Vec2> = HashMap::new();
+    measure_subtree(root, &machine_data, &q_children, &mut subtree_sizes);
+
+    // Top-down: place every node inside its parent's content area
+    place_subtree(root, root_position, &mut machine_data, &q_children, &subtree_sizes);
+
+    // Reset transition pills to their midpoints so they follow the new layout
+    for transition in machine_data.visual_transitions.iter_mut() {
+        transition.event_node_offset = egui::Vec2::ZERO;
+    }
+    machine_data.mark_layout_dirty();
+
+    info!("✅ Auto-laid out {} nodes for machine {:?}", subtree_sizes.len(), root);
+}
+
+/// Compute the size needed by an entity and all of its laid-out children
+fn measure_subtree(
+    entity: Entity,
+    machine_data: &StateMachinePersistentData,
+    q_children: &Query<&bevy_gearbox::StateChildren>,
+    subtree_sizes: &mut HashMap<Entity, egui::Vec2>,
+) -> egui::Vec2 {
+    let Some(node) = machine_data.nodes.get(&entity) else {
+        return egui::Vec2::ZERO;
+    };
+
+    let children: Vec<Entity> = q_children
+        .get(entity)
+        .map(|children| children.iter().filter(|child| machine_data.nodes.contains_key(child)).collect())
+        .unwrap_or_default();
+
+    let size = match node {
+        NodeType::Parent(parent) if !children.is_empty() => {
+            let mut row_width = 0.0;
+            let mut row_height: f32 = 0.0;
+            for (index, &child) in children.iter().enumerate() {
+                let child_size = measure_subtree(child, machine_data, q_children, subtree_sizes);
+                if index > 0 {
+                    row_width += AUTO_LAYOUT_SIBLING_SPACING;
+                }
+                row_width += child_size.x;
+                row_height = row_height.max(child_size.y);
+            }
+            let content_width = (row_width + parent.child_margin.x * 2.0).max(parent.min_content_size.x);
+            let content_height = (row_height + parent.child_margin.y * 2.0).max(parent.min_content_size.y);
+            egui::Vec2::new(content_width, content_height + parent.title_bar_height)
+        }
+        _ => node.current_rect().size(),
+    };
+
+    subtree_sizes.insert(entity, size);
+    size
+}
+
+/// Position an entity at `top_left` and lay out its children in a row below its title bar
+fn place_subtree(
+    entity: Entity,
+    top_left: egui::Pos2,
+    machine_data: &mut StateMachinePersistentData,
+    q_children: &Query<&bevy_gearbox::StateChildren>,
+    subtree_sizes: &HashMap<Entity, egui::Vec2>,
+) {
+    let Some(node) = machine_data.nodes.get_mut(&entity) else {
+        return;
+    };
+    node.set_position(top_left);
+
+    let NodeType::Parent(parent) = node else {
+        return;
+    };
+    if let Some(&size) = subtree_sizes.get(&entity) {
+        parent.entity_node.current_size = size;
+    }
+    let mut cursor = top_left + egui::Vec2::new(parent.child_margin.x, parent.title_bar_height + parent.child_margin.y);
+
+    let Ok(children) = q_children.get(entity) else {
+        return;
+    };
+    for child in children.iter() {
+        let Some(&child_size) = subtree_sizes.get(&child) else {
+            continue;
+        };
+        place_subtree(child, cursor, machine_data, q_children, subtree_sizes);
+        cursor.x += child_size.x + AUTO_LAYOUT_SIBLING_SPACING;
+    }
+}
@@ -0,0 +1,344 @@
+//! Undo/redo history for editor operations
+//!
+//! This module handles:
+//! - Recording reversible operations (node moves and node deletions) on `EditorState`
+//! - Snapshotting deleted subtrees and their edges (disabled ones included) so they can be re-spawned
+//! - Ctrl+Z / Ctrl+Shift+Z hotkeys to undo and redo
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy::ecs::entity::EntityHashMap;
+use bevy::platform::collections::HashMap;
+use bevy::scene::{DynamicScene, DynamicSceneBuilder};
+use bevy_egui::{egui, EguiContext, PrimaryEguiContext};
+use bevy_gearbox::{InitialState, StateMachine};
+use crate::reflectable::deny_runtime_components;
+use bevy_gearbox::transitions::{Target, Transitions as EdgeTransitions};
+
+use crate::components::NodeType;
+use crate::editor_state::{DisabledEdge, EditorState, EditorWindow, MachineNodesPopulated, NodeDragged, NodeMoved, StateMachinePersistentData, StateMachineTransientData};
+use crate::window_management::{CurrentEditorWindow, EditorHotkeys};
+use crate::node_kind::ChildAdded;
+
+/// Maximum number of operations kept on the undo stack
+pub const MAX_HISTORY_LEN: usize = 100;
+
+/// A reversible editor operation
+pub enum EditorOperation {
+    /// A node (and implicitly its children) was moved by `delta`
+    MoveNode { entity: Entity, delta: egui::Vec2 },
+    /// A node was deleted; the snapshot is enough to re-spawn it with its subtree and edges
+    DeleteNode(DeletedNodeSnapshot),
+}
+
+/// Everything needed to rebuild a deleted node
+pub struct DeletedNodeSnapshot {
+    /// The deleted node (after undo, the re-spawned node)
+    pub root: Entity,
+    /// State machine root the node belonged to
+    pub machine: Entity,
+    /// The node's logical parent, if any
+    pub parent: Option<Entity>,
+    /// Whether the parent's InitialState pointed at the deleted node
+    pub was_initial_state: bool,
+    /// Reflected components of the subtree and every edge touching it
+    pub scene: DynamicScene,
+    /// Editor node layouts of the subtree
+    pub nodes: HashMap<Entity, NodeType>,
+}
+
+/// Bounded undo/redo stacks
+#[derive(Default)]
+pub struct EditorHistory {
+    undo_stack: VecDeque<EditorOperation>,
+    redo_stack: Vec<EditorOperation>,
+    /// Set while a redo is replaying an operation, so recording it does not clear the redo stack
+    replaying: bool,
+}
+
+impl EditorHistory {
+    /// Record a newly performed operation
+    pub fn push(&mut self, operation: EditorOperation) {
+        self.undo_stack.push_back(operation);
+        if self.undo_stack.len() > MAX_HISTORY_LEN {
+            self.undo_stack.pop_front();
+        }
+        if !self.replaying {
+            self.redo_stack.clear();
+        }
+    }
+
+    /// Whether there is anything to undo
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// Whether there is anything to redo
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Rewrite entity references after a deleted subtree was re-spawned with new ids
+    fn remap_entities(&mut self, entity_map: &EntityHashMap<Entity>) {
+        let remap = |entity: &mut Entity| {
+            if let Some(mapped) = entity_map.get(entity) {
+                *entity = *mapped;
+            }
+        };
+        for operation in self.undo_stack.iter_mut().chain(self.redo_stack.iter_mut()) {
+            match operation {
+                EditorOperation::MoveNode { entity, .. } => remap(entity),
+                EditorOperation::DeleteNode(snapshot) => {
+                    remap(&mut snapshot.root);
+                    if let Some(parent) = snapshot.parent.as_mut() {
+                        remap(parent);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// System to undo (`EditorHotkeys::undo`, Ctrl+Z) and redo (`EditorHotkeys::redo`, Ctrl+Shift+Z) editor operations
+pub fn handle_history_hotkeys(
+    mut q_editor_context: Query<&mut EguiContext, (With<EditorWindow>, Without<PrimaryEguiContext>)>,
+    current_window: Res<CurrentEditorWindow>,
+    hotkeys: Res<EditorHotkeys>,
+    mut commands: Commands,
+) {
+    let Ok(mut egui_context) = q_editor_context.get_mut(current_window.camera) else {
+        return;
+    };
+    let ctx = egui_context.get_mut();
+    if ctx.wants_keyboard_input() {
+        return;
+    }
+
+    let (undo, redo) = ctx.input(|i| (hotkeys.undo.pressed_in(i), hotkeys.redo.pressed_in(i)));
+    if undo {
+        commands.queue(undo_last_operation);
+    } else if redo {
+        commands.queue(redo_last_operation);
+    }
+}
+
+/// Observer to record finished node drags
+pub fn record_node_move(
+    node_moved: On<NodeMoved>,
+    mut editor_state: ResMut<EditorState>,
+) {
+    editor_state.history.push(EditorOperation::MoveNode {
+        entity: node_moved.entity,
+        delta: node_moved.delta,
+    });
+}
+
+/// Snapshot a node before it is deleted and record the deletion
+///
+/// Must run before the node is despawned.
+pub(crate) fn record_node_deletion(world: &mut World, entity: Entity) {
+    match snapshot_node(world, entity) {
+        Some(snapshot) => world.resource_mut::<EditorState>().history.push(EditorOperation::DeleteNode(snapshot)),
+        None => warn!("⚠️ Could not snapshot node {:?} for undo", entity),
+    }
+}
+
+/// Revert the most recent operation
+pub fn undo_last_operation(world: &mut World) {
+    let Some(operation) = world.resource_mut::<EditorState>().history.undo_stack.pop_back() else {
+        return;
+    };
+
+    match operation {
+        EditorOperation::MoveNode { entity, delta } => {
+            move_node(world, entity, -delta);
+            world.resource_mut::<EditorState>().history.redo_stack.push(EditorOperation::MoveNode { entity, delta });
+        }
+        EditorOperation::DeleteNode(snapshot) => {
+            match restore_node(world, snapshot) {
+                Ok(snapshot) => world.resource_mut::<EditorState>().history.redo_stack.push(EditorOperation::DeleteNode(snapshot)),
+                Err(e) => error!("❌ Failed to restore deleted node: {}", e),
+            }
+        }
+    }
+}
+
+/// Re-apply the most recently undone operation
+pub fn redo_last_operation(world: &mut World) {
+    let Some(operation) = world.resource_mut::<EditorState>().history.redo_stack.pop() else {
+        return;
+    };
+
+    world.resource_mut::<EditorState>().history.replaying = true;
+    match operation {
+        EditorOperation::MoveNode { entity, delta } => {
+            move_node(world, entity, delta);
+            world.resource_mut::<EditorState>().history.push(EditorOperation::MoveNode { entity, delta });
+        }
+        EditorOperation::DeleteNode(snapshot) => {
+            // Deleting again goes through the regular path, which records a fresh snapshot
+            if world.entities().contains(snapshot.root) {
+                world.trigger(crate::editor_state::DeleteNode { entity: snapshot.root });
+                world.flush();
+            }
+        }
+    }
+    world.resource_mut::<EditorState>().history.replaying = false;
+}
+
+/// Move a node and its children by `delta`
+fn move_node(world: &mut World, entity: Entity, delta: egui::Vec2) {
+    let machine = root_ancestor(world, entity);
+    let Some(mut persistent_data) = world.get_mut::<StateMachinePersistentData>(machine) else {
+        return;
+    };
+    let Some(node) = persistent_data.nodes.get_mut(&entity) else {
+        return;
+    };
+    let position = node.position();
+    node.set_position(position + delta);
+    world.trigger(NodeDragged { entity, drag_delta: delta });
+}
+
+/// Capture the node, its descendants and every edge touching them
+fn snapshot_node(world: &mut World, entity: Entity) -> Option<DeletedNodeSnapshot> {
+    if !world.entities().contains(entity) {
+        return None;
+    }
+    let machine = root_ancestor(world, entity);
+    let parent = world.get::<bevy_gearbox::StateChildOf>(entity).map(|child_of| child_of.0);
+    let was_initial_state = parent
+        .and_then(|parent| world.get::<InitialState>(parent))
+        .is_some_and(|initial| initial.0 == entity);
+
+    let states = collect_subtree(world, entity);
+
+    // Outgoing edges from the subtree and incoming edges from the rest of the machine
+    let mut edges = Vec::new();
+    for state in &states {
+        if let Some(transitions) = world.get::<EdgeTransitions>(*state) {
+            edges.extend(transitions.into_iter().copied());
+        }
+    }
+    edges.extend(disabled_edges_from(world, &states));
+    let mut q_targets = world.query::<(Entity, &Target)>();
+    for (edge, target) in q_targets.iter(world) {
+        if states.contains(&target.0) && !edges.contains(&edge) {
+            edges.push(edge);
+        }
+    }
+
+    let scene = deny_runtime_components(DynamicSceneBuilder::from_world(world))
+        .extract_entities(states.iter().chain(edges.iter()).copied())
+        .build();
+
+    let persistent_data = world.get::<StateMachinePersistentData>(machine)?;
+    let nodes = states
+        .iter()
+        .filter_map(|state| persistent_data.nodes.get(state).map(|node| (*state, node.clone())))
+        .collect();
+
+    Some(DeletedNodeSnapshot {
+        root: entity,
+        machine,
+        parent,
+        was_initial_state,
+        scene,
+        nodes,
+    })
+}
+
+/// Re-spawn a deleted subtree and its edges, returning the snapshot remapped to the new entities
+fn restore_node(world: &mut World, mut snapshot: DeletedNodeSnapshot) -> Result<DeletedNodeSnapshot, Box<dyn std::error::Error>> {
+    if world.get::<StateMachine>(snapshot.machine).is_none() {
+        return Err(format!("state machine {:?} no longer exists", snapshot.machine).into());
+    }
+
+    // Keep references to the surviving parts of the machine pointing at the same entities
+    let mut entity_map: EntityHashMap<Entity> = EntityHashMap::default();
+    entity_map.insert(snapshot.machine, snapshot.machine);
+    if let Some(parent) = snapshot.parent {
+        entity_map.insert(parent, parent);
+    }
+    if let Some(persistent_data) = world.get::<StateMachinePersistentData>(snapshot.machine) {
+        for existing in persistent_data.nodes.keys() {
+            if world.entities().contains(*existing) {
+                entity_map.insert(*existing, *existing);
+            }
+        }
+    }
+
+    snapshot.scene.write_to_world(world, &mut entity_map)?;
+    let new_root = *entity_map.get(&snapshot.root).ok_or("restored scene is missing the deleted node")?;
+
+    // Restore editor layout for the subtree
+    let nodes = std::mem::take(&mut snapshot.nodes);
+    if let Some(mut persistent_data) = world.get_mut::<StateMachinePersistentData>(snapshot.machine) {
+        for (old_entity, node) in nodes {
+            let new_entity = entity_map.get(&old_entity).copied().unwrap_or(old_entity);
+            persistent_data.nodes.insert(new_entity, node);
+        }
+    }
+
+    // Let the parent's NodeKind machine know it has a child again, then restore its InitialState
+    if let Some(parent) = snapshot.parent.filter(|parent| world.entities().contains(*parent)) {
+        let nk_root = world
+            .get::<StateMachineTransientData>(snapshot.machine)
+            .and_then(|transient| transient.node_kind_roots.get(&parent).copied());
+        if let Some(nk_root) = nk_root {
+            world.trigger(ChildAdded::new(nk_root));
+            world.flush();
+        }
+        if snapshot.was_initial_state {
+            world.entity_mut(parent).insert(InitialState(new_root));
+        }
+    }
+
+    // Build NodeKind machines for the restored nodes
+    world.trigger(MachineNodesPopulated { root: snapshot.machine });
+
+    let mut history = std::mem::take(&mut world.resource_mut::<EditorState>().history);
+    history.remap_entities(&entity_map);
+    world.resource_mut::<EditorState>().history = history;
+
+    snapshot.root = new_root;
+    info!("✅ Restored deleted node as {:?}", new_root);
+    Ok(snapshot)
+}
+
+/// Collect a state and all of its descendants via `StateChildren`
+pub(crate) fn collect_subtree(world: &World, entity: Entity) -> Vec<Entity> {
+    let mut states = Vec::new();
+    let mut to_process = vec![entity];
+    while let Some(state) = to_process.pop() {
+        if !world.entities().contains(state) || states.contains(&state) {
+            continue;
+        }
+        states.push(state);
+        if let Some(state_children) = world.get::<bevy_gearbox::StateChildren>(state) {
+            to_process.extend(state_children.into_iter().copied());
+        }
+    }
+    states
+}
+
+/// Edges disabled in the editor whose source is one of `states`
+///
+/// These have no `Source`, so they are missing from their source's `Transitions`.
+pub(crate) fn disabled_edges_from(world: &mut World, states: &[Entity]) -> Vec<Entity> {
+    let mut q_disabled = world.query::<(Entity, &DisabledEdge)>();
+    q_disabled
+        .iter(world)
+        .filter(|(_, disabled)| states.contains(&disabled.source))
+        .map(|(edge, _)| edge)
+        .collect()
+}
+
+/// Walk `StateChildOf` up to the machine root
+pub(crate) fn root_ancestor(world: &World, mut entity: Entity) -> Entity {
+    while let Some(child_of) = world.get::<bevy_gearbox::StateChildOf>(entity) {
+        entity = child_of.0;
+    }
+    entity
+}
@@ -0,0 +1,205 @@
+//! Editor layout persistence
+//!
+//! This module handles:
+//! - Serializing open machines, their canvas offsets and editor preferences (including the inspector tab and density) to a RON sidecar
+//! - Restoring them when the plugin builds and the editor window opens
+
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use bevy_gearbox::StateMachine;
+use serde::{Deserialize, Serialize};
+
+use crate::editor_state::{entity_display_name_from_world, EditorState, InspectorLayout, InspectorTab, MachineScaffoldReady, MIN_CANVAS_ZOOM, MAX_CANVAS_ZOOM, StateMachinePersistentData, StateMachineTransientData};
+
+/// Default location of the layout sidecar, relative to the working directory
+pub const DEFAULT_LAYOUT_PATH: &str = "editor_layout.ron";
+
+/// Serializable snapshot of editor layout and preferences
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EditorLayout {
+    /// Whether the first-run onboarding overlay has been dismissed
+    pub onboarding_seen: bool,
+    /// Whether initial states are drawn with the circle-and-arrow indicator
+    pub show_initial_indicators: bool,
+    /// Whether nodes and pills use the compact density
+    pub compact: bool,
+    /// Tab last selected in the entity inspector
+    pub inspector_tab: InspectorTab,
+    /// Whether the inspectors float or are docked to a side
+    pub inspector_layout: InspectorLayout,
+    /// Whether the World Inspector window was open
+    pub show_world_inspector: bool,
+    /// World Inspector window position and size as `[min_x, min_y, max_x, max_y]`
+    pub world_inspector_rect: Option<[f32; 4]>,
+    /// Machines that were open on the canvas
+    pub open_machines: Vec<LayoutOpenMachine>,
+}
+
+impl Default for EditorLayout {
+    fn default() -> Self {
+        Self {
+            onboarding_seen: false,
+            show_initial_indicators: true,
+            compact: false,
+            inspector_tab: InspectorTab::default(),
+            inspector_layout: InspectorLayout::default(),
+            show_world_inspector: false,
+            world_inspector_rect: None,
+            open_machines: Vec::new(),
+        }
+    }
+}
+
+/// Serializable form of an open machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutOpenMachine {
+    /// Entity bits of the machine root (see `Entity::to_bits`)
+    pub entity: u64,
+    /// Display name at the time the layout was saved
+    pub display_name: String,
+    /// Canvas offset for positioning this machine
+    pub canvas_offset: [f32; 2],
+    /// Canvas zoom for this machine
+    #[serde(default = "default_canvas_zoom")]
+    pub canvas_zoom: f32,
+}
+
+fn default_canvas_zoom() -> f32 {
+    1.0
+}
+
+impl EditorLayout {
+    /// Read a layout from a RON file
+    pub fn read_from_file(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(ron::from_str(&contents)?)
+    }
+
+    /// Write this layout to a RON file
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
+        let serialized = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        std::fs::write(path, serialized)?;
+        Ok(())
+    }
+}
+
+impl EditorState {
+    /// Save the current editor layout to a RON file
+    pub fn save_layout(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
+        let open_machines = self
+            .view
+            .open_machines
+            .iter()
+            .map(|machine| LayoutOpenMachine {
+                entity: machine.entity.to_bits(),
+                display_name: machine.display_name.clone(),
+                canvas_offset: [machine.canvas_offset.x, machine.canvas_offset.y],
+                canvas_zoom: machine.canvas_zoom,
+            })
+            .collect();
+        let layout = EditorLayout {
+            onboarding_seen: self.onboarding_seen,
+            show_initial_indicators: self.show_initial_indicators,
+            compact: self.compact,
+            inspector_tab: self.inspector_tab.clone(),
+            inspector_layout: self.inspector_layout,
+            show_world_inspector: self.show_world_inspector,
+            world_inspector_rect: self.world_inspector_rect.map(|rect| [rect.min.x, rect.min.y, rect.max.x, rect.max.y]),
+            open_machines,
+        };
+        layout.write_to_file(path)
+    }
+
+    /// Load an editor layout from a RON file and apply it to this state
+    ///
+    /// Open machines are not restored immediately since their entities may not exist yet;
+    /// they are kept pending until the editor window opens (see `restore_pending_layout`).
+    pub fn load_layout(&mut self, path: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
+        let layout = EditorLayout::read_from_file(path)?;
+        self.onboarding_seen = layout.onboarding_seen;
+        self.show_onboarding = !layout.onboarding_seen;
+        self.show_initial_indicators = layout.show_initial_indicators;
+        self.compact = layout.compact;
+        self.inspector_tab = layout.inspector_tab;
+        self.inspector_layout = layout.inspector_layout;
+        self.show_world_inspector = layout.show_world_inspector;
+        self.world_inspector_rect = layout
+            .world_inspector_rect
+            .map(|[min_x, min_y, max_x, max_y]| egui::Rect::from_min_max(egui::pos2(min_x, min_y), egui::pos2(max_x, max_y)));
+        self.pending_layout_machines = layout.open_machines;
+        Ok(())
+    }
+}
+
+/// Build the initial editor state, restoring the layout sidecar if one exists
+///
+/// A missing file is treated as a first launch, so the onboarding overlay is shown.
+pub(crate) fn editor_state_from_layout(path: impl AsRef<Path>) -> EditorState {
+    let path = path.as_ref();
+    let mut editor_state = EditorState {
+        layout_path: PathBuf::from(path),
+        auto_open_related: true,
+        show_initial_indicators: true,
+        ..default()
+    };
+    if !path.exists() {
+        editor_state.show_onboarding = true;
+        return editor_state;
+    }
+    if let Err(e) = editor_state.load_layout(path) {
+        warn!("⚠️ Failed to load editor layout from {:?}: {}", path, e);
+        editor_state.show_onboarding = true;
+    }
+    editor_state
+}
+
+/// Re-open machines recorded in the loaded layout
+///
+/// Machines whose entities no longer exist (or are no longer state machines) are skipped with a warning.
+/// Entity bits are only stable within a run, so a machine is also skipped when its name no longer
+/// matches the saved one (the id was recycled by an unrelated entity).
+pub(crate) fn restore_pending_layout(world: &mut World) {
+    let pending = std::mem::take(&mut world.resource_mut::<EditorState>().pending_layout_machines);
+    for saved in pending {
+        let Ok(entity) = Entity::try_from_bits(saved.entity) else {
+            warn!("⚠️ Skipping saved machine '{}': invalid entity bits {:#x}", saved.display_name, saved.entity);
+            continue;
+        };
+        if !world.entities().contains(entity) || world.get::<StateMachine>(entity).is_none() || crate::node_kind::is_editor_internal(entity, world) {
+            warn!("⚠️ Skipping saved machine '{}' ({:?}): entity no longer exists", saved.display_name, entity);
+            continue;
+        }
+        let current_name = world.get::<Name>(entity).map(|name| name.as_str().to_string());
+        let display_name = entity_display_name_from_world(entity, world);
+        if current_name.as_deref() != Some(saved.display_name.as_str()) && display_name != saved.display_name {
+            warn!(
+                "⚠️ Skipping saved machine '{}' ({:?}): entity is now '{}'",
+                saved.display_name, entity, display_name
+            );
+            continue;
+        }
+        if world.resource::<EditorState>().is_machine_open(entity) {
+            continue;
+        }
+
+        let canvas_offset = egui::Vec2::new(saved.canvas_offset[0], saved.canvas_offset[1]);
+        world
+            .resource_mut::<EditorState>()
+            .add_machine_with_offset(entity, display_name, canvas_offset);
+        if let Some(open_machine) = world.resource_mut::<EditorState>().view.open_machines.last_mut() {
+            open_machine.canvas_zoom = saved.canvas_zoom.clamp(MIN_CANVAS_ZOOM, MAX_CANVAS_ZOOM);
+        }
+
+        // Ensure scaffold, then continue the regular open cascade
+        if world.get::<StateMachinePersistentData>(entity).is_none() {
+            world.entity_mut(entity).insert(StateMachinePersistentData::default());
+        }
+        if world.get::<StateMachineTransientData>(entity).is_none() {
+            world.entity_mut(entity).insert(StateMachineTransientData::default());
+        }
+        world.trigger(MachineScaffoldReady { root: entity });
+        info!("✅ Restored machine {:?} from editor layout", entity);
+    }
+}
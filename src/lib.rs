@@ -8,9 +8,10 @@ use bevy::prelude::*;
 use bevy::platform::collections::HashSet;
 use bevy_egui::EguiContext;
 use bevy_egui::PrimaryEguiContext;
-use bevy_inspector_egui::bevy_inspector::ui_for_world;
+use bevy_inspector_egui::bevy_inspector::{ui_for_all_assets, ui_for_entities_filtered, ui_for_entity, ui_for_resources, Filter};
+use node_kind::EditorInternal;
 use bevy_gearbox::{StateMachine, InitialState};
-use bevy_gearbox::transitions::{Target, Source, EdgeKind, AlwaysEdge};
+use bevy_gearbox::transitions::{Target, Source, EdgeKind, AlwaysEdge, Transitions};
 use bevy_ecs::schedule::ScheduleLabel;
 
 // Module declarations
@@ -20,12 +21,28 @@ mod node_editor;
 mod context_menu;
 mod window_management;
 mod entity_inspector;
+mod onboarding;
+mod validation;
+mod timeline;
+mod stepping;
 pub mod components;
+pub mod layout;
+pub mod history;
+pub mod clipboard;
+pub mod export;
+pub mod alignment;
 pub mod reflectable;
 pub mod node_kind;
+pub mod send_event;
+pub mod decorations;
+#[cfg(feature = "test_support")]
+pub mod test_support;
 
 // Re-exports
 pub use editor_state::*;
+pub use send_event::{SendableEventAppExt, SendableEvents};
+pub use decorations::{NodeDecoratorAppExt, NodeDecorators};
+pub use window_management::{EditorHotkeys, KeyBinding};
 
 // Import new events - these are also re-exported by the glob import above
 // but we need them explicitly for the observers
@@ -35,11 +52,38 @@ use bevy::ecs::reflect::ReflectComponent;
 use bevy::prelude::AppTypeRegistry;
 
 /// Schedule label for the editor window context
+///
+/// Runs once per editor window, from that window's `EditorWindowPass`; during the run
+/// `CurrentEditorWindow` names the window and `EditorState` holds its view.
 #[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct EditorWindowContextPass;
 
+/// Egui multipass schedule of a single editor window (keyed by the window entity)
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EditorWindowPass(pub Entity);
+
 /// Main plugin for the Bevy Gearbox Editor
-pub struct GearboxEditorPlugin;
+pub struct GearboxEditorPlugin {
+    /// Path of the RON sidecar used to persist the editor layout between sessions
+    pub layout_path: std::path::PathBuf,
+    /// Skip the egui windows, hotkeys and inspector systems (observers and sync systems still run)
+    pub headless: bool,
+    /// Key bindings for the editor windows and canvas shortcuts (see `EditorHotkeys`)
+    pub hotkeys: window_management::EditorHotkeys,
+    /// Labels states from domain components instead of their `Name` (`None` falls back to `Name`)
+    pub label_provider: Option<LabelProvider>,
+}
+
+impl Default for GearboxEditorPlugin {
+    fn default() -> Self {
+        Self {
+            layout_path: std::path::PathBuf::from(layout::DEFAULT_LAYOUT_PATH),
+            headless: false,
+            hotkeys: window_management::EditorHotkeys::default(),
+            label_provider: None,
+        }
+    }
+}
 
 impl Plugin for GearboxEditorPlugin {
     fn build(&self, app: &mut App) {
@@ -49,31 +93,67 @@ impl Plugin for GearboxEditorPlugin {
         //     DefaultInspectorConfigPlugin,
         // ));
 
-        // Initialize resources
-        app.init_resource::<EditorState>();
+        // Initialize resources, restoring persisted layout and preferences
+        app.insert_resource(layout::editor_state_from_layout(&self.layout_path));
+        app.init_resource::<hierarchy::RootAncestorCache>();
+        app.init_resource::<ComponentTypeCache>();
+        app.init_resource::<SendableEvents>();
+        app.init_resource::<NodeDecorators>();
+        // Kept if the app already inserted its own theme or config
+        app.init_resource::<EditorTheme>();
+        app.init_resource::<EditorConfig>();
+        app.init_resource::<window_management::ActiveEditorWindow>();
+        if let Some(provider) = self.label_provider {
+            app.insert_resource(EditorLabelProvider(provider))
+                .add_systems(Update, refresh_editor_labels);
+        }
+        app.insert_resource(self.hotkeys.clone());
         // NodeKind index is now transient per-machine; no global resource
 
         // Register reflectable types for scene serialization
         app.register_type::<reflectable::ReflectableStateMachinePersistentData>()
             .register_type::<reflectable::ReflectableNode>()
             .register_type::<reflectable::ReflectableNodeType>()
-            .register_type::<reflectable::ReflectableTransitionConnection>();
+            .register_type::<reflectable::ReflectableTransitionConnection>()
+            .register_type::<EdgeLabel>()
+            .register_type::<EdgeOrder>()
+            .register_type::<DisabledEdge>();
+
+        // Editor window, egui and hotkey systems
+        if !self.headless {
+            app.add_systems(Update, (window_management::handle_editor_hotkeys, window_management::track_focused_editor_window))
+                .add_systems(Last, window_management::save_layout_on_exit)
+                .add_observer(window_management::cleanup_editor_window)
+                // Samples text focus before any widget can release it (see `handle_keyboard_navigation`)
+                .add_systems(EditorWindowContextPass, node_editor::record_text_focus.before(embedded_world_inspector_exclusive).before(entity_inspector::entity_inspector_system))
+                // Inspector edits apply before the canvas renders, so labels and initial-state
+                // indicators (looked up live from `Name` / `InitialState`) match within the frame
+                .add_systems(EditorWindowContextPass, editor_ui_system.after(embedded_world_inspector_exclusive).after(entity_inspector::entity_inspector_system))
+                // Runs after the canvas so an Enter that confirmed a rename or picked an event is already consumed
+                .add_systems(EditorWindowContextPass, node_editor::handle_keyboard_navigation.after(editor_ui_system))
+                .add_systems(EditorWindowContextPass, decorations::paint_node_decorations.after(editor_ui_system))
+                .add_systems(EditorWindowContextPass, history::handle_history_hotkeys)
+                .add_systems(EditorWindowContextPass, clipboard::handle_clipboard_hotkeys)
+                .add_systems(EditorWindowContextPass, window_management::handle_fit_to_view_hotkey)
+                .add_systems(EditorWindowContextPass, embedded_world_inspector_exclusive)
+                .add_systems(EditorWindowContextPass, entity_inspector::entity_inspector_system);
+        }
 
         // Add systems
-        app.add_systems(Update, window_management::handle_editor_hotkeys)
-            .add_observer(window_management::cleanup_editor_window)
-            .add_systems(EditorWindowContextPass, editor_ui_system)
-            .add_systems(EditorWindowContextPass, embedded_world_inspector_exclusive)
-            .add_systems(EditorWindowContextPass, entity_inspector::entity_inspector_system)
-            .add_systems(Update, (
+        app.add_systems(Update, (
                 node_editor::update_node_types,
                 hierarchy::constrain_children_to_parents,
                 hierarchy::recalculate_parent_sizes,
+                hierarchy::separate_sibling_zones,
                 update_transition_pulses,
                 update_node_pulses,
                 reflectable::sync_reflectable_on_persistent_change,
             ).chain())
-            .add_systems(Update, sync_edge_visuals_from_ecs)
+            .add_systems(Update, refresh_component_type_cache)
+            .add_systems(Update, (sync_edge_visuals_from_ecs, sync_edge_guards_from_ecs).chain().after(refresh_component_type_cache))
+            .add_systems(Update, sync_node_components_from_ecs.after(refresh_component_type_cache))
+            .add_systems(Update, node_editor::cancel_stale_transition_creation)
+            .add_systems(Update, entity_inspector::remember_inspected_entity)
             // NodeKind event listeners
             .add_observer(node_kind::on_enter_nodekind_state_parallel)
             .add_observer(node_kind::on_enter_nodekind_state_parent)
@@ -88,16 +168,36 @@ impl Plugin for GearboxEditorPlugin {
         // Add observers
         app.add_observer(context_menu::handle_context_menu_request)
             .add_observer(context_menu::handle_node_action)
+            .add_observer(context_menu::handle_add_child_at)
             .add_observer(context_menu::handle_transition_context_menu_request)
+            .add_observer(context_menu::handle_rename_transition_label_request)
+            .add_observer(context_menu::handle_toggle_orthogonal_edges)
             .add_observer(hierarchy::handle_parent_child_movement)
+            .add_observer(hierarchy::handle_auto_layout_machine)
+            .add_observer(hierarchy::handle_reparent_node)
+            .add_observer(hierarchy::invalidate_root_cache_on_insert)
+            .add_observer(hierarchy::invalidate_root_cache_on_replace)
+            .add_observer(history::record_node_move)
+            .add_observer(validation::handle_validate_machine)
+            .add_observer(export::handle_export_machine_svg)
+            .add_observer(export::handle_export_machine_dot)
+            .add_observer(export::handle_export_machine_json)
+            .add_observer(alignment::handle_align_selection)
+            .add_observer(context_menu::handle_set_node_color)
+            .add_observer(stepping::handle_toggle_pause_machine)
+            .add_observer(stepping::handle_step_machine)
             .add_observer(handle_transition_creation_request)
             .add_observer(handle_create_transition)
             .add_observer(handle_save_state_machine)
+            .add_observer(handle_load_state_machine)
             .add_observer(reflectable::on_add_reflectable_state_machine)
             .add_observer(handle_node_enter_pulse)
             .add_observer(handle_transition_actions_pulse)
             .add_observer(handle_delete_transition)
             .add_observer(handle_delete_transition_by_edge)
+            .add_observer(handle_retarget_transition)
+            .add_observer(handle_reorder_transition)
+            .add_observer(handle_toggle_transition_enabled)
             .add_observer(handle_delete_node)
             .add_observer(handle_background_context_menu_request)
             .add_observer(handle_open_machine_request)
@@ -113,19 +213,35 @@ impl Plugin for GearboxEditorPlugin {
 /// Only runs when an editor window exists
 fn editor_ui_system(
     mut q_editor_context: Query<&mut EguiContext, (With<EditorWindow>, Without<bevy_egui::PrimaryEguiContext>)>,
+    current_window: Res<window_management::CurrentEditorWindow>,
     mut editor_state: ResMut<EditorState>,
     mut q_sm_data: Query<(Entity, Option<&Name>, Option<&mut StateMachinePersistentData>, Option<&mut StateMachineTransientData>), With<StateMachine>>,
-    q_sm: Query<(Entity, Option<&Name>), With<StateMachine>>,
-    q_entities: Query<(Entity, Option<&Name>, Option<&InitialState>)>,
+    q_sm: Query<(Entity, Option<&Name>, Option<&EditorLabel>), (With<StateMachine>, Without<EditorInternal>)>,
+    q_entities: EntityNamesQuery,
     q_child_of: Query<&bevy_gearbox::StateChildOf>,
     q_children: Query<&bevy_gearbox::StateChildren>,
     q_active: Query<&bevy_gearbox::active::Active>,
     q_parallel: Query<&bevy_gearbox::Parallel>,
+    q_paused: Query<(), With<EditorPaused>>,
+    q_active_kinds: node_kind::ActiveNodeKindQuery,
+    sendable_events: Res<SendableEvents>,
+    type_registry: Res<AppTypeRegistry>,
+    (theme, config, hotkeys, mut node_rect_scratch): (Res<EditorTheme>, Res<EditorConfig>, Res<EditorHotkeys>, Local<HashMap<Entity, egui::Rect>>),
     mut commands: Commands,
 ) {
     // Only run if there's an editor window
-    if let Ok(mut egui_context) = q_editor_context.single_mut() {
+    if let Ok(mut egui_context) = q_editor_context.get_mut(current_window.camera) {
         let ctx = egui_context.get_mut();
+        let theme = theme.resolved(&ctx.style().visuals);
+        // Keep machine titles in sync with renames (e.g. from the inspector)
+        for open_machine in editor_state.view.open_machines.iter_mut() {
+            if let Ok((entity, Some(name), label)) = q_sm.get(open_machine.entity) {
+                let display_name = format_entity_name(entity, label, Some(name));
+                if open_machine.display_name != display_name {
+                    open_machine.display_name = display_name;
+                }
+            }
+        }
         // Top banner with New/Open actions
         egui::TopBottomPanel::top("canvas_banner").show(ctx, |ui| {
             egui::Frame::NONE.show(ui, |ui| {
@@ -137,7 +253,7 @@ fn editor_ui_system(
                             Name::new("New Machine"),
                         )).id();
                         // Place near top-left of canvas as default for banner action
-                        commands.trigger(OpenMachineRequested { entity: new_entity, position: None });
+                        commands.trigger(OpenMachineRequested { entity: new_entity, position: None, focus_child: None });
                     }
                     // Open menu toggle button
                     let open_btn_resp = ui.button("Open");
@@ -153,57 +269,272 @@ fn editor_ui_system(
                     if ui.button(label).clicked() {
                         editor_state.show_world_inspector = !editor_state.show_world_inspector;
                     }
+                    egui::ComboBox::from_id_salt("inspector_layout")
+                        .selected_text(editor_state.inspector_layout.label())
+                        .show_ui(ui, |ui| {
+                            for layout in [InspectorLayout::Floating, InspectorLayout::DockedRight, InspectorLayout::DockedLeft] {
+                                ui.selectable_value(&mut editor_state.inspector_layout, layout, layout.label());
+                            }
+                        })
+                        .response
+                        .on_hover_text("Where the inspectors are shown; docked panels shrink the canvas instead of covering it");
+                    ui.separator();
+                    // Grid snapping toggle (hold Alt while dragging to bypass)
+                    let mut snap_enabled = editor_state.grid_snap.is_some();
+                    if ui.checkbox(&mut snap_enabled, "Snap to grid").on_hover_text("Hold Alt while dragging to place freely").changed() {
+                        editor_state.grid_snap = if snap_enabled { Some(DEFAULT_GRID_SIZE) } else { None };
+                    }
+                    if snap_enabled {
+                        ui.checkbox(&mut editor_state.show_grid, "Show grid");
+                    }
+                    ui.checkbox(&mut editor_state.snap_pills_to_line, "Snap pills to line")
+                        .on_hover_text("Dropped transition pills slide back onto their connection line; hold Alt to place freely");
+                    ui.checkbox(&mut editor_state.show_entity_ids, "Show entity IDs");
+                    if ui.checkbox(&mut editor_state.compact, "Compact")
+                        .on_hover_text("Smaller node padding, fonts and pills for large machines")
+                        .changed()
+                    {
+                        if let Err(e) = editor_state.save_layout(&editor_state.layout_path) {
+                            warn!("⚠️ Failed to save editor layout to {:?}: {}", editor_state.layout_path, e);
+                        }
+                    }
+                    if ui.checkbox(&mut editor_state.show_initial_indicators, "Initial arrows")
+                        .on_hover_text("When off, initial states are marked with a thin left border instead")
+                        .changed()
+                    {
+                        if let Err(e) = editor_state.save_layout(&editor_state.layout_path) {
+                            warn!("⚠️ Failed to save editor layout to {:?}: {}", editor_state.layout_path, e);
+                        }
+                    }
+                    ui.checkbox(&mut editor_state.show_transition_timeline, "Timeline");
+                    ui.checkbox(&mut editor_state.follow_active, "Follow active")
+                        .on_hover_text("Pan to keep the most recently entered state centered while the machine runs");
+                    ui.checkbox(&mut editor_state.show_send_event_panel, "Send event")
+                        .on_hover_text("Fire events at a running machine to test its transitions");
+                    ui.checkbox(&mut editor_state.auto_open_related, "Auto-open related")
+                        .on_hover_text("When off, related machines appear as chips under their origin to open on demand");
+                    let mut grow_parents = editor_state.contain_mode == ContainMode::Grow;
+                    if ui.checkbox(&mut grow_parents, "Grow parents")
+                        .on_hover_text("When off, a dragged state stops at its parent's edge instead of enlarging it")
+                        .changed()
+                    {
+                        editor_state.contain_mode = if grow_parents { ContainMode::Grow } else { ContainMode::Clamp };
+                    }
+                    ui.checkbox(&mut editor_state.separate_sibling_zones, "Separate zones")
+                        .on_hover_text("Push overlapping sibling parent states apart");
+                    ui.separator();
+                    // Pause/step transition feedback of the selected machine
+                    if let Some(machine) = editor_state.view.selected_entity.map(|entity| q_child_of.root_ancestor(entity)) {
+                        let buffered = q_sm_data
+                            .get(machine)
+                            .ok()
+                            .and_then(|(_, _, _, transient)| transient.map(|t| t.paused_transitions.len()))
+                            .unwrap_or(0);
+                        if q_paused.contains(machine) {
+                            if ui.button("▶ Resume").on_hover_text("Release all buffered transitions to the timeline").clicked() {
+                                commands.trigger(TogglePauseMachine { entity: machine });
+                            }
+                            if ui.add_enabled(buffered > 0, egui::Button::new(format!("⏭ Step ({})", buffered))).clicked() {
+                                commands.trigger(StepMachine { entity: machine });
+                            }
+                        } else if ui.button("⏸ Pause").on_hover_text("Buffer this machine's transitions and replay them one at a time").clicked() {
+                            commands.trigger(TogglePauseMachine { entity: machine });
+                        }
+                        ui.separator();
+                    }
+                    render_node_search(ui, &mut editor_state, &q_sm_data, &q_entities, &mut commands);
                 });
             });
         });
 
+        // Shown before the canvas so the CentralPanel leaves room for it
+        render_status_bar(ctx, &editor_state, &q_sm_data, &q_child_of, &q_children, &q_active, &q_paused);
+
         egui::CentralPanel::default().show(ctx, |ui| {
             // Track canvas origin in screen coordinates for later conversions
-            editor_state.canvas_origin = Some(ui.min_rect().min);
+            editor_state.view.canvas_origin = Some(ui.min_rect().min);
+            editor_state.view.canvas_rect = Some(ui.max_rect());
+            // Center a node picked from the search box
+            if let Some((machine, node)) = editor_state.view.pending_node_focus {
+                let persistent_data = q_sm_data.get(machine).ok().and_then(|(_, _, persistent_data, _)| persistent_data);
+                // A freshly opened machine may not have its nodes yet; keep the request until it does
+                if let Some(persistent_data) = persistent_data.filter(|data| !data.nodes.is_empty()) {
+                    editor_state.view.pending_node_focus = None;
+                    if let Some(node_center) = persistent_data.nodes.get(&node).map(|n| n.current_rect().center()) {
+                        editor_state.center_machine_on(machine, node_center, ui.max_rect().center());
+                    }
+                } else if !editor_state.is_machine_open(machine) {
+                    editor_state.view.pending_node_focus = None;
+                }
+            }
+            // Follow active: ease toward the latest active leaf, holding off while the pointer is dragging
+            if let Some((machine, node)) = editor_state.view.follow_target.filter(|_| editor_state.follow_active) {
+                let node_center = q_sm_data
+                    .get(machine)
+                    .ok()
+                    .and_then(|(_, _, persistent_data, _)| persistent_data)
+                    .and_then(|persistent_data| {
+                        let shown = persistent_data.collapsed_proxy(node, &q_child_of).unwrap_or(node);
+                        persistent_data.node_rect(shown)
+                    })
+                    .map(|rect| rect.center());
+                match node_center {
+                    Some(node_center) if !ui.input(|i| i.pointer.any_down()) => {
+                        let t = if config.disable_animations {
+                            1.0
+                        } else {
+                            1.0 - (-FOLLOW_ACTIVE_SPEED * ui.input(|i| i.stable_dt)).exp()
+                        };
+                        if editor_state.ease_machine_toward(machine, node_center, ui.max_rect().center(), t) {
+                            editor_state.view.follow_target = None;
+                        } else {
+                            ui.ctx().request_repaint();
+                        }
+                    }
+                    Some(_) => {}
+                    None => editor_state.view.follow_target = None,
+                }
+            } else {
+                editor_state.view.follow_target = None;
+            }
+            // Frame every open machine after the fit-to-view hotkey
+            if std::mem::take(&mut editor_state.view.fit_to_view_requested) {
+                window_management::fit_open_machines_to_view(
+                    &mut editor_state,
+                    |machine| {
+                        let (_, _, persistent_data, _) = q_sm_data.get(machine).ok()?;
+                        let persistent_data = persistent_data?;
+                        persistent_data
+                            .nodes
+                            .iter()
+                            .filter(|(entity, _)| persistent_data.collapsed_proxy(**entity, &q_child_of).is_none())
+                            .map(|(_, node)| node.current_rect())
+                            .reduce(|a, b| a.union(b))
+                    },
+                    ui.max_rect(),
+                );
+            }
+            // Render the snapping grid behind all machines
+            if let Some(grid_size) = editor_state.grid_snap.filter(|_| editor_state.show_grid) {
+                let transform = editor_state.view.open_machines.first().map(|m| m.canvas_transform()).unwrap_or_default();
+                node_editor::render_grid_dots(ui, grid_size, transform, ui.max_rect().min, theme.grid);
+            }
+            // Ctrl+scroll (or pinch) zooms the canvas around the cursor
+            let canvas_rect = ui.max_rect();
+            let zoom_delta = ui.input(|i| i.zoom_delta());
+            if zoom_delta != 1.0 {
+                if let Some(pointer_pos) = ui.input(|i| i.pointer.hover_pos()).filter(|p| canvas_rect.contains(*p)) {
+                    editor_state.zoom_canvas_at(zoom_delta, pointer_pos, canvas_rect.min);
+                }
+            }
+            // Empty-canvas drags draw a selection box (or pan with middle mouse / Space); allocated before the machines so nodes take precedence
+            let background_response = ui.interact(canvas_rect, egui::Id::new("canvas_background"), egui::Sense::drag());
             // Render each open machine directly on the canvas
-            for open_machine in &editor_state.open_machines.clone() {
+            let mut related_to_open: Option<(Entity, Entity)> = None;
+            // Only shared reads of editor_state happen in this loop (changes are deferred), so no copy is needed
+            for open_machine in &editor_state.view.open_machines {
                 if let Ok((sm_entity, _, persistent_data_opt, transient_data_opt)) = q_sm_data.get_mut(open_machine.entity) {
                     // Ensure the machine has both components
-                    if persistent_data_opt.is_none() {
-                        commands.entity(open_machine.entity).insert(StateMachinePersistentData::default());
-                        continue;
-                    }
-                    if transient_data_opt.is_none() {
-                        commands.entity(open_machine.entity).insert(StateMachineTransientData::default());
-                        continue;
-                    }
-                    
-                    let (_, _, Some(mut persistent_data), Some(mut transient_data)) = q_sm_data.get_mut(open_machine.entity).unwrap() else {
+                    let (Some(mut persistent_data), Some(mut transient_data)) = (persistent_data_opt, transient_data_opt) else {
+                        commands.entity(open_machine.entity).insert_if_new((
+                            StateMachinePersistentData::default(),
+                            StateMachineTransientData::default(),
+                        ));
                         continue;
                     };
                     
-                    // Apply canvas offset to all node positions before rendering
-                    apply_canvas_offset_to_nodes(&mut persistent_data, open_machine.canvas_offset);
+                    // Render into a layer panned and scaled by the machine's canvas transform; egui maps input
+                    // through the same transform, so stored node positions are never shifted and no early
+                    // `continue` or panic can leave them offset
+                    let transform = open_machine.layer_transform(canvas_rect.min);
+                    let layer_id = egui::LayerId::new(ui.layer_id().order, egui::Id::new("machine_canvas_layer").with(sm_entity));
+                    ui.ctx().set_sublayer(ui.layer_id(), layer_id);
+                    ui.ctx().set_transform_layer(layer_id, transform);
+                    let layer_rect = transform.inverse() * canvas_rect;
+                    let mut machine_ui = ui.new_child(egui::UiBuilder::new().layer_id(layer_id).max_rect(layer_rect));
+                    machine_ui.set_clip_rect(layer_rect);
                     
                     // Show the machine editor directly on the main canvas
                     node_editor::show_single_machine_on_canvas(
-                        ui,
+                        &mut machine_ui,
                         &mut persistent_data,
                         &mut transient_data,
                         sm_entity,
-                        editor_state.selected_entity,
+                        editor_state.view.selected_entity,
+                        &editor_state.view.selected_nodes,
                         &q_entities,
                         &q_child_of,
                         &q_children,
                         &q_active,
                         &q_parallel,
+                        editor_state.grid_snap,
+                        editor_state.show_entity_ids,
+                        editor_state.show_initial_indicators,
+                        editor_state.send_event_preview.as_deref(),
+                        &editor_state.breakpoints,
+                        editor_state.snap_pills_to_line,
+                        editor_state.compact,
+                        config.allow_self_transitions,
+                        &theme,
+                        &mut node_rect_scratch,
                         &mut commands,
                     );
                     
-                    // Remove canvas offset after rendering to keep stored positions clean
-                    remove_canvas_offset_from_nodes(&mut persistent_data, open_machine.canvas_offset);
+                    // Related machines waiting to be opened by hand
+                    if let Some(root_rect) = persistent_data.nodes.get(&sm_entity).map(|node| node.current_rect()) {
+                        if let Some(target) = render_related_chips(&mut machine_ui, &editor_state, sm_entity, root_rect, &q_entities) {
+                            related_to_open = Some((sm_entity, target));
+                        }
+                    }
                 }
             }
             
+            if let Some((origin, target)) = related_to_open {
+                let display_name = entity_display_name(target, &q_entities);
+                editor_state.open_related_machine(origin, target, display_name, &config);
+                info!("🔗 Opened related machine {:?} from origin {:?}", target, origin);
+            }
+            
             // Handle background interactions after node/transition interactions so suppression can take effect
-            handle_background_interactions(ui, &mut editor_state, &mut commands);
+            handle_background_interactions(ui, &background_response, &mut editor_state, &mut q_sm_data, &mut commands);
             
             // Render context menus
+            // Size of the multi-selection in the machine the context menu belongs to
+            let multi_selection_len = editor_state
+                .context_menu_entity
+                .map(|entity| q_child_of.root_ancestor(entity))
+                .map(|machine| {
+                    editor_state.view.selected_nodes.iter().filter(|selected| q_child_of.root_ancestor(**selected) == machine).count()
+                })
+                .unwrap_or(0);
+            // Evaluation order of the transition the context menu belongs to, if it shares its event
+            let edge_order_badge = editor_state
+                .transition_context_menu
+                .as_ref()
+                .and_then(|(source, _, _, edge)| {
+                    let (_, _, persistent_data, _) = q_sm_data.get(q_child_of.root_ancestor(*source)).ok()?;
+                    persistent_data?.visual_transitions.iter().find(|t| t.edge_entity == *edge)?.order_badge
+                });
+            let edge_disabled = editor_state
+                .transition_context_menu
+                .as_ref()
+                .is_some_and(|(source, _, _, edge)| {
+                    q_sm_data
+                        .get(q_child_of.root_ancestor(*source))
+                        .ok()
+                        .and_then(|(_, _, persistent_data, _)| persistent_data)
+                        .is_some_and(|persistent_data| persistent_data.visual_transitions.iter().any(|t| t.edge_entity == *edge && t.is_disabled))
+                });
+            // Kind of the right-clicked state (from its NodeKind machine) and how many children it has
+            let node_kind = editor_state.context_menu_entity.and_then(|entity| {
+                let (_, _, _, transient_data) = q_sm_data.get(q_child_of.root_ancestor(entity)).ok()?;
+                let nk_root = *transient_data?.node_kind_roots.get(&entity)?;
+                node_kind::active_node_kind(nk_root, &q_active_kinds)
+            });
+            let child_count = editor_state
+                .context_menu_entity
+                .and_then(|entity| q_children.get(entity).ok())
+                .map_or(0, |children| children.into_iter().count());
             context_menu::render_context_menu(
                 ctx,
                 &mut editor_state,
@@ -211,6 +542,11 @@ fn editor_ui_system(
                 &q_entities,
                 &q_child_of,
                 &q_parallel,
+                multi_selection_len,
+                edge_order_badge,
+                edge_disabled,
+                node_kind,
+                child_count,
             );
             
             // Render background context menu
@@ -218,6 +554,7 @@ fn editor_ui_system(
                 ctx,
                 &mut editor_state,
                 &q_sm,
+                &config,
                 &mut commands,
             );
 
@@ -228,15 +565,159 @@ fn editor_ui_system(
                 &q_sm,
                 &mut commands,
             );
+
+            // Recent transitions, newest first
+            timeline::render_transition_timeline(ctx, &mut editor_state, &mut q_sm_data, &q_entities, &config, &mut commands);
+
+            // Manually fire events at a running machine
+            if editor_state.show_send_event_panel {
+                let discovered = edge_event_type_names(&type_registry);
+                let selected_machine = editor_state.view.selected_entity.map(|entity| q_child_of.root_ancestor(entity));
+                send_event::render_send_event_panel(ctx, &mut editor_state, &sendable_events, &discovered, selected_machine, &mut commands);
+            }
+
+            // Ask before deleting a state together with its children
+            context_menu::render_delete_confirmation(ctx, &mut editor_state, &mut commands, &q_entities);
+
+            // Render first-run onboarding overlay on top of everything else
+            onboarding::render_onboarding_overlay(ctx, &mut editor_state, &theme, &hotkeys);
         });
     }
 }
 
+/// Render the bottom status bar with stats for the selected machine (else the first open one)
+fn render_status_bar(
+    ctx: &egui::Context,
+    editor_state: &EditorState,
+    q_sm_data: &Query<(Entity, Option<&Name>, Option<&mut StateMachinePersistentData>, Option<&mut StateMachineTransientData>), With<StateMachine>>,
+    q_child_of: &Query<&bevy_gearbox::StateChildOf>,
+    q_children: &Query<&bevy_gearbox::StateChildren>,
+    q_active: &Query<&bevy_gearbox::active::Active>,
+    q_paused: &Query<(), With<EditorPaused>>,
+) {
+    let machine = editor_state
+        .view
+        .selected_entity
+        .map(|entity| q_child_of.root_ancestor(entity))
+        .filter(|machine| editor_state.is_machine_open(*machine))
+        .or_else(|| editor_state.view.open_machines.first().map(|machine| machine.entity));
+
+    egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            let Some(machine) = machine else {
+                ui.weak("No machine open");
+                return;
+            };
+            let name = editor_state
+                .view
+                .open_machines
+                .iter()
+                .find(|open_machine| open_machine.entity == machine)
+                .map_or_else(|| format!("{:?}", machine), |open_machine| open_machine.display_name.clone());
+            let states: Vec<Entity> = std::iter::once(machine).chain(q_children.iter_descendants(machine)).collect();
+            let active = states.iter().filter(|state| q_active.contains(**state)).count();
+            let transitions = q_sm_data
+                .get(machine)
+                .ok()
+                .and_then(|(_, _, persistent_data, _)| persistent_data)
+                .map_or(0, |persistent_data| persistent_data.visual_transitions.len());
+
+            ui.strong(name);
+            ui.separator();
+            ui.label(format!("{} states", states.len()));
+            ui.separator();
+            ui.label(format!("{} transitions", transitions));
+            ui.separator();
+            ui.label(format!("{} active", active));
+            if q_paused.contains(machine) {
+                ui.separator();
+                ui.colored_label(egui::Color32::from_rgb(255, 180, 80), "⏸ Paused");
+            }
+        });
+    });
+}
+
+/// Render the node search field and its results, grouped by open machine
+fn render_node_search(
+    ui: &mut egui::Ui,
+    editor_state: &mut EditorState,
+    q_sm_data: &Query<(Entity, Option<&Name>, Option<&mut StateMachinePersistentData>, Option<&mut StateMachineTransientData>), With<StateMachine>>,
+    q_entities: &EntityNamesQuery,
+    commands: &mut Commands,
+) {
+    let search_response = ui.add_sized(
+        [180.0, 20.0],
+        egui::TextEdit::singleline(&mut editor_state.node_search_text)
+            .hint_text("Find state...")
+            .id_salt("node_search"),
+    );
+    if search_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+        editor_state.node_search_text.clear();
+    }
+    if editor_state.node_search_text.trim().is_empty() {
+        return;
+    }
+
+    // Case-insensitive substring match against node names, grouped by machine
+    let query = editor_state.node_search_text.trim().to_lowercase();
+    let mut groups: Vec<(Entity, String, Vec<(Entity, String)>)> = Vec::new();
+    for open_machine in &editor_state.view.open_machines {
+        let Ok((_, _, Some(persistent_data), _)) = q_sm_data.get(open_machine.entity) else { continue; };
+        let mut matches: Vec<(Entity, String)> = persistent_data
+            .nodes
+            .keys()
+            .map(|entity| (*entity, entity_display_name(*entity, q_entities)))
+            .filter(|(_, name)| name.to_lowercase().contains(&query))
+            .collect();
+        if matches.is_empty() { continue; }
+        matches.sort_by(|a, b| a.1.cmp(&b.1));
+        groups.push((open_machine.entity, open_machine.display_name.clone(), matches));
+    }
+
+    let mut picked: Option<(Entity, Entity)> = None;
+    egui::Area::new(egui::Id::new("node_search_results"))
+        .fixed_pos(search_response.rect.left_bottom() + egui::vec2(0.0, 4.0))
+        .order(egui::Order::Foreground)
+        .show(ui.ctx(), |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.set_min_width(220.0);
+                if groups.is_empty() {
+                    ui.label("No matching states");
+                    return;
+                }
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    for (machine, machine_name, matches) in &groups {
+                        ui.label(egui::RichText::new(machine_name).strong());
+                        for (entity, name) in matches {
+                            let mut job = egui::text::LayoutJob::default();
+                            job.append(name, 0.0, egui::TextFormat::default());
+                            job.append("  ", 0.0, egui::TextFormat::default());
+                            job.append(&format!("{:?}", entity), 0.0, egui::TextFormat {
+                                font_id: egui::FontId::monospace(12.0),
+                                color: ui.visuals().weak_text_color(),
+                                ..Default::default()
+                            });
+                            if ui.add(egui::Button::new(job)).clicked() {
+                                picked = Some((*machine, *entity));
+                            }
+                        }
+                    }
+                });
+            });
+        });
+
+    if let Some((machine, entity)) = picked {
+        editor_state.view.pending_node_focus = Some((machine, entity));
+        editor_state.node_search_text.clear();
+        commands.trigger(Select { selected: Some(entity) });
+    }
+}
+
 /// Render the persistent Open menu anchored near the top toolbar
 fn render_open_menu(
     ctx: &egui::Context,
     editor_state: &mut EditorState,
-    q_sm: &Query<(Entity, Option<&Name>), With<StateMachine>>,
+    q_sm: &Query<(Entity, Option<&Name>, Option<&EditorLabel>), (With<StateMachine>, Without<EditorInternal>)>,
     commands: &mut Commands,
 ) {
     if !editor_state.show_open_menu {
@@ -263,13 +744,9 @@ fn render_open_menu(
                 );
 
                 let mut items: Vec<(Entity, String)> = Vec::new();
-                for (entity, name_opt) in q_sm.iter() {
+                for (entity, name_opt, label_opt) in q_sm.iter() {
                     if editor_state.is_machine_open(entity) { continue; }
-                    if let Some(name) = name_opt {
-                        if name.as_str() == "NodeKind" { continue; }
-                    }
-                    let display_name = if let Some(name) = name_opt { name.as_str().to_string() } else { format!("Unnamed Machine") };
-                    items.push((entity, display_name));
+                    items.push((entity, format_entity_name(entity, label_opt, name_opt)));
                 }
                 if !editor_state.machine_search_text.is_empty() {
                     let q = editor_state.machine_search_text.to_lowercase();
@@ -293,7 +770,7 @@ fn render_open_menu(
                                     ..Default::default()
                                 });
                                 if ui.add(egui::Button::new(job)).clicked() {
-                                    commands.trigger(OpenMachineRequested { entity: *entity, position: None });
+                                    commands.trigger(OpenMachineRequested { entity: *entity, position: None, focus_child: None });
                                     editor_state.show_open_menu = false;
                                 }
                             }
@@ -309,7 +786,7 @@ fn render_open_menu(
                                 ..Default::default()
                             });
                             if ui.add(egui::Button::new(job)).clicked() {
-                                commands.trigger(OpenMachineRequested { entity: *entity, position: None });
+                                commands.trigger(OpenMachineRequested { entity: *entity, position: None, focus_child: None });
                                 editor_state.show_open_menu = false;
                             }
                         }
@@ -339,30 +816,110 @@ fn render_open_menu(
 fn embedded_world_inspector_exclusive(world: &mut World) {
     // Query EguiContext for the editor window, clone the egui Context to end the borrow before using world again
     let ctx_opt = {
+        let camera = world.resource::<window_management::CurrentEditorWindow>().camera;
         let mut query = world.query_filtered::<&mut EguiContext, (With<EditorWindow>, Without<PrimaryEguiContext>)>();
-        query.iter_mut(world).next().map(|mut egui_context| egui_context.get_mut().clone())
+        query.get_mut(world, camera).ok().map(|mut egui_context| egui_context.get_mut().clone())
     };
     if let Some(ctx) = ctx_opt {
         let show = world.resource::<EditorState>().show_world_inspector;
         if show {
-            egui::Window::new("World Inspector").default_open(true).show(&ctx, |ui| {
+            // Machine whose subtree the scoped view shows: the selection's, else the first open one
+            let scoped_machine = {
+                let editor_state = world.resource::<EditorState>();
+                editor_state
+                    .scope_inspector_to_machine
+                    .then(|| editor_state.view.selected_entity.or_else(|| editor_state.view.open_machines.first().map(|m| m.entity)))
+                    .flatten()
+            };
+            let (saved_rect, inspector_layout) = {
+                let editor_state = world.resource::<EditorState>();
+                (editor_state.world_inspector_rect, editor_state.inspector_layout)
+            };
+            let mut window = egui::Window::new("World Inspector")
+                .id(egui::Id::new("world_inspector_window"))
+                .default_open(true)
+                .resizable(true)
+                .default_size(egui::vec2(360.0, 480.0));
+            // Restore the saved geometry; egui keeps it in memory afterwards
+            if let Some(rect) = saved_rect {
+                window = window.default_rect(rect);
+            }
+            let mut open = true;
+            let floating_rect = entity_inspector::show_inspector_frame(&ctx, inspector_layout, window, "world_inspector_panel", "World Inspector", &mut open, |ui| {
+            let mut scoped = world.resource::<EditorState>().scope_inspector_to_machine;
+            if ui.checkbox(&mut scoped, "Only the selected machine").changed() {
+                world.resource_mut::<EditorState>().scope_inspector_to_machine = scoped;
+            }
+            ui.separator();
             egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
-                ui_for_world(world, ui);
+                if scoped {
+                    match scoped_machine.filter(|entity| world.entities().contains(*entity)) {
+                        Some(entity) => {
+                            let root = history::root_ancestor(world, entity);
+                            for entity in machine_subtree_entities(world, root) {
+                                let name = entity_display_name_from_world(entity, world);
+                                egui::CollapsingHeader::new(format!("{} ({:?})", name, entity))
+                                    .id_salt(("scoped_inspector", entity))
+                                    .show(ui, |ui| {
+                                        ui_for_entity(world, entity, ui);
+                                    });
+                            }
+                        }
+                        None => {
+                            ui.weak("Select a state or open a machine to inspect it.");
+                        }
+                    }
+                    return;
+                }
+                // Like `ui_for_world`, but without the editor's internal entities
+                egui::CollapsingHeader::new("Entities").default_open(true).show(ui, |ui| {
+                    let filter = Filter::<Without<EditorInternal>>::from_ui_fuzzy(ui, egui::Id::new("world_inspector_filter"));
+                    ui_for_entities_filtered(world, ui, false, &filter);
+                });
+                egui::CollapsingHeader::new("Resources").show(ui, |ui| {
+                    ui_for_resources(world, ui);
+                });
+                egui::CollapsingHeader::new("Assets").show(ui, |ui| {
+                    ui_for_all_assets(world, ui);
+                });
             });
             });
+
+            let mut editor_state = world.resource_mut::<EditorState>();
+            if let Some(rect) = floating_rect {
+                if editor_state.world_inspector_rect != Some(rect) {
+                    editor_state.world_inspector_rect = Some(rect);
+                }
+            }
+            if !open {
+                editor_state.show_world_inspector = false;
+            }
         }
     }
 }
 
+/// A machine root, its `StateChildren` descendants, and the transition edges leaving them
+fn machine_subtree_entities(world: &World, root: Entity) -> Vec<Entity> {
+    let states = history::collect_subtree(world, root);
+    let edges: Vec<Entity> = states
+        .iter()
+        .filter_map(|state| world.get::<bevy_gearbox::transitions::Transitions>(*state))
+        .flat_map(|transitions| transitions.into_iter().copied())
+        .collect();
+    states.into_iter().chain(edges).collect()
+}
+
 /// Observer to handle transition creation requests
 fn handle_transition_creation_request(
     transition_creation_requested: On<TransitionCreationRequested>,
     mut q_sm: Query<&mut StateMachineTransientData, With<StateMachine>>,
     q_child_of: Query<&bevy_gearbox::StateChildOf>,
+    mut root_cache: ResMut<hierarchy::RootAncestorCache>,
     type_registry: Res<AppTypeRegistry>,
+    mut commands: Commands,
 ) {
     // Resolve the state machine root via relationships
-    let selected_machine = q_child_of.root_ancestor(transition_creation_requested.source_entity);
+    let selected_machine = root_cache.cached_root_ancestor(transition_creation_requested.source_entity, &q_child_of);
     
     let Ok(mut transient_data) = q_sm.get_mut(selected_machine) else {
         return;
@@ -373,6 +930,14 @@ fn handle_transition_creation_request(
     
     // Discover available event types for EventEdge
     discover_transition_edge_listener_event_types(&mut transient_data.transition_creation, &type_registry);
+    
+    // Rank the event types this machine already listens for ahead of the rest
+    commands.queue(move |world: &mut World| {
+        let used = machine_event_types(world, selected_machine);
+        if let Some(mut transient_data) = world.get_mut::<StateMachineTransientData>(selected_machine) {
+            transient_data.transition_creation.machine_event_types = used;
+        }
+    });
 }
 
 /// Observer to handle transition creation with selected event type
@@ -380,10 +945,11 @@ fn handle_create_transition(
     create_transition: On<CreateTransition>,
     mut q_sm: Query<(&mut StateMachineTransientData, &mut StateMachinePersistentData), With<StateMachine>>,
     q_child_of: Query<&bevy_gearbox::StateChildOf>,
+    mut root_cache: ResMut<hierarchy::RootAncestorCache>,
     mut commands: Commands,
 ) {
     // Resolve the state machine root via relationships
-    let selected_machine = q_child_of.root_ancestor(create_transition.source_entity);
+    let selected_machine = root_cache.cached_root_ancestor(create_transition.source_entity, &q_child_of);
     
     let Ok((mut transient_data, mut persistent_data)) = q_sm.get_mut(selected_machine) else {
         return;
@@ -393,11 +959,12 @@ fn handle_create_transition(
     let source = create_transition.source_entity;
     let target = create_transition.target_entity;
     let event_type = create_transition.event_type.clone();
+    let internal = create_transition.internal;
 
     let edge_entity = commands.spawn_empty().id();
     
     commands.queue(move |world: &mut World| {
-        match create_transition_edge_entity(world, edge_entity, source, target, &event_type) {
+        match create_transition_edge_entity(world, edge_entity, source, target, &event_type, internal) {
             Ok(edge) => {
                 info!("✅ Created transition edge {:?} for {:?} -> {:?} ({})", edge, source, target, event_type);
             }
@@ -431,6 +998,14 @@ fn handle_create_transition(
             event_node_position: initial_event_position,
             is_dragging_event_node: false,
             event_node_offset: egui::Vec2::ZERO, // Initially at midpoint
+            sibling_index: 0,
+            sibling_count: 1,
+            guards: Vec::new(),
+            is_always: create_transition.event_type == "Always",
+            is_internal: create_transition.internal,
+            order_badge: None,
+            listener_event: create_transition.event_type.clone(),
+            is_disabled: false,
         });
     }
 }
@@ -440,33 +1015,70 @@ fn discover_transition_edge_listener_event_types(
     transition_state: &mut TransitionCreationState,
     type_registry: &AppTypeRegistry,
 ) {
+    let mut event_types = edge_event_type_names(type_registry);
+    
+    // Prepend a default "Always" option
+    if !event_types.iter().any(|e| e == "Always") {
+        event_types.insert(0, "Always".to_string());
+    }
+    transition_state.available_event_types = event_types;
+}
+
+/// Sorted short names of every event type an `EventEdge<E>` is registered for
+fn edge_event_type_names(type_registry: &AppTypeRegistry) -> Vec<String> {
     let registry = type_registry.read();
     let mut event_types = Vec::new();
-    
     for registration in registry.iter() {
-        let type_path = registration.type_info().type_path();
-        
-        // Look for EventEdge<EventType> patterns
-        if let Some(start) = type_path.find("EventEdge<") {
-            if let Some(end) = type_path[start..].find('>') {
-                let event_type = &type_path[start + 10..start + end]; // 10 = len("EventEdge<")
-                
-                // Skip generic parameters and extract just the event type name
-                if let Some(last_part) = event_type.split("::").last() {
-                    if !event_types.contains(&last_part.to_string()) {
-                        event_types.push(last_part.to_string());
-                    }
-                }
+        if let Some(event_type) = event_edge_type_name(registration.type_info().type_path()) {
+            if !event_types.contains(&event_type) {
+                event_types.push(event_type);
             }
         }
     }
-    
-    // Sort for consistent ordering and prepend a default "Always" option
     event_types.sort();
-    if !event_types.iter().any(|e| e == "Always") {
-        event_types.insert(0, "Always".to_string());
+    event_types
+}
+
+/// Short event type name of an `EventEdge<EventType>` type path
+fn event_edge_type_name(type_path: &str) -> Option<String> {
+    // Look for EventEdge<EventType> patterns
+    let start = type_path.find("EventEdge<")?;
+    let end = type_path[start..].find('>')?;
+    let event_type = &type_path[start + 10..start + end]; // 10 = len("EventEdge<")
+    
+    // Skip generic parameters and extract just the event type name
+    event_type.split("::").last().map(str::to_string)
+}
+
+/// Event types listened for by the edges of a machine, most used first ("Always" for `AlwaysEdge`)
+fn machine_event_types(world: &World, machine: Entity) -> Vec<String> {
+    let edge_types: Vec<(String, ReflectComponent)> = {
+        let registry = world.resource::<AppTypeRegistry>().read();
+        registry
+            .iter()
+            .filter_map(|registration| {
+                let event_type = event_edge_type_name(registration.type_info().type_path())?;
+                Some((event_type, registration.data::<ReflectComponent>()?.clone()))
+            })
+            .collect()
+    };
+    let Some(persistent) = world.get::<StateMachinePersistentData>(machine) else { return Vec::new(); };
+    
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for transition in &persistent.visual_transitions {
+        let Ok(edge) = world.get_entity(transition.edge_entity) else { continue; };
+        if edge.contains::<AlwaysEdge>() {
+            *counts.entry("Always".to_string()).or_default() += 1;
+        }
+        for (event_type, reflect_component) in &edge_types {
+            if reflect_component.contains(edge) {
+                *counts.entry(event_type.clone()).or_default() += 1;
+            }
+        }
     }
-    transition_state.available_event_types = event_types;
+    let mut used: Vec<(String, usize)> = counts.into_iter().collect();
+    used.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    used.into_iter().map(|(event_type, _)| event_type).collect()
 }
 
 /// Create a transition edge entity using reflection (marker component on the edge)
@@ -476,10 +1088,12 @@ fn create_transition_edge_entity(
     source_entity: Entity,
     target_entity: Entity,
     event_type: &str,
+    internal: bool,
 ) -> Result<(), String> {
+    let kind = if internal { EdgeKind::Internal } else { EdgeKind::External };
     // Special-case: create an Always transition without a listener
     if event_type == "Always" {
-        world.entity_mut(edge_entity).insert((Source(source_entity), Target(target_entity), EdgeKind::External, AlwaysEdge, Name::new("Always")));
+        world.entity_mut(edge_entity).insert((Source(source_entity), Target(target_entity), kind, AlwaysEdge, Name::new("Always")));
         return Ok(());
     }
     // Find the full EventEdge type path and get reflection data
@@ -509,7 +1123,7 @@ fn create_transition_edge_entity(
     let edge = edge_entity;
     world
         .entity_mut(edge)
-        .insert((Source(source_entity), Target(target_entity), EdgeKind::External));
+        .insert((Source(source_entity), Target(target_entity), kind));
 
     // Attach the event-specific listener via reflection to the edge entity (empty struct)
     {
@@ -538,20 +1152,20 @@ fn handle_save_state_machine(
     let entity = save_state_machine.entity;
     commands.queue(move |world: &mut World| {
         // Generate a filename based on the entity name
-        let entity_name = if let Some(name) = world.get::<Name>(entity) {
-            name.as_str().to_string()
-        } else {
-            format!("state_machine_{:?}", entity)
-        };
+        let entity_name = Some(entity_raw_name_from_world(entity, world))
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| format!("state_machine_{:?}", entity));
         
         let filename = format!("assets/{}.scn.ron", entity_name.replace(" ", "_").to_lowercase());
         
-        // Save the state machine
-        match crate::reflectable::ReflectableStateMachinePersistentData::save_state_machine_to_file(
+        // Save the state machine (runtime state such as `Active` is left out of the scene)
+        let result = crate::reflectable::ReflectableStateMachinePersistentData::save_state_machine_to_file(
             world, 
             entity, 
             &filename
-        ) {
+        );
+        
+        match result {
             Ok(_) => {
                 info!("✅ State machine '{}' saved to {}", entity_name, filename);
             }
@@ -562,15 +1176,41 @@ fn handle_save_state_machine(
     });
 }
 
+/// Observer to handle load state machine requests
+fn handle_load_state_machine(
+    load_state_machine: On<LoadStateMachine>,
+    mut commands: Commands,
+) {
+    // Queue the load operation as a command to access the world
+    let path = load_state_machine.path.clone();
+    commands.queue(move |world: &mut World| {
+        match crate::reflectable::ReflectableStateMachinePersistentData::load_state_machine_into_world(world, &path) {
+            Ok(roots) => {
+                if roots.is_empty() {
+                    warn!("⚠️ No state machine root found in {:?}", path);
+                }
+                for root in roots {
+                    info!("✅ State machine {:?} loaded from {:?}", root, path);
+                    world.trigger(OpenMachineRequested { entity: root, position: None, focus_child: None });
+                }
+            }
+            Err(e) => {
+                error!("❌ Failed to load state machine from {:?}: {}", path, e);
+            }
+        }
+    });
+}
+
 /// Observer to handle transition deletion requests
 fn handle_delete_transition(
     delete_transition: On<DeleteTransition>,
     mut q_sm: Query<&mut StateMachinePersistentData, With<StateMachine>>,
     q_child_of: Query<&bevy_gearbox::StateChildOf>,
+    mut root_cache: ResMut<hierarchy::RootAncestorCache>,
     mut commands: Commands,
 ) {
     // Find the state machine root that contains the source entity
-    let root = q_child_of.root_ancestor(delete_transition.source_entity);
+    let root = root_cache.cached_root_ancestor(delete_transition.source_entity, &q_child_of);
     
     // Remove the visual transition from persistent data
     if let Ok(mut persistent_data) = q_sm.get_mut(root) {
@@ -631,25 +1271,58 @@ fn handle_transition_actions_pulse(
     transition_actions: On<bevy_gearbox::TransitionActions>,
     q_edge: Query<(&Source, &Target)>,
     q_child_of: Query<&bevy_gearbox::StateChildOf>,
-    mut q_sm: Query<&mut StateMachineTransientData, With<StateMachine>>,
+    mut root_cache: ResMut<hierarchy::RootAncestorCache>,
+    mut q_sm: Query<(&mut StateMachineTransientData, Option<&StateMachinePersistentData>, Has<EditorPaused>), With<StateMachine>>,
+    config: Res<EditorConfig>,
+    time: Res<Time>,
 ) {
     let edge = transition_actions.target;
     let Ok((Source(source), Target(target))) = q_edge.get(edge) else { return; };
-    let root = q_child_of.root_ancestor(*source);
-    if let Ok(mut transient) = q_sm.get_mut(root) {
-        transient.transition_pulses.push(TransitionPulse::new(*source, *target, edge));
+    let root = root_cache.cached_root_ancestor(*source, &q_child_of);
+    if let Ok((mut transient, persistent, paused)) = q_sm.get_mut(root) {
+        let event_type = persistent
+            .and_then(|persistent| persistent.visual_transitions.iter().find(|t| t.edge_entity == edge))
+            .map(|t| t.event_type.clone())
+            .unwrap_or_else(|| "?".to_string());
+        let record = TransitionRecord {
+            time: time.elapsed_secs(),
+            source: *source,
+            target: *target,
+            edge,
+            event_type,
+        };
+
+        // Paused machines buffer the transition until it is stepped or resumed
+        if paused {
+            if transient.paused_transitions.len() >= TRANSITION_HISTORY_LEN {
+                transient.paused_transitions.pop_front();
+            }
+            transient.paused_transitions.push_back(record);
+            return;
+        }
+        if !config.disable_animations {
+            transient.transition_pulses.push(TransitionPulse::new(*source, *target, edge, config.transition_pulse_seconds));
+        }
+        stepping::push_transition_history(&mut transient, record);
     }
 }
 
 /// System to update transition pulse timers and remove expired pulses
 fn update_transition_pulses(
     mut q_sm: Query<&mut StateMachineTransientData, With<StateMachine>>,
+    config: Res<EditorConfig>,
     time: Res<Time>,
 ) {
     for mut transient_data in q_sm.iter_mut() {
-        // Update all pulse timers
+        // Reduced motion: drop any pulses left from before animations were disabled
+        if config.disable_animations {
+            transient_data.transition_pulses.clear();
+            continue;
+        }
+        // Update all pulse timers and advance the marching ants
         for pulse in transient_data.transition_pulses.iter_mut() {
             pulse.timer.tick(time.delta());
+            pulse.phase += time.delta_secs() * MARCHING_ANTS_SPEED;
         }
         
         // Remove finished pulses
@@ -658,24 +1331,54 @@ fn update_transition_pulses(
 }
 
 /// Observer to track EnterState events and create node pulses
+///
+/// Entries into machines open on the canvas are re-emitted as `EditorStateEntered` for host apps.
 fn handle_node_enter_pulse(
     enter_state: On<bevy_gearbox::EnterState>,
+    mut editor_state: ResMut<EditorState>,
     q_child_of: Query<&bevy_gearbox::StateChildOf>,
+    q_children: Query<&bevy_gearbox::StateChildren>,
     mut q_sm: Query<&mut StateMachineTransientData, With<StateMachine>>,
+    config: Res<EditorConfig>,
+    mut commands: Commands,
 ) {
     let state = enter_state.target;
     let root = q_child_of.root_ancestor(state);
-    if let Ok(mut transient) = q_sm.get_mut(root) {
-        transient.node_pulses.push(NodePulse::new(state));
+    if !config.disable_animations {
+        if let Ok(mut transient) = q_sm.get_mut(root) {
+            transient.node_pulses.push(NodePulse::new(state, config.node_pulse_seconds));
+        }
+    }
+    if editor_state.is_machine_open(root) {
+        commands.trigger(EditorStateEntered { machine: root, state });
+
+        // Breakpoint: pause the machine's feedback and bring the state into view
+        if editor_state.breakpoints.contains(&state) {
+            info!("🔴 Breakpoint hit: {:?} entered in {:?}", state, root);
+            // Inserted rather than toggled so several breakpoints hit in one frame keep it paused
+            commands.entity(root).insert(EditorPaused);
+            commands.trigger(Select { selected: Some(state) });
+            editor_state.view.pending_node_focus = Some((root, state));
+        }
+
+        // Follow active: ease toward the newest active leaf (parents are entered on the way down)
+        if editor_state.follow_active && !q_children.contains(state) {
+            editor_state.view.follow_target = Some((root, state));
+        }
     }
 }
 
 /// System to update node pulse timers and remove expired pulses
 fn update_node_pulses(
     mut q_sm: Query<&mut StateMachineTransientData, With<StateMachine>>,
+    config: Res<EditorConfig>,
     time: Res<Time>,
 ) {
     for mut transient in q_sm.iter_mut() {
+        if config.disable_animations {
+            transient.node_pulses.clear();
+            continue;
+        }
         for pulse in transient.node_pulses.iter_mut() {
             pulse.timer.tick(time.delta());
         }
@@ -686,7 +1389,7 @@ fn update_node_pulses(
 /// Observer to handle node deletion with all edge cases
 fn handle_delete_node(
     delete_node: On<DeleteNode>,
-    mut q_sm: Query<&mut StateMachinePersistentData, With<StateMachine>>,
+    mut q_sm: Query<(&mut StateMachinePersistentData, Option<&mut StateMachineTransientData>), With<StateMachine>>,
     q_state_child_of: Query<&bevy_gearbox::StateChildOf>,
     mut commands: Commands,
 ) {
@@ -695,11 +1398,22 @@ fn handle_delete_node(
     // Find the state machine root that contains this entity
     let root = q_state_child_of.root_ancestor(entity_to_delete);
 
-    let Ok(mut persistent_data) = q_sm.get_mut(root) else {
+    let Ok((mut persistent_data, transient_data)) = q_sm.get_mut(root) else {
         warn!("⚠️ Could not find persistent data for state machine root {:?}", root);
         return;
     };
 
+    // A transition being created from (or to) the deleted subtree can no longer be completed;
+    // `cancel_stale_transition_creation` would catch it next frame, but the canvas may draw before then
+    if let Some(mut transient_data) = transient_data {
+        let in_deleted_subtree = |entity: Entity| {
+            entity == entity_to_delete || q_state_child_of.iter_ancestors(entity).any(|ancestor| ancestor == entity_to_delete)
+        };
+        if transient_data.transition_creation.cancel_if_endpoint(in_deleted_subtree) {
+            info!("Cancelled transition creation: its source or target state was deleted");
+        }
+    }
+
     // Only remove transitions that TARGET the selected node
     let incoming_to_deleted: Vec<_> = persistent_data
         .visual_transitions
@@ -716,6 +1430,16 @@ fn handle_delete_node(
         });
     }
 
+    // Snapshot the node for undo before anything is despawned
+    commands.queue(move |world: &mut World| {
+        history::record_node_deletion(world, entity_to_delete);
+        // Disabled edges aren't in their source's `Transitions`, so the relationship won't despawn them
+        let states = history::collect_subtree(world, entity_to_delete);
+        for edge in history::disabled_edges_from(world, &states) {
+            world.despawn(edge);
+        }
+    });
+
     // Remove the visual node for the deleted entity only
     persistent_data.nodes.remove(&entity_to_delete);
 
@@ -727,12 +1451,28 @@ fn handle_delete_node(
 fn sync_edge_visuals_from_ecs(
     editor_state: Res<EditorState>,
     mut machines: Query<&mut StateMachinePersistentData, With<StateMachine>>,
-    q_edges: Query<(Entity, &Source, &Target)>,
+    q_edges: Query<(Entity, Option<&Source>, Option<&DisabledEdge>, &Target, Has<AlwaysEdge>, Option<&EdgeKind>)>,
     q_names: Query<&Name>,
+    q_edge_labels: Query<&EdgeLabel>,
     q_child_of: Query<&bevy_gearbox::StateChildOf>,
+    mut root_cache: ResMut<hierarchy::RootAncestorCache>,
+    q_transitions: Query<&Transitions>,
+    q_edge_order: Query<&EdgeOrder>,
 ) {
+    // Bucket edges by their machine root once, so each open machine only visits its own edges
+    let open_roots: HashSet<Entity> = editor_state.view.open_machines.iter().map(|machine| machine.entity).collect();
+    let mut edges_by_root: HashMap<Entity, Vec<_>> = HashMap::new();
+    for (edge, source, disabled, target, is_always, kind) in &q_edges {
+        // Disabled edges have no `Source`; they remember it in `DisabledEdge`
+        let Some(source) = source.map(|source| source.0).or(disabled.map(|disabled| disabled.source)) else { continue; };
+        let root = root_cache.cached_root_ancestor(source, &q_child_of);
+        if open_roots.contains(&root) {
+            edges_by_root.entry(root).or_default().push((edge, source, disabled.is_some(), target, is_always, kind));
+        }
+    }
+
     // Sync edges for all open machines
-    for open_machine in &editor_state.open_machines {
+    for open_machine in &editor_state.view.open_machines {
         let selected_root = open_machine.entity;
         let Ok(mut persistent) = machines.get_mut(selected_root) else { continue; };
 
@@ -745,27 +1485,47 @@ fn sync_edge_visuals_from_ecs(
             node_rects.insert(*entity, node.current_rect());
         }
 
+        // Existing visuals by edge, so matching an ECS edge doesn't rescan the list
+        let mut visual_index: HashMap<Entity, usize> = persistent
+            .visual_transitions
+            .iter()
+            .enumerate()
+            .map(|(index, t)| (t.edge_entity, index))
+            .collect();
+
         // Ensure each ECS edge has a visual entry; update rects and label
-        for (edge, source, target) in &q_edges {
-            if q_child_of.root_ancestor(source.0) != selected_root { continue; }
+        let edges = edges_by_root.remove(&selected_root).unwrap_or_default();
+        for (edge, source, is_disabled, target, is_always, kind) in edges {
+            let is_internal = matches!(kind, Some(EdgeKind::Internal));
             seen_edges.insert(edge);
 
             // Compute rects if available
             let (Some(source_rect), Some(target_rect)) = (
-                node_rects.get(&source.0).copied(),
+                node_rects.get(&source).copied(),
                 node_rects.get(&target.0).copied(),
             ) else { continue; };
 
-            // Derive display label from Name or fallback to ID
-            let label = if let Ok(n) = q_names.get(edge) { n.as_str().to_string() } else { format!("{:?}", edge) };
+            // The edge's Name is the event it listens for; the display label prefers EdgeLabel
+            let listener_event = match q_names.get(edge) {
+                Ok(name) => name.as_str().to_string(),
+                Err(_) => format!("{:?}", edge),
+            };
+            let label = match q_edge_labels.get(edge) {
+                Ok(edge_label) => edge_label.0.clone(),
+                Err(_) => listener_event.clone(),
+            };
 
             // Find existing visual or create a new one
-            if let Some(vt) = persistent.visual_transitions.iter_mut().find(|t| t.edge_entity == edge) {
-                vt.source_entity = source.0;
+            if let Some(vt) = visual_index.get(&edge).map(|index| &mut persistent.visual_transitions[*index]) {
+                vt.source_entity = source;
                 vt.target_entity = target.0;
                 vt.source_rect = source_rect;
                 vt.target_rect = target_rect;
                 vt.event_type = label;
+                vt.listener_event = listener_event;
+                vt.is_always = is_always;
+                vt.is_internal = is_internal;
+                vt.is_disabled = is_disabled;
                 if !vt.is_dragging_event_node {
                     vt.update_event_node_position();
                 }
@@ -775,7 +1535,7 @@ fn sync_edge_visuals_from_ecs(
                     (source_rect.center().y + target_rect.center().y) / 2.0,
                 );
                 persistent.visual_transitions.push(TransitionConnection {
-                    source_entity: source.0,
+                    source_entity: source,
                     edge_entity: edge,
                     target_entity: target.0,
                     event_type: label,
@@ -784,12 +1544,196 @@ fn sync_edge_visuals_from_ecs(
                     event_node_position: midpoint,
                     is_dragging_event_node: false,
                     event_node_offset: egui::Vec2::ZERO,
+                    sibling_index: 0,
+                    sibling_count: 1,
+                    guards: Vec::new(),
+                    is_always,
+                    is_internal,
+                    order_badge: None,
+                    listener_event,
+                    is_disabled,
                 });
+                visual_index.insert(edge, persistent.visual_transitions.len() - 1);
             }
         }
 
         // Remove visuals whose edges no longer exist
         persistent.visual_transitions.retain(|t| seen_edges.contains(&t.edge_entity));
+
+        // Assign sibling indices for transitions connecting the same pair of nodes (in either direction)
+        let mut siblings: HashMap<(Entity, Entity), Vec<(Entity, usize)>> = HashMap::new();
+        for (index, t) in persistent.visual_transitions.iter().enumerate() {
+            let pair = if t.source_entity <= t.target_entity { (t.source_entity, t.target_entity) } else { (t.target_entity, t.source_entity) };
+            siblings.entry(pair).or_default().push((t.edge_entity, index));
+        }
+        for group in siblings.values_mut() {
+            // Order by edge entity so the fan-out is deterministic
+            group.sort_by_key(|(edge, _)| *edge);
+            let count = group.len();
+            for (sibling_index, (_, index)) in group.iter().enumerate() {
+                let vt = &mut persistent.visual_transitions[*index];
+                vt.sibling_index = sibling_index;
+                vt.sibling_count = count;
+                if !vt.is_dragging_event_node {
+                    vt.update_event_node_position();
+                }
+            }
+        }
+
+        // Number edges sharing a source and event in evaluation order
+        let mut same_event: HashMap<(Entity, String), Vec<(Entity, usize)>> = HashMap::new();
+        for (index, t) in persistent.visual_transitions.iter().enumerate() {
+            same_event.entry((t.source_entity, t.listener_event.clone())).or_default().push((t.edge_entity, index));
+        }
+        for ((source, _), mut group) in same_event {
+            if group.len() < 2 {
+                persistent.visual_transitions[group[0].1].order_badge = None;
+                continue;
+            }
+            group.sort_by_key(|(edge, _)| edge_evaluation_key(*edge, source, &q_transitions, &q_edge_order));
+            let count = group.len();
+            for (order, (_, index)) in group.iter().enumerate() {
+                persistent.visual_transitions[*index].order_badge = Some((order + 1, count));
+            }
+        }
+    }
+}
+
+/// Whether a component's type name marks it as a guard (`Guard`, `Guards` or a `...Guard` suffix)
+fn is_guard_type_name(base_name: &str) -> bool {
+    base_name.ends_with("Guard") || base_name.ends_with("Guards")
+}
+
+/// Reflected component types the per-frame syncs look for, cached from the type registry
+///
+/// Built in one pass over the registry and rebuilt only when the number of registrations changes,
+/// so the guard annotations and component badges don't rescan the registry every frame.
+#[derive(Resource, Default)]
+pub(crate) struct ComponentTypeCache {
+    registration_count: usize,
+    /// Guard components by full type path
+    guards: Vec<(String, ReflectComponent)>,
+    /// User-facing components (everything not in `INTERNAL_COMPONENT_NAMES`) by short name
+    user_facing: Vec<(String, ReflectComponent)>,
+}
+
+impl ComponentTypeCache {
+    /// Rebuild the cache if types were registered since it was last built
+    fn refresh(&mut self, registry: &bevy::reflect::TypeRegistry) {
+        let registration_count = registry.iter().count();
+        if registration_count == self.registration_count {
+            return;
+        }
+        self.registration_count = registration_count;
+        self.guards.clear();
+        self.user_facing.clear();
+        for registration in registry.iter() {
+            let Some(reflect_component) = registration.data::<ReflectComponent>() else {
+                continue;
+            };
+            let type_path = registration.type_info().type_path();
+            let short_name = registration.type_info().type_path_table().short_path();
+            // Match on the type name itself, ignoring module path and generic parameters
+            let base_name = short_name.split('<').next().unwrap_or(short_name);
+            if is_guard_type_name(base_name) {
+                self.guards.push((type_path.to_string(), reflect_component.clone()));
+            }
+            if !INTERNAL_COMPONENT_NAMES.contains(&base_name) {
+                self.user_facing.push((short_name.to_string(), reflect_component.clone()));
+            }
+        }
+    }
+}
+
+/// System to keep `ComponentTypeCache` in step with the type registry
+fn refresh_component_type_cache(type_registry: Res<AppTypeRegistry>, mut cache: ResMut<ComponentTypeCache>) {
+    cache.refresh(&type_registry.read());
+}
+
+/// Mirror guard components found on edge entities onto their visual transitions
+fn sync_edge_guards_from_ecs(world: &mut World) {
+    world.resource_scope(|world, cache: Mut<ComponentTypeCache>| {
+        if !cache.guards.is_empty() {
+            sync_edge_guards(world, &cache.guards);
+        }
+    });
+}
+
+fn sync_edge_guards(world: &mut World, guard_types: &[(String, ReflectComponent)]) {
+    let open_machines = world.resource::<EditorState>().get_open_machine_entities();
+    for machine in open_machines {
+        let Some(persistent) = world.get::<StateMachinePersistentData>(machine) else { continue; };
+        let updates: Vec<(usize, Vec<String>)> = persistent
+            .visual_transitions
+            .iter()
+            .enumerate()
+            .filter_map(|(index, t)| {
+                let edge = world.get_entity(t.edge_entity).ok()?;
+                let guards: Vec<String> = guard_types
+                    .iter()
+                    .filter(|(_, reflect_component)| reflect_component.contains(edge))
+                    .map(|(type_path, _)| type_path.clone())
+                    .collect();
+                (guards != t.guards).then_some((index, guards))
+            })
+            .collect();
+        if updates.is_empty() {
+            continue;
+        }
+        let mut persistent = world.get_mut::<StateMachinePersistentData>(machine).unwrap();
+        for (index, guards) in updates {
+            persistent.visual_transitions[index].guards = guards;
+        }
+    }
+}
+
+/// Components every state or machine carries for gearbox/editor bookkeeping; not counted on node badges
+const INTERNAL_COMPONENT_NAMES: &[&str] = &[
+    "Name",
+    "ChildOf",
+    "Children",
+    "StateMachine",
+    "StateChildOf",
+    "StateChildren",
+    "InitialState",
+    "Active",
+    "Inactive",
+    "Parallel",
+    "Transitions",
+    "StateMachinePersistentData",
+    "ReflectableStateMachinePersistentData",
+    "StateMachineTransientData",
+];
+
+/// Record which user-facing reflected components each open node's entity carries
+fn sync_node_components_from_ecs(world: &mut World) {
+    world.resource_scope(|world, cache: Mut<ComponentTypeCache>| sync_node_components(world, &cache.user_facing));
+}
+
+fn sync_node_components(world: &mut World, component_types: &[(String, ReflectComponent)]) {
+    let open_machines = world.resource::<EditorState>().get_open_machine_entities();
+    for machine in open_machines {
+        let Some(persistent) = world.get::<StateMachinePersistentData>(machine) else { continue; };
+        let Some(transient) = world.get::<StateMachineTransientData>(machine) else { continue; };
+        let updates: Vec<(Entity, Vec<String>)> = persistent
+            .nodes
+            .keys()
+            .filter_map(|entity| {
+                let entity_ref = world.get_entity(*entity).ok()?;
+                let mut components: Vec<String> = component_types
+                    .iter()
+                    .filter(|(_, reflect_component)| reflect_component.contains(entity_ref))
+                    .map(|(name, _)| name.clone())
+                    .collect();
+                components.sort();
+                (transient.node_components.get(entity) != Some(&components)).then_some((*entity, components))
+            })
+            .collect();
+        if updates.is_empty() {
+            continue;
+        }
+        let mut transient = world.get_mut::<StateMachineTransientData>(machine).unwrap();
+        transient.node_components.extend(updates);
     }
 }
 
@@ -809,6 +1753,118 @@ fn handle_delete_transition_by_edge(
     });
 }
 
+/// Observer to point an existing edge at a new target state
+fn handle_retarget_transition(
+    retarget_transition: On<RetargetTransition>,
+    mut commands: Commands,
+) {
+    let edge = retarget_transition.edge_entity;
+    let new_target = retarget_transition.new_target;
+    commands.queue(move |world: &mut World| {
+        if !world.entities().contains(edge) || !world.entities().contains(new_target) {
+            warn!("⚠️ RetargetTransition: edge {:?} or target {:?} does not exist", edge, new_target);
+            return;
+        }
+        world.entity_mut(edge).insert(Target(new_target));
+        info!("✅ Retargeted edge {:?} to {:?}", edge, new_target);
+    });
+}
+
+/// Sort key for edges sharing a source and event: explicit `EdgeOrder` first, then `Transitions` order
+fn edge_evaluation_key(
+    edge: Entity,
+    source: Entity,
+    q_transitions: &Query<&Transitions>,
+    q_edge_order: &Query<&EdgeOrder>,
+) -> (u32, usize) {
+    let order = q_edge_order.get(edge).map(|order| order.0).unwrap_or(u32::MAX);
+    let position = q_transitions
+        .get(source)
+        .ok()
+        .and_then(|transitions| transitions.into_iter().position(|candidate| *candidate == edge))
+        .unwrap_or(usize::MAX);
+    (order, position)
+}
+
+/// Observer to move an edge earlier or later among edges sharing its source and event
+///
+/// Every sibling gets an `EdgeOrder`, and their `Source` is re-inserted in the new order so the
+/// source's `Transitions` list (which gearbox evaluates in order) matches.
+fn handle_reorder_transition(
+    reorder_transition: On<ReorderTransition>,
+    mut commands: Commands,
+) {
+    let edge = reorder_transition.edge_entity;
+    let up = reorder_transition.up;
+    commands.queue(move |world: &mut World| {
+        let Some(source) = world.get::<Source>(edge).map(|source| source.0) else {
+            warn!("⚠️ ReorderTransition: edge {:?} has no source", edge);
+            return;
+        };
+        let event = world.get::<Name>(edge).map(|name| name.as_str().to_string());
+        // Same ordering as `edge_evaluation_key`: explicit order, then position in `Transitions`
+        let mut keyed: Vec<((u32, usize), Entity)> = world
+            .get::<Transitions>(source)
+            .map(|transitions| transitions.into_iter().copied().enumerate().collect::<Vec<_>>())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(_, sibling)| world.get::<Name>(*sibling).map(|name| name.as_str().to_string()) == event)
+            .map(|(position, sibling)| {
+                let order = world.get::<EdgeOrder>(sibling).map(|order| order.0).unwrap_or(u32::MAX);
+                ((order, position), sibling)
+            })
+            .collect();
+        keyed.sort_by_key(|(key, _)| *key);
+        let mut siblings: Vec<Entity> = keyed.into_iter().map(|(_, sibling)| sibling).collect();
+        
+        let Some(index) = siblings.iter().position(|sibling| *sibling == edge) else { return; };
+        let swap_with = if up { index.checked_sub(1) } else { Some(index + 1).filter(|next| *next < siblings.len()) };
+        let Some(swap_with) = swap_with else { return; };
+        siblings.swap(index, swap_with);
+        
+        for (order, sibling) in siblings.iter().enumerate() {
+            world.entity_mut(*sibling).insert((EdgeOrder(order as u32), Source(source)));
+        }
+        info!("✅ Moved edge {:?} to position {} of {}", edge, swap_with + 1, siblings.len());
+    });
+}
+
+/// Observer to disable a transition (or re-enable it)
+///
+/// Disabling moves the edge's `Source` into `DisabledEdge`, which takes it out of its source's
+/// `Transitions`; re-enabling puts the `Source` back at its old position, so its evaluation
+/// priority is unchanged. The edge and its layout are kept either way.
+fn handle_toggle_transition_enabled(
+    toggle: On<ToggleTransitionEnabled>,
+    mut commands: Commands,
+) {
+    let edge = toggle.edge_entity;
+    commands.queue(move |world: &mut World| {
+        let Ok(mut edge_entity) = world.get_entity_mut(edge) else { return; };
+        if let Some(disabled) = edge_entity.take::<DisabledEdge>() {
+            edge_entity.insert(Source(disabled.source));
+            // Inserting appends to `Transitions`; re-insert the edges that came after it so they follow again
+            let later: Vec<Entity> = world
+                .get::<Transitions>(disabled.source)
+                .map(|transitions| transitions.into_iter().copied().skip(disabled.index).filter(|later| *later != edge).collect())
+                .unwrap_or_default();
+            for later in later {
+                world.entity_mut(later).insert(Source(disabled.source));
+            }
+            info!("✅ Enabled transition {:?}", edge);
+        } else if let Some(source) = edge_entity.get::<Source>().map(|source| source.0) {
+            let index = world
+                .get::<Transitions>(source)
+                .and_then(|transitions| transitions.into_iter().position(|candidate| *candidate == edge))
+                .unwrap_or(usize::MAX);
+            world.entity_mut(edge).remove::<Source>().insert(DisabledEdge { source, index });
+            info!("✅ Disabled transition {:?}", edge);
+        } else {
+            warn!("⚠️ ToggleTransitionEnabled: edge {:?} has no source", edge);
+        }
+    });
+}
+
 /// Observer to handle SetInitialStateRequested requests
 fn handle_set_initial_state_request(
     set_initial_state_requested: On<SetInitialStateRequested>,
@@ -829,9 +1885,16 @@ fn handle_set_initial_state_request(
 /// Handle background interactions for the canvas
 fn handle_background_interactions(
     ui: &mut egui::Ui,
+    background_response: &egui::Response,
     editor_state: &mut EditorState,
+    q_sm_data: &mut Query<(Entity, Option<&Name>, Option<&mut StateMachinePersistentData>, Option<&mut StateMachineTransientData>), With<StateMachine>>,
     commands: &mut Commands,
 ) {
+    // Panning takes the drag, so it never also starts a rubber-band selection
+    if !handle_canvas_pan(ui, background_response, editor_state) {
+        handle_selection_box(ui, background_response, editor_state, q_sm_data, commands);
+    }
+
     // If a node/transition menu was just opened this frame, suppress background handling once
     if editor_state.suppress_background_context_menu_once {
         editor_state.suppress_background_context_menu_once = false;
@@ -853,41 +1916,87 @@ fn handle_background_interactions(
     }
 }
 
-/// Apply canvas offset to all nodes in a state machine (for rendering)
-fn apply_canvas_offset_to_nodes(persistent_data: &mut StateMachinePersistentData, offset: egui::Vec2) {
-    for node in persistent_data.nodes.values_mut() {
-        match node {
-            crate::components::NodeType::Leaf(leaf_node) => {
-                leaf_node.entity_node.position += offset;
-            }
-            crate::components::NodeType::Parent(parent_node) => {
-                parent_node.entity_node.position += offset;
-            }
+/// Pan every open machine together with a middle-drag or Space+left-drag on empty canvas
+///
+/// Returns true while a pan gesture owns the background drag.
+fn handle_canvas_pan(ui: &egui::Ui, background_response: &egui::Response, editor_state: &mut EditorState) -> bool {
+    // Space only means "pan" when no text field is taking keyboard input
+    let space_held = !ui.ctx().wants_keyboard_input() && ui.input(|i| i.key_down(egui::Key::Space));
+    let panning = background_response.dragged_by(egui::PointerButton::Middle)
+        || (space_held && background_response.dragged_by(egui::PointerButton::Primary));
+    if !panning {
+        if space_held && background_response.hovered() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
         }
+        return false;
     }
-    
-    // Also offset transition event node positions
-    for transition in persistent_data.visual_transitions.iter_mut() {
-        transition.event_node_position += offset;
+
+    ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+    let delta = background_response.drag_delta();
+    for open_machine in &mut editor_state.view.open_machines {
+        // Offsets are in unzoomed canvas units
+        open_machine.canvas_offset += delta / open_machine.canvas_zoom;
     }
+    editor_state.view.follow_target = None;
+    editor_state.view.selection_box = None;
+    true
 }
 
-/// Remove canvas offset from all nodes in a state machine (after rendering)
-fn remove_canvas_offset_from_nodes(persistent_data: &mut StateMachinePersistentData, offset: egui::Vec2) {
-    for node in persistent_data.nodes.values_mut() {
-        match node {
-            crate::components::NodeType::Leaf(leaf_node) => {
-                leaf_node.entity_node.position -= offset;
-            }
-            crate::components::NodeType::Parent(parent_node) => {
-                parent_node.entity_node.position -= offset;
-            }
-        }
+/// Rubber-band selection: drag on empty canvas, then select every node the box touches
+fn handle_selection_box(
+    ui: &egui::Ui,
+    background_response: &egui::Response,
+    editor_state: &mut EditorState,
+    q_sm_data: &mut Query<(Entity, Option<&Name>, Option<&mut StateMachinePersistentData>, Option<&mut StateMachineTransientData>), With<StateMachine>>,
+    commands: &mut Commands,
+) {
+    let Some(pointer_pos) = ui.input(|i| i.pointer.interact_pos()) else {
+        return;
+    };
+    if background_response.drag_started_by(egui::PointerButton::Primary) {
+        editor_state.view.selection_box = Some(egui::Rect::from_two_pos(pointer_pos, pointer_pos));
+        commands.trigger(Select { selected: None });
     }
+    let Some(selection_box) = editor_state.view.selection_box.as_mut() else {
+        return;
+    };
+    let start = selection_box.min;
     
-    // Also remove offset from transition event node positions
-    for transition in persistent_data.visual_transitions.iter_mut() {
-        transition.event_node_position -= offset;
+    if background_response.dragged() {
+        // `min` keeps the drag start; normalize only for drawing and hit-testing
+        selection_box.max = pointer_pos;
+        let rect = egui::Rect::from_two_pos(start, pointer_pos);
+        let painter = ui.ctx().layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("selection_box")));
+        painter.rect_filled(rect, 0.0, egui::Color32::from_rgba_unmultiplied(100, 150, 255, 30));
+        painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.0, egui::Color32::from_rgb(100, 150, 255)), egui::StrokeKind::Inside);
+    }
+    
+    if background_response.drag_stopped() {
+        let rect = egui::Rect::from_two_pos(start, selection_box.max);
+        editor_state.view.selection_box = None;
+        let canvas_origin = editor_state.view.canvas_origin.unwrap_or(egui::Pos2::ZERO);
+        let mut selected_nodes = HashSet::new();
+        for open_machine in &editor_state.view.open_machines {
+            let Ok((_, _, Some(persistent_data), _)) = q_sm_data.get(open_machine.entity) else {
+                continue;
+            };
+            // Node rects are stored without the canvas offset and zoom; compare in screen space
+            let transform = open_machine.canvas_transform();
+            // Leaves are picked when touched; parents (and the root) only when fully enclosed, so a
+            // drag inside a parent doesn't grab it and every ancestor along with the children
+            selected_nodes.extend(persistent_data
+                .nodes
+                .iter()
+                .filter(|(_, node)| {
+                    let node_rect = transform.rect_to_screen(node.current_rect(), canvas_origin);
+                    match node {
+                        components::NodeType::Parent(_) => rect.contains_rect(node_rect),
+                        components::NodeType::Leaf(_) => rect.intersects(node_rect),
+                    }
+                })
+                .map(|(entity, _)| *entity));
+        }
+        editor_state.view.selected_nodes = selected_nodes;
     }
 }
 
@@ -895,7 +2004,8 @@ fn remove_canvas_offset_from_nodes(persistent_data: &mut StateMachinePersistentD
 fn render_background_context_menu(
     ctx: &egui::Context,
     editor_state: &mut EditorState,
-    q_sm: &Query<(Entity, Option<&Name>), With<StateMachine>>,
+    q_sm: &Query<(Entity, Option<&Name>, Option<&EditorLabel>), (With<StateMachine>, Without<EditorInternal>)>,
+    config: &EditorConfig,
     commands: &mut Commands,
 ) {
     if let Some(position) = editor_state.background_context_menu_position {
@@ -922,7 +2032,20 @@ fn render_background_context_menu(
                         )).id();
                         // Use background menu position to place at mouse; fallback to center
                         let pos = editor_state.background_context_menu_position;
-                        commands.trigger(OpenMachineRequested { entity: new_entity, position: pos });
+                        commands.trigger(OpenMachineRequested { entity: new_entity, position: pos, focus_child: None });
+                        editor_state.background_context_menu_position = None;
+                    }
+
+                    if ui.button("Auto Layout").clicked() {
+                        // Arrange every open machine along its state hierarchy
+                        for open_machine in &editor_state.view.open_machines {
+                            commands.trigger(AutoLayoutMachine { entity: open_machine.entity });
+                        }
+                        editor_state.background_context_menu_position = None;
+                    }
+
+                    if ui.button("Show Onboarding").clicked() {
+                        editor_state.show_onboarding = true;
                         editor_state.background_context_menu_position = None;
                     }
                     // Capture rect
@@ -946,7 +2069,7 @@ fn render_background_context_menu(
     // Render machine selection submenu
     if editor_state.show_machine_selection_menu {
         if let Some(base_position) = editor_state.background_context_menu_position {
-            let submenu_position = egui::Pos2::new(base_position.x + 130.0, base_position.y);
+            let submenu_position = egui::Pos2::new(base_position.x + config.background_submenu_offset, base_position.y);
             let submenu_id = egui::Id::new("machine_selection_submenu");
             
             // Track drawn rects
@@ -970,20 +2093,12 @@ fn render_background_context_menu(
                         );
 
                         let mut items: Vec<(Entity, String)> = Vec::new();
-                        for (entity, name_opt) in q_sm.iter() {
+                        for (entity, name_opt, label_opt) in q_sm.iter() {
                             // Skip machines that are already open
                             if editor_state.is_machine_open(entity) {
                                 continue;
                             }
-                            
-                            // Skip internal NodeKind machines
-                            if let Some(name) = name_opt {
-                                if name.as_str() == "NodeKind" {
-                                    continue;
-                                }
-                            }
-                            let display_name = if let Some(name) = name_opt { name.as_str().to_string() } else { format!("Unnamed Machine") };
-                            items.push((entity, display_name));
+                            items.push((entity, format_entity_name(entity, label_opt, name_opt)));
                         }
 
                         // Filter by search
@@ -1011,7 +2126,7 @@ fn render_background_context_menu(
                                         });
                                         if ui.add(egui::Button::new(job)).clicked() {
                                             let pos = editor_state.background_context_menu_position;
-                                            commands.trigger(OpenMachineRequested { entity: *entity, position: pos });
+                                            commands.trigger(OpenMachineRequested { entity: *entity, position: pos, focus_child: None });
                                             editor_state.background_context_menu_position = None;
                                             editor_state.show_machine_selection_menu = false;
                                         }
@@ -1029,7 +2144,7 @@ fn render_background_context_menu(
                                     });
                                     if ui.add(egui::Button::new(job)).clicked() {
                                         let pos = editor_state.background_context_menu_position;
-                                        commands.trigger(OpenMachineRequested { entity: *entity, position: pos });
+                                        commands.trigger(OpenMachineRequested { entity: *entity, position: pos, focus_child: None });
                                         editor_state.background_context_menu_position = None;
                                         editor_state.show_machine_selection_menu = false;
                                     }
@@ -1078,22 +2193,32 @@ fn handle_background_context_menu_request(
 fn handle_open_machine_request(
     open_machine_requested: On<OpenMachineRequested>,
     mut editor_state: ResMut<EditorState>,
-    q_name: Query<&Name>,
+    config: Res<EditorConfig>,
+    q_entities: EntityNamesQuery,
+    q_internal: Query<(), With<EditorInternal>>,
     mut commands: Commands,
 ) {
+    // Editor bookkeeping machines are never opened on the canvas
+    if q_internal.contains(open_machine_requested.entity) {
+        warn!("⚠️ Refusing to open editor-internal machine {:?}", open_machine_requested.entity);
+        return;
+    }
+
+    // Deep link: select the requested state and center it once the machine's nodes exist
+    if let Some(focus_child) = open_machine_requested.focus_child {
+        editor_state.view.pending_node_focus = Some((open_machine_requested.entity, focus_child));
+        commands.trigger(Select { selected: Some(focus_child) });
+    }
+
     // Don't open if already open
     if editor_state.is_machine_open(open_machine_requested.entity) {
         return;
     }
     
-    let display_name = if let Ok(name) = q_name.get(open_machine_requested.entity) {
-        name.as_str().to_string()
-    } else {
-        format!("Machine {:?}", open_machine_requested.entity)
-    };
+    let display_name = entity_display_name(open_machine_requested.entity, &q_entities);
     
-    // Determine desired screen position; default to (100, 100) from top-left of the screen
-    let desired_screen_pos = open_machine_requested.position.unwrap_or(egui::Pos2::new(100.0, 100.0));
+    // Determine desired screen position; default to the configured spot from top-left of the screen
+    let desired_screen_pos = open_machine_requested.position.unwrap_or(config.default_open_position);
     editor_state.desired_open_positions.insert(open_machine_requested.entity, desired_screen_pos);
     // Avoid adding an additional canvas offset so positioning is exact
     editor_state.add_machine_with_offset(open_machine_requested.entity, display_name, egui::Vec2::ZERO);
@@ -1126,10 +2251,18 @@ fn handle_machine_scaffold_ready(
     q_children: Query<&bevy_gearbox::StateChildren>,
     mut q_sm: Query<&mut StateMachinePersistentData, With<StateMachine>>,
     mut editor_state: ResMut<EditorState>,
+    entities: &bevy::ecs::entity::Entities,
     mut commands: Commands,
 ) {
     let root = ready.root;
     let Ok(mut persistent) = q_sm.get_mut(root) else { return; };
+    // Bring back what this machine last had in the inspector, unless something else is inspected
+    if editor_state.inspected_entity.is_none() {
+        if let Some(inspected) = persistent.inspected_entity.filter(|entity| entities.contains(*entity)) {
+            editor_state.inspected_entity = Some(inspected);
+            info!("✅ Restored inspected entity {:?} for machine {:?}", inspected, root);
+        }
+    }
     // Build list: root + descendants
     let mut entities: Vec<Entity> = q_children.iter_descendants_depth_first(root).collect();
     entities.insert(0, root);
@@ -1143,7 +2276,7 @@ fn handle_machine_scaffold_ready(
     if after != before { info!("Cascade: populated nodes {} -> {} for root {:?}", before, after, root); }
     // If a desired open position was specified, apply it by shifting all nodes so the root's top-left aligns
     if let Some(screen_pos) = editor_state.desired_open_positions.remove(&root) {
-        if let Some(canvas_origin) = editor_state.canvas_origin {
+        if let Some(canvas_origin) = editor_state.view.canvas_origin {
             // Convert screen pos to canvas-local position
             let target_top_left = egui::Pos2::new(screen_pos.x - canvas_origin.x, screen_pos.y - canvas_origin.y);
             if let Some(root_node) = persistent.nodes.get(&root) {
@@ -1171,8 +2304,14 @@ fn handle_close_machine_request(
     close_machine_requested: On<CloseMachineRequested>,
     mut editor_state: ResMut<EditorState>,
 ) {
+    let open_before = editor_state.view.open_machines.len();
     editor_state.remove_machine(close_machine_requested.entity);
-    info!("✅ Closed machine {:?} from canvas", close_machine_requested.entity);
+    let closed = open_before - editor_state.view.open_machines.len();
+    if closed > 1 {
+        info!("✅ Closed machine {:?} and {} related machine(s) from canvas", close_machine_requested.entity, closed - 1);
+    } else {
+        info!("✅ Closed machine {:?} from canvas", close_machine_requested.entity);
+    }
 }
 
 /// Observer to handle ViewRelated events
@@ -1180,7 +2319,8 @@ fn handle_close_machine_request(
 fn handle_view_related(
     view_related: On<ViewRelated>,
     mut editor_state: ResMut<EditorState>,
-    q_name: Query<&Name>,
+    config: Res<EditorConfig>,
+    q_entities: EntityNamesQuery,
     q_sm: Query<Entity, With<StateMachine>>,
 ) {
     // Check if the origin entity is currently being viewed
@@ -1200,47 +2340,75 @@ fn handle_view_related(
         return;
     }
     
-    // Get display name for the target
-    let display_name = if let Ok(name) = q_name.get(view_related.target) {
-        name.as_str().to_string()
-    } else {
-        format!("Related {:?}", view_related.target)
-    };
-    
-    // Position the related entity near its origin
-    let origin_offset = editor_state.open_machines.iter()
-        .find(|m| m.entity == view_related.origin)
-        .map(|m| m.canvas_offset)
-        .unwrap_or(egui::Vec2::ZERO);
-    
-    // Offset the related entity slightly to the right and down from the origin
-    let related_offset = origin_offset + egui::Vec2::new(300.0, 100.0);
+    // Manual mode: only track the relationship; the origin shows a chip to open it on demand
+    if !editor_state.auto_open_related {
+        editor_state.track_related(view_related.origin, view_related.target);
+        return;
+    }
     
-    // Add the related machine with the calculated offset
-    editor_state.add_machine_with_offset(view_related.target, display_name, related_offset);
+    // Get display name for the target
+    let display_name = entity_display_name(view_related.target, &q_entities);
     
-    // Track the relationship for cleanup purposes
-    editor_state.related_entities
-        .entry(view_related.origin)
-        .or_insert_with(Vec::new)
-        .push(view_related.target);
+    // Add the related machine near its origin and track the relationship for cleanup purposes
+    editor_state.open_related_machine(view_related.origin, view_related.target, display_name, &config);
     
     info!("🔗 Auto-loaded related machine {:?} because origin {:?} is being viewed", 
           view_related.target, view_related.origin);
 }
 
+/// Draw a clickable "↗ related" chip below a machine root for each related machine that is not open
+///
+/// Returns the related machine whose chip was clicked, if any.
+fn render_related_chips(
+    ui: &mut egui::Ui,
+    editor_state: &EditorState,
+    origin: Entity,
+    root_rect: egui::Rect,
+    q_entities: &EntityNamesQuery,
+) -> Option<Entity> {
+    let related = editor_state.view.related_entities.get(&origin)?;
+    let mut clicked = None;
+    let mut chip_pos = root_rect.left_bottom() + egui::vec2(0.0, 6.0);
+    for target in related.iter().filter(|target| !editor_state.is_machine_open(**target)) {
+        let text = format!("↗ related: {}", entity_display_name(*target, q_entities));
+        let galley = ui.painter().layout_no_wrap(text.clone(), egui::FontId::proportional(11.0), egui::Color32::WHITE);
+        let chip_rect = egui::Rect::from_min_size(chip_pos, galley.size() + egui::vec2(12.0, 6.0));
+        let button = egui::Button::new(egui::RichText::new(text).size(11.0)).corner_radius(8.0);
+        if ui.put(chip_rect, button).on_hover_text("Open this related machine").clicked() {
+            clicked = Some(*target);
+        }
+        chip_pos.x = chip_rect.max.x + 6.0;
+    }
+    clicked
+}
+
 /// Observer to apply Select events to editor state
 fn handle_select_event(
     select: On<Select>,
     mut editor_state: ResMut<EditorState>,
-    mut q_sm: Query<&mut StateMachineTransientData, With<StateMachine>>,
+    mut q_sm: Query<(Entity, &mut StateMachineTransientData), With<StateMachine>>,
+    q_child_of: Query<&bevy_gearbox::StateChildOf>,
 ) {
     // Update selected entity in editor state
-    editor_state.selected_entity = select.selected;
+    editor_state.view.selected_entity = select.selected;
+
+    // Mirror the selection into the owning machine so it is raised in z-order
+    let selected_root = select.selected.map(|entity| q_child_of.root_ancestor(entity));
+    // Selecting inside an auto-opened related machine keeps it open when its origin closes
+    if let Some(root) = selected_root.filter(|root| editor_state.view.related_entities.values().any(|related| related.contains(root))) {
+        editor_state.view.interacted_related.insert(root);
+    }
+    // Selecting anything outside the multi-selection drops it
+    if !select.selected.is_some_and(|selected| editor_state.view.selected_nodes.contains(&selected)) {
+        editor_state.view.selected_nodes.clear();
+    }
+    for (root, mut transient) in q_sm.iter_mut() {
+        transient.selected_node = if selected_root == Some(root) { select.selected } else { None };
+    }
 
     // If currently renaming and a different entity is selected, cancel rename
     if let Some(new_selection) = select.selected {
-        for mut transient in q_sm.iter_mut() {
+        for (_, mut transient) in q_sm.iter_mut() {
             if let Some(editing_entity) = transient.text_editing.editing_entity {
                 if editing_entity != new_selection {
                     transient.text_editing.cancel_editing();
@@ -1248,4 +2416,81 @@ fn handle_select_event(
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct DoorGuard;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct KeyGuard;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Health(u32);
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Guardian;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct SafeguardTimer;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct GuardRail;
+
+    #[test]
+    fn component_type_cache_finds_guards_and_rebuilds_on_registration() {
+        let mut registry = bevy::reflect::TypeRegistry::default();
+        registry.register::<DoorGuard>();
+        registry.register::<Name>();
+        let mut cache = ComponentTypeCache::default();
+        cache.refresh(&registry);
+        assert_eq!(cache.guards.len(), 1);
+        assert!(cache.guards[0].0.ends_with("DoorGuard"));
+
+        // Registering a type rebuilds the cache
+        registry.register::<KeyGuard>();
+        cache.refresh(&registry);
+        assert_eq!(cache.guards.len(), 2);
+    }
+
+    #[test]
+    fn component_type_cache_ignores_names_merely_containing_guard() {
+        let mut registry = bevy::reflect::TypeRegistry::default();
+        registry.register::<Guardian>();
+        registry.register::<SafeguardTimer>();
+        registry.register::<GuardRail>();
+        let mut cache = ComponentTypeCache::default();
+        cache.refresh(&registry);
+        assert!(cache.guards.is_empty());
+    }
+
+    #[test]
+    fn guard_type_names_need_an_exact_suffix() {
+        assert!(is_guard_type_name("Guard"));
+        assert!(is_guard_type_name("Guards"));
+        assert!(is_guard_type_name("DoorGuard"));
+        assert!(!is_guard_type_name("Guardian"));
+        assert!(!is_guard_type_name("GuardRail"));
+        assert!(!is_guard_type_name("Safeguard"));
+    }
+
+    #[test]
+    fn component_type_cache_skips_internal_components() {
+        let mut registry = bevy::reflect::TypeRegistry::default();
+        registry.register::<Name>();
+        registry.register::<Health>();
+        let mut cache = ComponentTypeCache::default();
+        cache.refresh(&registry);
+        assert!(cache.user_facing.iter().any(|(name, _)| name == "Health"));
+        assert!(cache.user_facing.iter().all(|(name, _)| name != "Name"));
+    }
+}
@@ -5,15 +5,17 @@
 //! - Converting between node types (Leaf <-> Parent)
 //! - Z-ordering and selection management
 //! - Node interaction and dragging
+//! - Keyboard navigation between nodes
 
 use bevy::prelude::*;
 use bevy_gearbox::active::Active;
 use bevy_gearbox::{InitialState, StateMachine};
-use bevy_egui::egui;
-use bevy::platform::collections::HashSet;
+use bevy_egui::{egui, EguiContext, PrimaryEguiContext};
+use bevy::platform::collections::{HashMap, HashSet};
 
-use crate::editor_state::{EditorState, StateMachinePersistentData, StateMachineTransientData, NodeDragged, NodeContextMenuRequested, TransitionContextMenuRequested, RenderItem, get_entity_name, should_get_selection_boost, TransitionCreationRequested, CreateTransition, draw_arrow, draw_interactive_pill_label, closest_point_on_rect_edge, get_node_display_color, get_transition_color};
+use crate::editor_state::{EditorState, EntityNamesQuery, EditorWindow, Select, NodeAction, NodeActionTriggered, StateMachinePersistentData, StateMachineTransientData, NodeDragged, NodeMoved, ReparentNode, NodeContextMenuRequested, TransitionContextMenuRequested, RenderItem, entity_display_name, entity_raw_name, should_get_selection_boost, TransitionCreationRequested, CreateTransition, SetInitialStateRequested, draw_arrow_with_stroke, draw_interactive_pill_label, pill_padding, closest_point_on_rect_edge, get_node_display_color, tint_for_target_selection, get_transition_color, TARGET_CANDIDATE_COLOR, snap_to_grid, EdgeLabel, EditorTheme, CanvasTransform};
 use crate::components::{NodeType, LeafNode, ParentNode};
+use crate::window_management::{CurrentEditorWindow, EditorHotkeys};
 
 /// System to update node types based on entity hierarchy
 /// 
@@ -21,15 +23,15 @@ use crate::components::{NodeType, LeafNode, ParentNode};
 /// and parent nodes back to leaf nodes when they lose all children.
 pub fn update_node_types(
     editor_state: Res<EditorState>,
-    mut q_sm: Query<&mut StateMachinePersistentData, With<StateMachine>>,
+    mut q_sm: Query<(&mut StateMachinePersistentData, Option<&mut StateMachineTransientData>), With<StateMachine>>,
     q_parent: Query<Entity, With<InitialState>>,
     q_leaf: Query<Entity, Without<InitialState>>,
     q_children: Query<&bevy_gearbox::StateChildren>,
     q_parallel: Query<Entity, With<bevy_gearbox::Parallel>>,
 ) {
     // Update node types for all open machines
-    for open_machine in &editor_state.open_machines {
-        if let Ok(mut machine_data) = q_sm.get_mut(open_machine.entity) {
+    for open_machine in &editor_state.view.open_machines {
+        if let Ok((mut machine_data, mut transient_data)) = q_sm.get_mut(open_machine.entity) {
             let selected_root = open_machine.entity;
         
         // Get all entities in the selected state machine's hierarchy
@@ -47,9 +49,18 @@ pub fn update_node_types(
                         // Already a parent node, no change needed
                     }
                     Some(NodeType::Leaf(leaf_node)) => {
-                        // Convert leaf to parent
-                        let parent_node = ParentNode::new(leaf_node.entity_node.position);
+                        // Convert leaf to parent, restoring its last parent layout at the leaf's position
+                        let position = leaf_node.entity_node.position;
+                        let cached = transient_data.as_mut().and_then(|transient| transient.parent_layout_cache.remove(&entity));
+                        let parent_node = match cached {
+                            Some(mut parent_node) => {
+                                parent_node.entity_node.position = position;
+                                parent_node
+                            }
+                            None => ParentNode::new(position),
+                        };
                         machine_data.nodes.insert(entity, NodeType::Parent(parent_node));
+                        machine_data.mark_layout_dirty();
                     }
                     None => {
                         // Create new parent node
@@ -64,9 +75,13 @@ pub fn update_node_types(
                         // Already a leaf node, no change needed
                     }
                     Some(NodeType::Parent(parent_node)) => {
-                        // Convert parent to leaf
+                        // Convert parent to leaf, keeping the parent layout around in case it gains children again
                         let leaf_node = LeafNode::new(parent_node.entity_node.position);
+                        if let Some(transient) = transient_data.as_mut() {
+                            transient.parent_layout_cache.insert(entity, parent_node.clone());
+                        }
                         machine_data.nodes.insert(entity, NodeType::Leaf(leaf_node));
+                        machine_data.mark_layout_dirty();
                     }
                     None => {
                         // Create new leaf node
@@ -80,6 +95,36 @@ pub fn update_node_types(
             // Remove nodes that are no longer part of the active hierarchy
             let valid_entities: HashSet<Entity> = descendants.into_iter().collect();
             machine_data.nodes.retain(|entity, _| valid_entities.contains(entity));
+            if let Some(transient) = transient_data.as_mut() {
+                transient.parent_layout_cache.retain(|entity, _| valid_entities.contains(entity));
+            }
+            machine_data.node_colors.retain(|entity, _| valid_entities.contains(entity));
+            machine_data.z_bias.retain(|entity, _| valid_entities.contains(entity));
+            
+            // Mirror Parallel onto parent nodes so they can be drawn striped
+            for (entity, node) in machine_data.nodes.iter_mut() {
+                if let NodeType::Parent(parent_node) = node {
+                    parent_node.parallel = q_parallel.contains(*entity);
+                }
+            }
+            
+            // Only parent nodes can stay collapsed; mirror the flag onto the node for drawing
+            let parent_entities: HashSet<Entity> = machine_data.nodes.iter()
+                .filter(|(_, node)| matches!(node, NodeType::Parent(_)))
+                .map(|(entity, _)| *entity)
+                .collect();
+            machine_data.collapsed.retain(|entity| parent_entities.contains(entity));
+            let collapsed = machine_data.collapsed.clone();
+            for (entity, node) in machine_data.nodes.iter_mut() {
+                if let NodeType::Parent(parent_node) = node {
+                    parent_node.collapsed = collapsed.contains(entity);
+                    parent_node.hidden_descendant_count = if parent_node.collapsed {
+                        q_children.iter_descendants(*entity).count()
+                    } else {
+                        0
+                    };
+                }
+            }
         }
     }
 }
@@ -91,11 +136,22 @@ pub fn show_single_machine_on_canvas(
     transient_data: &mut StateMachineTransientData,
     selected_root: Entity,
     selected_entity: Option<Entity>,
-    all_entities: &Query<(Entity, Option<&Name>, Option<&InitialState>)>,
+    selected_nodes: &HashSet<Entity>,
+    all_entities: &EntityNamesQuery,
     q_child_of: &Query<&bevy_gearbox::StateChildOf>,
     q_children: &Query<&bevy_gearbox::StateChildren>,
     q_active: &Query<&Active>,
     q_parallel: &Query<&bevy_gearbox::Parallel>,
+    grid_snap: Option<f32>,
+    show_entity_ids: bool,
+    show_initial_indicators: bool,
+    preview_event: Option<&str>,
+    breakpoints: &HashSet<Entity>,
+    snap_pills: bool,
+    compact: bool,
+    allow_self_transitions: bool,
+    theme: &EditorTheme,
+    rects_before: &mut HashMap<Entity, egui::Rect>,
     commands: &mut Commands,
 ) {
     // Render the machine content directly on the canvas without any container frame
@@ -105,11 +161,22 @@ pub fn show_single_machine_on_canvas(
         transient_data,
         selected_root,
         selected_entity,
+        selected_nodes,
         all_entities,
         q_child_of,
         q_children,
         q_active,
         q_parallel,
+        grid_snap,
+        show_entity_ids,
+        show_initial_indicators,
+        preview_event,
+        breakpoints,
+        snap_pills,
+        compact,
+        allow_self_transitions,
+        theme,
+        rects_before,
         commands,
     );
 }
@@ -121,23 +188,43 @@ fn render_machine_content(
     transient_data: &mut StateMachineTransientData,
     selected_root: Entity,
     selected_entity: Option<Entity>,
-    all_entities: &Query<(Entity, Option<&Name>, Option<&InitialState>)>,
+    selected_nodes: &HashSet<Entity>,
+    all_entities: &EntityNamesQuery,
     q_child_of: &Query<&bevy_gearbox::StateChildOf>,
     q_children: &Query<&bevy_gearbox::StateChildren>,
     q_active: &Query<&Active>,
     q_parallel: &Query<&bevy_gearbox::Parallel>,
+    grid_snap: Option<f32>,
+    show_entity_ids: bool,
+    show_initial_indicators: bool,
+    preview_event: Option<&str>,
+    breakpoints: &HashSet<Entity>,
+    snap_pills: bool,
+    compact: bool,
+    allow_self_transitions: bool,
+    theme: &EditorTheme,
+    rects_before: &mut HashMap<Entity, egui::Rect>,
     commands: &mut Commands,
 ) {
     // Build render queue with z-order based on hierarchy depth
     let mut render_queue = Vec::new();
     
-    // Get all entities in depth-first order for natural z-ordering
-    let mut hierarchy_entities: Vec<Entity> = q_children
-        .iter_descendants_depth_first(selected_root)
-        .collect();
-    hierarchy_entities.insert(0, selected_root);
+    // Get all entities in depth-first order for natural z-ordering; siblings follow their z-bias
+    let hierarchy_entities = stacked_depth_first(selected_root, &persistent_data.z_bias, q_children);
+    
+    // Descendants of collapsed parents are not rendered (kept on the transient data for keyboard navigation)
+    transient_data.hidden_nodes.clear();
+    transient_data.hidden_nodes.extend(
+        hierarchy_entities
+            .iter()
+            .copied()
+            .filter(|entity| persistent_data.collapsed_proxy(*entity, q_child_of).is_some()),
+    );
     
     for (hierarchy_index, entity) in hierarchy_entities.iter().enumerate() {
+        if transient_data.hidden_nodes.contains(entity) {
+            continue;
+        }
         if let Some(_node) = persistent_data.nodes.get(entity) {
             let base_z_order = hierarchy_index as i32 * 10;
             let selection_boost = if should_get_selection_boost(*entity, transient_data.selected_node, q_child_of) { 
@@ -156,29 +243,39 @@ fn render_machine_content(
     // Sort by z-order (lower values render first, higher values on top)
     render_queue.sort_by_key(|item| item.z_order);
     
+    // Remember node rects so any move or resize during rendering marks the layout dirty
+    // (the map is the caller's scratch buffer, reused across machines and frames)
+    rects_before.clear();
+    rects_before.extend(persistent_data.nodes.iter().map(|(entity, node)| (*entity, node.current_rect())));
+    
     // Render all nodes in z-order
     for render_item in render_queue {
         let entity = render_item.entity;
-        let entity_name = get_entity_name(entity, all_entities);
+        let entity_name = entity_display_name(entity, all_entities);
+        let base_color = persistent_data.node_colors.get(&entity).copied();
         
         if let Some(node) = persistent_data.nodes.get_mut(&entity) {
-            let is_selected = selected_entity == Some(entity);
+            let is_selected = selected_entity == Some(entity) || selected_nodes.contains(&entity);
             let is_root = selected_root == entity;
             let is_editing = transient_data.text_editing.is_editing(entity);
             let should_focus = transient_data.text_editing.should_focus;
             
             let first_focus = transient_data.text_editing.first_focus;
+            let entity_id = show_entity_ids.then(|| format!("{:?}", entity));
             
-            // Determine node color (active solid gold, else gold->grey pulse)
-            let node_color = Some(get_node_display_color(entity, q_active, &transient_data.node_pulses));
+            // Determine node color (active solid gold, else gold->grey pulse), tinted while picking a transition target
+            let display_color = get_node_display_color(entity, q_active, &transient_data.node_pulses, base_color, theme);
+            let node_color = Some(tint_for_target_selection(display_color, entity, &transient_data.transition_creation));
             
             let response = match node {
                 NodeType::Leaf(leaf_node) => {
+                    leaf_node.entity_node.set_compact(compact);
+                    leaf_node.entity_node.theme = *theme;
                     let dotted = is_direct_child_of_parallel(entity, q_child_of, q_parallel);
                     leaf_node.show_with_border_style(
                         ui, 
                         &entity_name, 
-                        Some(&format!("{:?}", entity)), 
+                        entity_id.as_deref(), 
                         is_selected, 
                         is_editing, 
                         &mut transient_data.text_editing.current_text, 
@@ -189,11 +286,13 @@ fn render_machine_content(
                     )
                 }
                 NodeType::Parent(parent_node) => {
+                    parent_node.set_compact(compact);
+                    parent_node.entity_node.theme = *theme;
                     let dotted = is_direct_child_of_parallel(entity, q_child_of, q_parallel);
                     parent_node.show_with_border_style(
                         ui, 
                         &entity_name, 
-                        Some(&format!("{:?}", entity)), 
+                        entity_id.as_deref(), 
                         is_selected, 
                         is_root, 
                         is_editing, 
@@ -206,6 +305,74 @@ fn render_machine_content(
                 }
             };
             
+            // Outline nodes flagged by the last validation run
+            if let Some(issues) = transient_data.validation_issues.get(&entity) {
+                let rect = node.current_rect().expand(3.0);
+                ui.painter().rect_stroke(
+                    rect,
+                    egui::CornerRadius::same(12),
+                    egui::Stroke::new(2.0, egui::Color32::RED),
+                    egui::StrokeKind::Outside,
+                );
+                let hovered = layer_pointer_pos(ui).is_some_and(|pos| rect.contains(pos));
+                if hovered {
+                    let text = issues.iter().map(|issue| issue.description()).collect::<Vec<_>>().join("\n");
+                    egui::show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), egui::Id::new("validation_issues").with(entity), |ui| {
+                        ui.label(text);
+                    });
+                }
+            }
+            
+            // Warn about compound states the machine cannot enter (no InitialState to resolve)
+            let missing_initial_state = matches!(node, NodeType::Parent(_))
+                && q_parallel.get(entity).is_err()
+                && q_children.get(entity).is_ok_and(|children| children.into_iter().next().is_some())
+                && all_entities.get(entity).is_ok_and(|(_, _, initial_state, _)| initial_state.is_none());
+            if missing_initial_state {
+                let rect = node.current_rect().expand(6.0);
+                crate::components::draw_dotted_rect(
+                    ui.painter(),
+                    rect,
+                    egui::CornerRadius::same(14),
+                    egui::Stroke::new(1.5, egui::Color32::RED),
+                    6.0,
+                    4.0,
+                );
+                if layer_pointer_pos(ui).is_some_and(|pos| rect.contains(pos) && !rect.shrink(12.0).contains(pos)) {
+                    egui::show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), egui::Id::new("missing_initial_state").with(entity), |ui| {
+                        ui.label("No initial state: the machine can't enter this region. Right-click a child → Set as Initial State.");
+                    });
+                }
+            }
+            
+            // Red dot on states with a breakpoint
+            if breakpoints.contains(&entity) {
+                let center = node.current_rect().left_top() + egui::vec2(2.0, 2.0);
+                ui.painter().circle_filled(center, 5.0, egui::Color32::from_rgb(220, 50, 50));
+                ui.painter().circle_stroke(center, 5.0, egui::Stroke::new(1.0, theme.text_light));
+            }
+
+            // Badge with the number of gameplay components on the state; hover lists them
+            if let Some(components) = transient_data.node_components.get(&entity).filter(|components| !components.is_empty()) {
+                let center = node.current_rect().right_bottom();
+                let text = components.len().to_string();
+                let font_id = egui::FontId::proportional(10.0);
+                let badge_fill = egui::Color32::from_rgb(70, 90, 140);
+                let text_color = theme.text_color_for_bg(badge_fill);
+                let galley = ui.painter().layout_no_wrap(text, font_id, text_color);
+                let radius = (galley.size().x * 0.5 + 4.0).max(8.0);
+                let badge_rect = egui::Rect::from_center_size(center, egui::vec2(radius * 2.0, 16.0));
+                ui.painter().rect_filled(badge_rect, egui::CornerRadius::same(8), badge_fill);
+                ui.painter().galley(center - galley.size() * 0.5, galley, text_color);
+                if layer_pointer_pos(ui).is_some_and(|pos| badge_rect.contains(pos)) {
+                    egui::show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), egui::Id::new("node_components").with(entity), |ui| {
+                        for component in components {
+                            ui.label(component);
+                        }
+                    });
+                }
+            }
+            
             // Clear focus flag after first frame
             if should_focus {
                 transient_data.text_editing.should_focus = false;
@@ -220,12 +387,31 @@ fn render_machine_content(
             if response.clicked {
                 // If we're currently picking a transition target, treat this click as selecting the target
                 if transient_data.transition_creation.awaiting_target_selection {
-                    let pointer_pos = ui.input(|i| i.pointer.hover_pos().unwrap_or_default());
-                    transient_data.transition_creation.set_target(entity, pointer_pos);
+                    if !allow_self_transitions && transient_data.transition_creation.source_entity == Some(entity) {
+                        warn!("⚠️ Self-transitions are disabled (EditorConfig::allow_self_transitions)");
+                    } else {
+                        let pointer_pos = ui.input(|i| i.pointer.hover_pos().unwrap_or_default());
+                        transient_data.transition_creation.set_target(entity, pointer_pos);
+                    }
                 }
                 commands.trigger(crate::Select { selected: Some(entity) });
             }
             
+            // Handle collapse toggle in the parent header
+            if response.collapse_toggled {
+                if let NodeType::Parent(parent_node) = node {
+                    if persistent_data.collapsed.remove(&entity) {
+                        // Size is recalculated from the children on the next update
+                        parent_node.collapsed = false;
+                        persistent_data.layout_dirty = true;
+                    } else {
+                        persistent_data.collapsed.insert(entity);
+                        parent_node.collapsed = true;
+                        parent_node.entity_node.current_size = parent_node.collapsed_size();
+                    }
+                }
+            }
+            
             // Handle + button click for transition creation (leaf nodes only)
             if response.add_transition_clicked {
                 commands.trigger(crate::Select { selected: Some(entity) });
@@ -234,6 +420,19 @@ fn render_machine_content(
                 });
             }
             
+            // Double-clicking a label starts inline renaming (same as context menu → Rename)
+            if response.label_double_clicked && !transient_data.text_editing.is_editing(entity) {
+                commands.trigger(crate::Select { selected: Some(entity) });
+                transient_data.text_editing.start_editing(entity, &entity_raw_name(entity, all_entities));
+            }
+            
+            // Double-clicking an expanded parent's body adds a leaf child under the pointer
+            if response.body_double_clicked && !persistent_data.collapsed.contains(&entity) {
+                if let Some(position) = layer_pointer_pos(ui) {
+                    commands.trigger(crate::AddChildAt { parent: entity, position });
+                }
+            }
+            
             // Handle right-click context menu
             if response.right_clicked {
                 let pointer_pos = ui.input(|i| i.pointer.hover_pos().unwrap_or_default());
@@ -247,11 +446,109 @@ fn render_machine_content(
             // Handle dragging
             if response.dragged {
                 // Node was dragged - position is automatically updated in the component
+                let mut drag_delta = response.drag_delta;
+                
+                // Remember where the drag started so the whole move can be undone at once
+                if !transient_data.drag_origin.is_some_and(|(dragged_entity, _)| dragged_entity == entity) {
+                    transient_data.drag_origin = Some((entity, node.position() - response.drag_delta));
+                }
+                
+                // Snap to grid unless Alt is held; track the unsnapped position so small deltas accumulate
+                let snap_disabled = ui.input(|i| i.modifiers.alt);
+                if let Some(grid_size) = grid_snap.filter(|_| !snap_disabled) {
+                    let previous_position = node.position() - response.drag_delta;
+                    let unsnapped = match transient_data.drag_unsnapped_position {
+                        Some((dragged_entity, position)) if dragged_entity == entity => position,
+                        _ => previous_position,
+                    } + response.drag_delta;
+                    transient_data.drag_unsnapped_position = Some((entity, unsnapped));
+                    
+                    let snapped = snap_to_grid(unsnapped, grid_size);
+                    node.set_position(snapped);
+                    drag_delta = snapped - previous_position;
+                } else {
+                    transient_data.drag_unsnapped_position = None;
+                }
+                
                 // Emit event to handle parent-child movement
-                commands.trigger(NodeDragged {
-                    entity,
-                    drag_delta: response.drag_delta,
-                });
+                if drag_delta != egui::Vec2::ZERO {
+                    if selected_nodes.contains(&entity) {
+                        // Move the whole multi-selection; each outermost member carries its subtree
+                        let outermost = outermost_selected_nodes(selected_nodes, q_child_of);
+                        if !outermost.contains(&entity) {
+                            // A selected ancestor carries this node along
+                            let position = node.position();
+                            node.set_position(position - drag_delta);
+                        }
+                        for member in outermost {
+                            if member != entity {
+                                if let Some(member_node) = persistent_data.nodes.get_mut(&member) {
+                                    let position = member_node.position();
+                                    member_node.set_position(position + drag_delta);
+                                }
+                            }
+                            commands.trigger(NodeDragged {
+                                entity: member,
+                                drag_delta,
+                            });
+                        }
+                    } else {
+                        commands.trigger(NodeDragged {
+                            entity,
+                            drag_delta,
+                        });
+                    }
+                }
+            } else {
+                if transient_data.drag_unsnapped_position.is_some_and(|(dragged_entity, _)| dragged_entity == entity) {
+                    // Drag ended; forget the unsnapped position
+                    transient_data.drag_unsnapped_position = None;
+                }
+                if let Some((dragged_entity, origin)) = transient_data.drag_origin {
+                    if dragged_entity == entity {
+                        // Drag ended; record the total movement for undo
+                        transient_data.drag_origin = None;
+                        let delta = node.position() - origin;
+                        if delta != egui::Vec2::ZERO {
+                            commands.trigger(NodeMoved { entity, delta });
+                        }
+                        // Dropped into a different zone: move the node under that parent
+                        if let Some(new_parent) = transient_data.reparent_target.take() {
+                            let current_parent = q_child_of.get(entity).ok().map(|child_of| child_of.0);
+                            if current_parent.is_some_and(|parent| parent != new_parent) {
+                                commands.trigger(ReparentNode { entity, new_parent });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    
+    if persistent_data.nodes.iter().any(|(entity, node)| rects_before.get(entity) != Some(&node.current_rect())) {
+        persistent_data.mark_layout_dirty();
+    }
+    
+    // Track the parent zone under the pointer while a single (non-root) node is dragged
+    transient_data.reparent_target = transient_data
+        .drag_origin
+        .map(|(dragged, _)| dragged)
+        .filter(|dragged| *dragged != selected_root && !selected_nodes.contains(dragged))
+        .map(|dragged| {
+            layer_pointer_pos(ui)
+                .and_then(|pos| drop_zone_at(persistent_data, pos, dragged, &transient_data.hidden_nodes, q_child_of))
+                .unwrap_or(selected_root)
+        });
+    
+    // Tint the zone the node would be moved into when it differs from its current parent
+    if let (Some(target), Some((dragged, _))) = (transient_data.reparent_target, transient_data.drag_origin) {
+        let current_parent = q_child_of.get(dragged).ok().map(|child_of| child_of.0);
+        if current_parent != Some(target) {
+            if let Some(zone) = persistent_data.nodes.get(&target) {
+                let rect = zone.current_rect();
+                let green = egui::Color32::from_rgb(80, 200, 120);
+                ui.painter().rect_filled(rect, egui::CornerRadius::same(10), green.gamma_multiply(0.12));
+                ui.painter().rect_stroke(rect, egui::CornerRadius::same(10), egui::Stroke::new(2.0, green), egui::StrokeKind::Outside);
             }
         }
     }
@@ -260,16 +557,22 @@ fn render_machine_content(
     update_transition_rectangles(persistent_data, q_child_of);
     
     // Render transition arrows after all nodes
-    render_transition_connections(ui, persistent_data, transient_data, q_child_of, commands);
+    let active_states: HashSet<Entity> = persistent_data
+        .nodes
+        .keys()
+        .copied()
+        .filter(|entity| q_active.contains(*entity))
+        .collect();
+    render_transition_connections(ui, persistent_data, transient_data, &active_states, all_entities, q_child_of, preview_event, snap_pills, compact, theme, commands);
     
     // Render initial state indicators
-    render_initial_state_indicators(ui, persistent_data, &all_entities, selected_root);
+    render_initial_state_indicators(ui, persistent_data, &all_entities, q_child_of, selected_root, &transient_data.hidden_nodes, show_initial_indicators, theme.transition, commands);
     
     // Handle background clicks to cancel transition creation
     if transient_data.transition_creation.awaiting_target_selection {
         // Check for clicks on background (not handled by any node)
         if ui.input(|i| i.pointer.primary_clicked()) {
-            let pointer_pos = ui.input(|i| i.pointer.hover_pos().unwrap_or_default());
+            let pointer_pos = layer_pointer_pos(ui).unwrap_or_default();
             let clicked_on_node = persistent_data.nodes.values().any(|node| {
                 node.current_rect().contains(pointer_pos)
             });
@@ -284,7 +587,246 @@ fn render_machine_content(
     handle_text_editing_completion(ui, transient_data, commands);
     
     // Render transition creation UI
-    render_transition_creation_ui(ui, persistent_data, transient_data, commands);
+    render_transition_creation_ui(ui, persistent_data, transient_data, theme, commands);
+}
+
+/// Render the snapping grid as faint dots covering the visible canvas
+///
+/// Dots sit on grid multiples in node coordinates and are panned and scaled by `transform`, so they
+/// line up with the positions nodes snap to at any zoom.
+pub fn render_grid_dots(ui: &egui::Ui, grid_size: f32, transform: CanvasTransform, canvas_origin: egui::Pos2, dot_color: egui::Color32) {
+    let painter = ui.painter();
+    for dot in grid_dot_positions(ui.clip_rect(), grid_size, transform, canvas_origin) {
+        painter.circle_filled(dot, 1.0, dot_color);
+    }
+}
+
+/// Screen positions of the grid dots inside `clip_rect`
+///
+/// Nothing is drawn once zooming out packs the dots closer than 4 screen pixels.
+fn grid_dot_positions(clip_rect: egui::Rect, grid_size: f32, transform: CanvasTransform, canvas_origin: egui::Pos2) -> Vec<egui::Pos2> {
+    if grid_size * transform.zoom < 4.0 {
+        return Vec::new();
+    }
+    let min = transform.from_screen(clip_rect.min, canvas_origin);
+    let max = transform.from_screen(clip_rect.max, canvas_origin);
+    let start_x = (min.x / grid_size).ceil() * grid_size;
+    let start_y = (min.y / grid_size).ceil() * grid_size;
+    let mut dots = Vec::new();
+    let mut y = start_y;
+    while y <= max.y {
+        let mut x = start_x;
+        while x <= max.x {
+            dots.push(transform.to_screen(egui::Pos2::new(x, y), canvas_origin));
+            x += grid_size;
+        }
+        y += grid_size;
+    }
+    dots
+}
+
+/// System to abandon in-progress transition creation
+///
+/// Escape (`EditorHotkeys::cancel_transition`) cancels it in every machine regardless of which
+/// widget has focus (the key is read from Bevy input, not egui). A creation whose source or
+/// target state no longer exists is dropped as well, so nothing is left pointing at a despawned entity.
+pub fn cancel_stale_transition_creation(
+    input: Option<Res<ButtonInput<KeyCode>>>,
+    hotkeys: Res<EditorHotkeys>,
+    mut q_transient: Query<&mut StateMachineTransientData>,
+    entities: &bevy::ecs::entity::Entities,
+) {
+    let escape = input.is_some_and(|input| hotkeys.cancel_transition.just_pressed(&input));
+    for mut transient_data in &mut q_transient {
+        if !transient_data.transition_creation.is_active() {
+            continue;
+        }
+        if escape {
+            transient_data.transition_creation.cancel();
+        } else if transient_data.transition_creation.cancel_if_endpoint(|entity| !entities.contains(entity)) {
+            info!("Cancelled transition creation: its source or target was removed");
+        }
+    }
+}
+
+/// Record whether a text field has keyboard focus before this frame's editor UI runs
+pub fn record_text_focus(
+    mut q_editor_context: Query<&mut EguiContext, (With<EditorWindow>, Without<PrimaryEguiContext>)>,
+    current_window: Res<CurrentEditorWindow>,
+    mut editor_state: ResMut<EditorState>,
+) {
+    let Ok(mut egui_context) = q_editor_context.get_mut(current_window.camera) else {
+        return;
+    };
+    editor_state.text_focus_at_frame_start = egui_context.get_mut().wants_keyboard_input();
+}
+
+/// System to move the selection between nodes with the keyboard
+///
+/// Arrow keys select the spatially nearest node in that direction (by rect center);
+/// Tab / Shift+Tab cycle through the selected node's siblings in `StateChildren` order.
+/// The keys are configurable in `EditorHotkeys`.
+/// Ignored while any text field has keyboard focus or a node is being renamed.
+pub fn handle_keyboard_navigation(
+    mut q_editor_context: Query<&mut EguiContext, (With<EditorWindow>, Without<PrimaryEguiContext>)>,
+    current_window: Res<CurrentEditorWindow>,
+    hotkeys: Res<EditorHotkeys>,
+    editor_state: Res<EditorState>,
+    q_sm: Query<(&StateMachinePersistentData, &StateMachineTransientData), With<StateMachine>>,
+    q_child_of: Query<&bevy_gearbox::StateChildOf>,
+    q_children: Query<&bevy_gearbox::StateChildren>,
+    mut commands: Commands,
+) {
+    let Ok(mut egui_context) = q_editor_context.get_mut(current_window.camera) else {
+        return;
+    };
+    let ctx = egui_context.get_mut();
+    // Also checked at frame start: Enter in a single-line field has already released focus by now
+    if ctx.wants_keyboard_input() || editor_state.text_focus_at_frame_start {
+        return;
+    }
+    let Some(selected) = editor_state.view.selected_entity else {
+        return;
+    };
+    let root = q_child_of.root_ancestor(selected);
+    if !editor_state.is_machine_open(root) {
+        return;
+    }
+    let Ok((persistent_data, transient_data)) = q_sm.get(root) else {
+        return;
+    };
+    if transient_data.text_editing.editing_entity.is_some() {
+        return;
+    }
+
+    let (direction, sibling_step) = ctx.input(|i| {
+        let direction = if hotkeys.select_left.pressed_in(i) {
+            Some(egui::Vec2::new(-1.0, 0.0))
+        } else if hotkeys.select_right.pressed_in(i) {
+            Some(egui::Vec2::new(1.0, 0.0))
+        } else if hotkeys.select_up.pressed_in(i) {
+            Some(egui::Vec2::new(0.0, -1.0))
+        } else if hotkeys.select_down.pressed_in(i) {
+            Some(egui::Vec2::new(0.0, 1.0))
+        } else {
+            None
+        };
+        // Some(reverse) when a sibling step was requested
+        let sibling_step = if hotkeys.next_sibling.pressed_in(i) {
+            Some(false)
+        } else if hotkeys.previous_sibling.pressed_in(i) {
+            Some(true)
+        } else {
+            None
+        };
+        (direction, sibling_step)
+    });
+    
+    // Enter adds a sibling below the selection, outliner-style
+    let enter = ctx.input_mut(|i| hotkeys.add_sibling.consume_in(i));
+    if enter && q_child_of.get(selected).is_ok() {
+        commands.trigger(NodeActionTriggered { entity: selected, action: NodeAction::AddSibling });
+        return;
+    }
+
+    let next = if let Some(direction) = direction {
+        nearest_node_in_direction(persistent_data, &transient_data.hidden_nodes, selected, direction)
+    } else if let Some(reverse) = sibling_step {
+        next_sibling(selected, reverse, &transient_data.hidden_nodes, &q_child_of, &q_children)
+    } else {
+        None
+    };
+
+    if let Some(next) = next {
+        commands.trigger(Select { selected: Some(next) });
+    }
+}
+
+/// `root` followed by its descendants in depth-first order, siblings stably sorted by z-bias
+///
+/// Raising a state therefore raises its whole subtree above overlapping siblings.
+fn stacked_depth_first(
+    root: Entity,
+    z_bias: &HashMap<Entity, i32>,
+    q_children: &Query<&bevy_gearbox::StateChildren>,
+) -> Vec<Entity> {
+    let mut ordered = Vec::new();
+    let mut stack = vec![root];
+    while let Some(entity) = stack.pop() {
+        ordered.push(entity);
+        if let Ok(children) = q_children.get(entity) {
+            let mut children: Vec<Entity> = children.iter().collect();
+            children.sort_by_key(|child| z_bias.get(child).copied().unwrap_or_default());
+            // Reversed so the lowest-biased child is popped (and drawn) first
+            stack.extend(children.into_iter().rev());
+        }
+    }
+    ordered
+}
+
+/// Find the node whose center lies closest to `from` in the given direction
+///
+/// Nodes off to the side are penalized so that movement feels aligned with the pressed key.
+/// `hidden` nodes (inside collapsed parents) are never picked.
+fn nearest_node_in_direction(
+    persistent_data: &StateMachinePersistentData,
+    hidden: &HashSet<Entity>,
+    from: Entity,
+    direction: egui::Vec2,
+) -> Option<Entity> {
+    let origin = persistent_data.nodes.get(&from)?.current_rect().center();
+    persistent_data
+        .nodes
+        .iter()
+        .filter(|(entity, _)| **entity != from && !hidden.contains(*entity))
+        .filter_map(|(entity, node)| {
+            let delta = node.current_rect().center() - origin;
+            let along = delta.dot(direction);
+            if along <= 0.0 {
+                return None;
+            }
+            let across = (delta - direction * along).length();
+            // Only consider nodes within 45 degrees of the pressed direction
+            if across > along {
+                return None;
+            }
+            Some((*entity, along + across * 2.0))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(entity, _)| entity)
+}
+
+/// Cycle to the next (or previous) visible sibling in `StateChildren` order, wrapping around
+fn next_sibling(
+    entity: Entity,
+    reverse: bool,
+    hidden: &HashSet<Entity>,
+    q_child_of: &Query<&bevy_gearbox::StateChildOf>,
+    q_children: &Query<&bevy_gearbox::StateChildren>,
+) -> Option<Entity> {
+    let parent = q_child_of.get(entity).ok()?.0;
+    let siblings: Vec<Entity> = q_children
+        .get(parent)
+        .ok()?
+        .iter()
+        .filter(|sibling| *sibling == entity || !hidden.contains(sibling))
+        .collect();
+    let index = siblings.iter().position(|sibling| *sibling == entity)?;
+    let count = siblings.len();
+    let next_index = if reverse { (index + count - 1) % count } else { (index + 1) % count };
+    (next_index != index).then(|| siblings[next_index])
+}
+
+/// Pointer position in the coordinate space of the ui's layer
+///
+/// Machines are rendered into zoomed layers, so raw screen positions must be mapped back
+/// before comparing them with node rects.
+fn layer_pointer_pos(ui: &egui::Ui) -> Option<egui::Pos2> {
+    let pointer_pos = ui.input(|i| i.pointer.hover_pos())?;
+    Some(match ui.ctx().layer_transform_to_global(ui.layer_id()) {
+        Some(transform) => transform.inverse() * pointer_pos,
+        None => pointer_pos,
+    })
 }
 
 /// Render the transition creation dropdown UI
@@ -292,6 +834,7 @@ fn render_transition_creation_ui(
     ui: &mut egui::Ui,
     persistent_data: &mut StateMachinePersistentData,
     transient_data: &mut StateMachineTransientData,
+    theme: &EditorTheme,
     commands: &mut Commands,
 ) {
     // Show visual arrow from source to mouse if we're waiting for target selection
@@ -299,15 +842,15 @@ fn render_transition_creation_ui(
         if let Some(source) = transient_data.transition_creation.source_entity {
             // Draw arrow from source entity to mouse cursor
             if let Some(source_node) = persistent_data.nodes.get(&source) {
-                let mouse_pos = ui.input(|i| i.pointer.hover_pos().unwrap_or_default());
+                let mouse_pos = layer_pointer_pos(ui).unwrap_or_default();
                 let source_rect = source_node.current_rect();
                 
                 // Draw from the edge of the source node to the mouse cursor
                 let source_edge = closest_point_on_rect_edge(source_rect, mouse_pos);
                 
-                // Draw a dashed line from source edge to mouse (white color)
+                // Draw a dashed line from source edge to mouse (transition line color)
                 let painter = ui.painter();
-                draw_dashed_arrow(&painter, source_edge, mouse_pos, egui::Color32::WHITE);
+                draw_dashed_arrow(&painter, source_edge, mouse_pos, theme.transition);
             }
             
             // Check for cancellation via right-click, escape key, or clicking background
@@ -328,8 +871,9 @@ fn render_transition_creation_ui(
             transient_data.transition_creation.dropdown_position,
         ) {
             let dropdown_id = egui::Id::new("transition_event_dropdown");
+            let mut picked_event_type = None;
             
-            egui::Area::new(dropdown_id)
+            let area_response = egui::Area::new(dropdown_id)
                 .fixed_pos(position)
                 .order(egui::Order::Foreground)
                 .show(ui.ctx(), |ui| {
@@ -339,32 +883,122 @@ fn render_transition_creation_ui(
                             ui.heading("Select Event Type");
                             ui.separator();
                             
-                            if transient_data.transition_creation.available_event_types.is_empty() {
+                            let creation = &mut transient_data.transition_creation;
+                            if creation.available_event_types.is_empty() {
                                 ui.label("No EventEdge event types found.");
                                 ui.label("Make sure event types are registered with the type registry.");
                             } else {
-                                for event_type in &transient_data.transition_creation.available_event_types.clone() {
-                                    if ui.button(event_type).clicked() {
-                                        commands.trigger(CreateTransition {
-                                            source_entity: source,
-                                            target_entity: target,
-                                            event_type: event_type.clone(),
+                                // Live filter; arrow keys move the highlight and Enter picks it
+                                let filter_response = ui.add(
+                                    egui::TextEdit::singleline(&mut creation.event_filter)
+                                        .hint_text("Filter events...")
+                                        .desired_width(f32::INFINITY),
+                                );
+                                if creation.event_filter_should_focus {
+                                    filter_response.request_focus();
+                                    creation.event_filter_should_focus = false;
+                                }
+                                if filter_response.changed() {
+                                    creation.highlighted_event = 0;
+                                }
+                                let filter = creation.event_filter.to_lowercase();
+                                let matches = |event_type: &&String| event_type.to_lowercase().contains(&filter);
+                                
+                                // Event types this machine already uses come first; the rest go under "More"
+                                let used: Vec<&String> = creation
+                                    .machine_event_types
+                                    .iter()
+                                    .filter(|event_type| creation.available_event_types.contains(event_type))
+                                    .filter(matches)
+                                    .collect();
+                                let rest: Vec<&String> = creation
+                                    .available_event_types
+                                    .iter()
+                                    .filter(|event_type| !used.contains(event_type) && !creation.machine_event_types.contains(event_type))
+                                    .filter(matches)
+                                    .collect();
+                                let show_flat = used.is_empty() || !filter.is_empty();
+                                let navigable: Vec<&String> = if show_flat {
+                                    used.iter().chain(rest.iter()).copied().collect()
+                                } else {
+                                    used.clone()
+                                };
+                                
+                                // Enter is consumed so keyboard navigation doesn't also add a sibling
+                                let (down, up, enter) = ui.input_mut(|i| (
+                                    i.key_pressed(egui::Key::ArrowDown),
+                                    i.key_pressed(egui::Key::ArrowUp),
+                                    i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                                ));
+                                let mut highlighted = creation.highlighted_event.min(navigable.len().saturating_sub(1));
+                                if down {
+                                    highlighted = (highlighted + 1).min(navigable.len().saturating_sub(1));
+                                }
+                                if up {
+                                    highlighted = highlighted.saturating_sub(1);
+                                }
+                                if enter {
+                                    picked_event_type = navigable.get(highlighted).map(|event_type| (*event_type).clone());
+                                }
+                                
+                                let mut event_button = |ui: &mut egui::Ui, event_type: &String| {
+                                    let is_highlighted = navigable.get(highlighted) == Some(&event_type);
+                                    let response = ui.selectable_label(is_highlighted, event_type);
+                                    if is_highlighted && (down || up) {
+                                        response.scroll_to_me(None);
+                                    }
+                                    if response.clicked() {
+                                        picked_event_type = Some(event_type.clone());
+                                    }
+                                };
+                                if navigable.is_empty() {
+                                    ui.weak("No matching event types");
+                                } else if show_flat {
+                                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                                        for event_type in &navigable {
+                                            event_button(ui, *event_type);
+                                        }
+                                    });
+                                } else {
+                                    ui.weak("Used in this machine");
+                                    for event_type in &used {
+                                        event_button(ui, *event_type);
+                                    }
+                                    if !rest.is_empty() {
+                                        ui.collapsing(format!("More ({})", rest.len()), |ui| {
+                                            egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                                                for event_type in &rest {
+                                                    event_button(ui, *event_type);
+                                                }
+                                            });
                                         });
                                     }
                                 }
+                                creation.highlighted_event = highlighted;
                             }
                             
                             ui.separator();
+                            ui.checkbox(&mut transient_data.transition_creation.internal, "Internal")
+                                .on_hover_text("Internal transitions don't exit and re-enter the source state");
                             if ui.button("Cancel").clicked() {
                                 transient_data.transition_creation.cancel();
                             }
                         });
                 });
             
+            if let Some(event_type) = picked_event_type {
+                commands.trigger(CreateTransition {
+                    source_entity: source,
+                    target_entity: target,
+                    event_type,
+                    internal: transient_data.transition_creation.internal,
+                });
+            }
+            
             // Close dropdown if clicked elsewhere
             if ui.input(|i| i.pointer.any_click()) {
                 let pointer_pos = ui.input(|i| i.pointer.hover_pos().unwrap_or_default());
-                let dropdown_rect = egui::Rect::from_min_size(position, egui::Vec2::new(200.0, 150.0));
+                let dropdown_rect = area_response.response.rect;
                 
                 if !dropdown_rect.contains(pointer_pos) {
                     transient_data.transition_creation.cancel();
@@ -378,48 +1012,211 @@ fn render_transition_creation_ui(
 fn render_transition_connections(
     ui: &mut egui::Ui,
     persistent_data: &mut StateMachinePersistentData,
-    transient_data: &StateMachineTransientData,
+    transient_data: &mut StateMachineTransientData,
+    active_states: &HashSet<Entity>,
+    all_entities: &EntityNamesQuery,
     q_child_of: &Query<&bevy_gearbox::StateChildOf>,
+    preview_event: Option<&str>,
+    snap_pills: bool,
+    compact: bool,
+    theme: &EditorTheme,
     commands: &mut Commands,
 ) {
+    // Transitions entirely inside a collapsed parent are hidden along with their endpoints
+    let is_hidden_inside_collapsed = |transition: &crate::TransitionConnection| {
+        let source = persistent_data.collapsed_proxy(transition.source_entity, q_child_of).unwrap_or(transition.source_entity);
+        let target = persistent_data.collapsed_proxy(transition.target_entity, q_child_of).unwrap_or(transition.target_entity);
+        source == target && (source != transition.source_entity || target != transition.target_entity)
+    };
+    
     // Extract data needed for rendering to avoid borrowing issues
-    let transitions_data: Vec<_> = persistent_data.visual_transitions.iter().enumerate().map(|(index, transition)| {
-        let transition_color = get_transition_color(
+    let transitions_data: Vec<_> = persistent_data.visual_transitions.iter().enumerate().filter(|(_, transition)| !is_hidden_inside_collapsed(transition)).map(|(index, transition)| {
+        let mut transition_color = get_transition_color(
             transition.edge_entity,
-            &transient_data.transition_pulses
+            &transient_data.transition_pulses,
+            theme,
         );
+        if transition.is_disabled {
+            transition_color = theme.disabled_transition;
+        }
+        // Elbow routing replaces the two segments through the event node; the pill sits on the longest segment
+        let route = (persistent_data.orthogonal_edges
+            && !transition.is_self_transition()
+            && !is_ancestor_of(transition.source_entity, transition.target_entity, q_child_of)
+            && !is_ancestor_of(transition.target_entity, transition.source_entity, q_child_of))
+            .then(|| orthogonal_route(transition.source_rect, transition.target_rect, transition.sibling_index, transition.sibling_count));
+        let event_pos = route.as_deref().map(longest_segment_midpoint).unwrap_or(transition.event_node_position);
+        let mut segment_points = transition.calculate_two_segment_points();
+        if let Some(target_end) = route.as_ref().and_then(|route| route.last()) {
+            segment_points.3 = *target_end;
+        }
+        // Eventless transitions are marked with an epsilon on their pill, internal ones with a loop
+        let mut label = transition.event_type.clone();
+        if transition.is_always {
+            label = format!("ε {}", label);
+        }
+        if transition.is_internal {
+            label = format!("↻ {}", label);
+        }
         (index, 
-         transition.calculate_two_segment_points(),
-         transition.event_node_position,
-         transition.event_type.clone(),
+         segment_points,
+         event_pos,
+         label,
          transition.is_dragging_event_node,
-         transition_color)
+         transition_color,
+         route)
     }).collect();
     
+    // Edge under the cursor (closest line within a small threshold, or its pill)
+    const EDGE_HOVER_DISTANCE: f32 = 6.0;
+    let pointer_pos = layer_pointer_pos(ui).filter(|_| !ui.input(|i| i.pointer.any_down()));
+    let hovered_edge = pointer_pos.and_then(|pointer| {
+        transitions_data
+            .iter()
+            .filter_map(|(index, (source_start, source_end, target_start, target_end), event_pos, _, _, _, route)| {
+                let segments: Vec<(egui::Pos2, egui::Pos2)> = match route {
+                    Some(route) => route.windows(2).map(|segment| (segment[0], segment[1])).collect(),
+                    None => vec![(*source_start, *source_end), (*target_start, *target_end)],
+                };
+                let line_distance = segments
+                    .iter()
+                    .map(|(a, b)| distance_to_segment(pointer, *a, *b))
+                    .fold(f32::INFINITY, f32::min);
+                let pill_distance = (pointer.distance(*event_pos) - 14.0).max(0.0);
+                let distance = line_distance.min(pill_distance);
+                (distance <= EDGE_HOVER_DISTANCE).then_some((*index, distance))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index)
+    });
+    if let Some(index) = hovered_edge {
+        let transition = &persistent_data.visual_transitions[index];
+        egui::show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), egui::Id::new("transition_hover").with(transition.edge_entity), |ui| {
+            ui.label(format!("{} → {}", entity_display_name(transition.source_entity, all_entities), entity_display_name(transition.target_entity, all_entities)));
+            ui.label(format!("Event: {}", transition.event_type));
+            ui.label(if transition.is_internal { "Kind: internal" } else { "Kind: external" });
+            ui.label(format!("Edge: {:?}", transition.edge_entity));
+        });
+    }
+    
     let painter = ui.painter();
     let mut interaction_data = Vec::new();
     
     // First pass: Draw all the arrows (using painter)
-    for (index, (source_start, source_end, target_start, target_end), event_pos, _event_type, _is_dragging, _color) in &transitions_data {
+    for (index, (source_start, source_end, target_start, target_end), event_pos, _event_type, _is_dragging, _color, route) in &transitions_data {
         let tconn = &persistent_data.visual_transitions[*index];
         let source_rect = tconn.source_rect;
         let is_ancestor = is_ancestor_of(tconn.source_entity, tconn.target_entity, q_child_of);
-        if is_ancestor {
+        // Edges leading into the active configuration are drawn as a thick gold trail
+        let mut stroke = if tconn.is_disabled {
+            egui::Stroke::new(2.0, theme.disabled_transition)
+        } else if is_on_active_path(tconn, active_states, q_child_of) {
+            egui::Stroke::new(4.0, theme.active)
+        } else {
+            egui::Stroke::new(2.0, theme.transition)
+        };
+        // Thicken the edge under the cursor
+        if hovered_edge == Some(*index) {
+            stroke.width += 2.0;
+        }
+        // Edges that would fire for the event hovered in the "Send event" window
+        let previewed = preview_event == Some(tconn.listener_event.as_str());
+        if previewed {
+            stroke = egui::Stroke::new(stroke.width + 2.0, TARGET_CANDIDATE_COLOR);
+        }
+        // Always and disabled transitions use dashed straight runs so they stand apart from event-driven ones
+        let dashed = tconn.is_always || tconn.is_disabled;
+        let arrow = |start: egui::Pos2, end: egui::Pos2| {
+            if dashed {
+                draw_dashed_arrow_with_stroke(&painter, start, end, stroke);
+            } else {
+                draw_arrow_with_stroke(&painter, start, end, stroke);
+            }
+        };
+        if let Some(route) = route {
+            // Orthogonal elbow: straight runs with an arrowhead on the final segment
+            for segment in route.windows(2).take(route.len().saturating_sub(2)) {
+                if dashed {
+                    painter.add(egui::Shape::dashed_line(&[segment[0], segment[1]], stroke, 8.0, 4.0));
+                } else {
+                    painter.line_segment([segment[0], segment[1]], stroke);
+                }
+            }
+            if let [.., last_start, last_end] = route.as_slice() {
+                arrow(*last_start, *last_end);
+            }
+        } else if tconn.is_self_transition() {
+            // Loop out of the top edge, through the event node, and back into the right edge
+            draw_self_loop(&painter, source_rect, *event_pos, stroke);
+        } else if is_ancestor {
             // Curved segment from parent to event node, straight segment from event node to target
-            draw_fish_hook_to_point(&painter, source_rect, *event_pos, egui::Color32::WHITE);
-            draw_arrow(&painter, *event_pos, *target_end, egui::Color32::WHITE);
+            draw_fish_hook_to_point(&painter, source_rect, *event_pos, stroke);
+            arrow(*event_pos, *target_end);
         } else {
             // Default two-segment
-            draw_arrow(&painter, *source_start, *source_end, egui::Color32::WHITE);
-            draw_arrow(&painter, *target_start, *target_end, egui::Color32::WHITE);
+            arrow(*source_start, *source_end);
+            arrow(*target_start, *target_end);
+        }
+        
+        // While the transition fires, march dashes from source toward target, fading with the pulse
+        if let Some(pulse) = transient_data.transition_pulses.iter().find(|p| p.edge_entity == tconn.edge_entity) {
+            // Self-loops are curves; their color pulse alone is enough
+            let path: Vec<egui::Pos2> = match route {
+                Some(route) => route.clone(),
+                None if tconn.is_self_transition() => Vec::new(),
+                None if is_ancestor => vec![*event_pos, *target_end],
+                None => vec![*source_start, *source_end, *target_start, *target_end],
+            };
+            let ants_color = theme.bright_active.gamma_multiply(pulse.intensity());
+            draw_marching_ants(&painter, &path, pulse.phase, egui::Stroke::new(stroke.width + 1.0, ants_color));
         }
     }
     
     // Second pass: Draw interactive event nodes (using ui mutably)
-    for (index, (_source_start, _source_end, _target_start, _target_end), event_pos, event_type, is_dragging, color) in transitions_data {
+    for (index, (_source_start, _source_end, _target_start, _target_end), event_pos, event_type, is_dragging, color, _route) in transitions_data.clone() {
+        let font_id = egui::FontId::new(if compact { 10.0 } else { 12.0 }, egui::FontFamily::Proportional);
+
+        // Replace the pill with a text field while its label is being renamed
+        let edge_entity = persistent_data.visual_transitions[index].edge_entity;
+        if transient_data.text_editing.is_editing(edge_entity) {
+            render_edge_label_editor(ui, event_pos, font_id, transient_data, theme);
+            continue;
+        }
+
         // Draw the interactive event node (keep existing placement for now)
-        let font_id = egui::FontId::new(12.0, egui::FontFamily::Proportional);
-        let response = draw_interactive_pill_label(ui, event_pos, &event_type, font_id, is_dragging, color);
+        let response = draw_interactive_pill_label(ui, event_pos, &event_type, font_id, pill_padding(compact), is_dragging, color, theme.text_color_for_bg(color));
+        if preview_event == Some(persistent_data.visual_transitions[index].listener_event.as_str()) {
+            ui.painter().rect_stroke(response.rect.expand(2.0), egui::CornerRadius::same(12), egui::Stroke::new(2.0, TARGET_CANDIDATE_COLOR), egui::StrokeKind::Outside);
+        }
+        
+        // Evaluation order badge left of the pill when several edges share this source and event
+        if let Some((order, _)) = persistent_data.visual_transitions[index].order_badge {
+            let center = response.rect.left_center() - egui::vec2(9.0, 0.0);
+            let badge_fill = egui::Color32::from_rgb(90, 110, 160);
+            ui.painter().circle_filled(center, 7.0, badge_fill);
+            ui.painter().text(
+                center,
+                egui::Align2::CENTER_CENTER,
+                order.to_string(),
+                egui::FontId::proportional(10.0),
+                theme.text_color_for_bg(badge_fill),
+            );
+        }
+        
+        // Annotate guarded transitions next to the pill; hovering lists the guard types
+        let guards = &persistent_data.visual_transitions[index].guards;
+        if !guards.is_empty() {
+            let annotation = if guards.len() == 1 { "[guard]".to_string() } else { format!("[{} guards]", guards.len()) };
+            let annotation_font = egui::FontId::new(10.0, egui::FontFamily::Proportional);
+            let annotation_rect = ui.painter().text(
+                response.rect.right_center() + egui::vec2(4.0, 0.0),
+                egui::Align2::LEFT_CENTER,
+                annotation,
+                annotation_font,
+                egui::Color32::from_rgb(230, 190, 90),
+            );
+            ui.allocate_rect(annotation_rect, egui::Sense::hover()).on_hover_text(guards.join("\n"));
+        }
         
         // Store interaction data for later processing
         interaction_data.push((index, response));
@@ -441,6 +1238,11 @@ fn render_transition_connections(
             });
         }
         
+        // Pills follow the route in elbow mode, so they can't be dragged
+        if persistent_data.orthogonal_edges && !transition.is_self_transition() {
+            continue;
+        }
+        
         // Handle event node dragging
         if response.drag_started() {
             transition.is_dragging_event_node = true;
@@ -448,14 +1250,188 @@ fn render_transition_connections(
         
         if response.dragged() && transition.is_dragging_event_node {
             transition.event_node_position += response.drag_delta();
+            persistent_data.layout_dirty = true;
         }
         
         if response.drag_stopped() {
             transition.is_dragging_event_node = false;
             // Update the offset based on the new position
             transition.update_event_node_offset();
+            // Pull the pill back onto its line unless Alt is held
+            if snap_pills && !ui.input(|i| i.modifiers.alt) {
+                transition.snap_event_node_to_line();
+            }
+        }
+    }
+    
+    // Arrowhead handles: drag the target end of a transition onto another state to retarget it
+    for (index, (_source_start, _source_end, _target_start, target_end), event_pos, _event_type, _is_dragging, _color, _route) in &transitions_data {
+        let transition = &persistent_data.visual_transitions[*index];
+        if transition.is_self_transition() {
+            continue;
+        }
+        let edge_entity = transition.edge_entity;
+        let handle_rect = egui::Rect::from_center_size(*target_end, egui::Vec2::splat(14.0));
+        let response = ui.interact(handle_rect, egui::Id::new("transition_target_handle").with(edge_entity), egui::Sense::drag());
+        
+        if response.hovered() || response.dragged() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
+            ui.painter().circle_stroke(*target_end, 6.0, egui::Stroke::new(1.5, egui::Color32::LIGHT_BLUE));
+        }
+        
+        if response.dragged() {
+            if let Some(pointer_pos) = layer_pointer_pos(ui) {
+                draw_dashed_arrow(ui.painter(), *event_pos, pointer_pos, egui::Color32::LIGHT_BLUE);
+            }
+        }
+        
+        // Dropping over empty space leaves the transition pointing at its original target
+        if response.drag_stopped() {
+            let new_target = layer_pointer_pos(ui).and_then(|pos| topmost_node_at(persistent_data, pos, q_child_of));
+            let transition = &mut persistent_data.visual_transitions[*index];
+            if let Some(new_target) = new_target.filter(|new_target| *new_target != transition.target_entity) {
+                transition.target_entity = new_target;
+                commands.trigger(crate::RetargetTransition { edge_entity, new_target });
+            }
+        }
+    }
+}
+
+/// Find the innermost visible state whose rect contains `pos`
+fn topmost_node_at(
+    persistent_data: &StateMachinePersistentData,
+    pos: egui::Pos2,
+    q_child_of: &Query<&bevy_gearbox::StateChildOf>,
+) -> Option<Entity> {
+    persistent_data
+        .nodes
+        .iter()
+        .filter(|(entity, node)| node.current_rect().contains(pos) && persistent_data.collapsed_proxy(**entity, q_child_of).is_none())
+        .min_by(|(_, a), (_, b)| a.current_rect().area().total_cmp(&b.current_rect().area()))
+        .map(|(entity, _)| *entity)
+}
+
+/// Smallest visible parent zone containing `pos` that `dragged` could be moved into
+///
+/// The dragged node's own subtree is excluded, as are collapsed parents.
+fn drop_zone_at(
+    persistent_data: &StateMachinePersistentData,
+    pos: egui::Pos2,
+    dragged: Entity,
+    hidden_entities: &HashSet<Entity>,
+    q_child_of: &Query<&bevy_gearbox::StateChildOf>,
+) -> Option<Entity> {
+    persistent_data
+        .nodes
+        .iter()
+        .filter(|(entity, node)| {
+            matches!(node, NodeType::Parent(_))
+                && **entity != dragged
+                && !is_ancestor_of(dragged, **entity, q_child_of)
+                && !hidden_entities.contains(*entity)
+                && !persistent_data.collapsed.contains(*entity)
+                && node.current_rect().contains(pos)
+        })
+        .min_by(|(_, a), (_, b)| a.current_rect().area().total_cmp(&b.current_rect().area()))
+        .map(|(entity, _)| *entity)
+}
+
+/// Orthogonal route between two node rects, leaving and entering perpendicular to their edges
+///
+/// Nodes separated along one axis get a straight run or an S-shape (two bends); nodes that
+/// overlap on both axes get an L-shape. Siblings between the same pair are spread apart.
+fn orthogonal_route(source: egui::Rect, target: egui::Rect, sibling_index: usize, sibling_count: usize) -> Vec<egui::Pos2> {
+    let spread = (sibling_index as f32 - (sibling_count.saturating_sub(1)) as f32 * 0.5) * 12.0;
+    let delta = target.center() - source.center();
+    let separated_x = source.max.x < target.min.x || target.max.x < source.min.x;
+    let separated_y = source.max.y < target.min.y || target.max.y < source.min.y;
+
+    if separated_x && (delta.x.abs() >= delta.y.abs() || !separated_y) {
+        // Horizontal: leave the facing side, bend halfway
+        let (start_x, end_x) = if delta.x > 0.0 { (source.max.x, target.min.x) } else { (source.min.x, target.max.x) };
+        let start = egui::pos2(start_x, source.center().y + spread);
+        let end = egui::pos2(end_x, target.center().y + spread);
+        if (start.y - end.y).abs() < 1.0 {
+            return vec![start, end];
+        }
+        let mid_x = (start_x + end_x) * 0.5 + spread;
+        vec![start, egui::pos2(mid_x, start.y), egui::pos2(mid_x, end.y), end]
+    } else if separated_y {
+        // Vertical: leave the facing side, bend halfway
+        let (start_y, end_y) = if delta.y > 0.0 { (source.max.y, target.min.y) } else { (source.min.y, target.max.y) };
+        let start = egui::pos2(source.center().x + spread, start_y);
+        let end = egui::pos2(target.center().x + spread, end_y);
+        if (start.x - end.x).abs() < 1.0 {
+            return vec![start, end];
         }
+        let mid_y = (start_y + end_y) * 0.5 + spread;
+        vec![start, egui::pos2(start.x, mid_y), egui::pos2(end.x, mid_y), end]
+    } else {
+        // Overlapping: leave sideways, then drop onto the target's top or bottom
+        let start_x = if delta.x >= 0.0 { source.max.x } else { source.min.x };
+        let start = egui::pos2(start_x, source.center().y + spread);
+        let end_y = if delta.y >= 0.0 { target.min.y } else { target.max.y };
+        let corner = egui::pos2(target.center().x + spread, start.y);
+        vec![start, corner, egui::pos2(corner.x, end_y)]
+    }
+}
+
+/// Members of a multi-selection that have no selected ancestor
+pub(crate) fn outermost_selected_nodes(selected_nodes: &HashSet<Entity>, q_child_of: &Query<&bevy_gearbox::StateChildOf>) -> Vec<Entity> {
+    selected_nodes
+        .iter()
+        .copied()
+        .filter(|entity| {
+            let mut current = *entity;
+            while let Ok(child_of) = q_child_of.get(current) {
+                if selected_nodes.contains(&child_of.0) {
+                    return false;
+                }
+                current = child_of.0;
+            }
+            true
+        })
+        .collect()
+}
+
+/// Shortest distance from `point` to the segment `a`-`b`
+fn distance_to_segment(point: egui::Pos2, a: egui::Pos2, b: egui::Pos2) -> f32 {
+    let ab = b - a;
+    let length_sq = ab.length_sq();
+    if length_sq <= f32::EPSILON {
+        return point.distance(a);
+    }
+    let t = ((point - a).dot(ab) / length_sq).clamp(0.0, 1.0);
+    point.distance(a + ab * t)
+}
+
+/// Midpoint of the longest segment of a polyline
+fn longest_segment_midpoint(points: &[egui::Pos2]) -> egui::Pos2 {
+    points
+        .windows(2)
+        .max_by(|a, b| a[0].distance(a[1]).total_cmp(&b[0].distance(b[1])))
+        .map(|segment| segment[0].lerp(segment[1], 0.5))
+        .unwrap_or_default()
+}
+
+/// Whether a transition leads into the active configuration
+///
+/// That is the case when its target is active and it was taken from within an active region:
+/// the source itself is active, or the source's parent is.
+fn is_on_active_path(
+    transition: &crate::TransitionConnection,
+    active_states: &HashSet<Entity>,
+    q_child_of: &Query<&bevy_gearbox::StateChildOf>,
+) -> bool {
+    if !active_states.contains(&transition.target_entity) {
+        return false;
+    }
+    if active_states.contains(&transition.source_entity) {
+        return true;
     }
+    q_child_of
+        .get(transition.source_entity)
+        .is_ok_and(|child_of| active_states.contains(&child_of.0))
 }
 
 fn is_direct_child_of_parallel(
@@ -480,9 +1456,19 @@ fn update_transition_rectangles(
         node_rects.insert(*entity, node.current_rect());
     }
     
+    // Endpoints hidden inside a collapsed parent attach to that parent's edge instead
+    let mut proxies: std::collections::HashMap<Entity, Entity> = std::collections::HashMap::new();
+    for entity in persistent_data.nodes.keys() {
+        if let Some(proxy) = persistent_data.collapsed_proxy(*entity, q_child_of) {
+            proxies.insert(*entity, proxy);
+        }
+    }
+    
     for transition in &mut persistent_data.visual_transitions {
-        if let Some(r) = node_rects.get(&transition.source_entity) { transition.source_rect = *r; }
-        if let Some(r) = node_rects.get(&transition.target_entity) { transition.target_rect = *r; }
+        let source = proxies.get(&transition.source_entity).copied().unwrap_or(transition.source_entity);
+        let target = proxies.get(&transition.target_entity).copied().unwrap_or(transition.target_entity);
+        if let Some(r) = node_rects.get(&source) { transition.source_rect = *r; }
+        if let Some(r) = node_rects.get(&target) { transition.target_rect = *r; }
         
         // Update event node position based on new source/target positions (unless being dragged)
         if !transition.is_dragging_event_node {
@@ -505,6 +1491,10 @@ fn constrain_event_node_position(
     let other = if higher == transition.source_entity { transition.target_entity } else { transition.source_entity };
     let is_direct_child = match q_child_of.get(other) { Ok(rel) => rel.0 == higher, Err(_) => false };
     let parent_for_pill = if is_direct_child { higher } else if let Ok(rel) = q_child_of.get(higher) { rel.0 } else { higher };
+    // A self-loop on the root has no enclosing parent; clamping into the node itself would hide the loop
+    if transition.is_self_transition() && parent_for_pill == transition.source_entity {
+        return;
+    }
     if let Some(parent_rect) = node_rects.get(&parent_for_pill) {
         // Reconstruct an approximate ParentNode content rect assumptions are required here;
         // since we only stored the whole rect, approximate content by shrinking top bar and margins
@@ -526,6 +1516,53 @@ fn constrain_event_node_position(
     }
 }
 
+/// Render an inline text field in place of a transition pill whose label is being renamed
+fn render_edge_label_editor(
+    ui: &mut egui::Ui,
+    event_pos: egui::Pos2,
+    font_id: egui::FontId,
+    transient_data: &mut StateMachineTransientData,
+    theme: &EditorTheme,
+) {
+    let should_focus = transient_data.text_editing.should_focus;
+    let first_focus = transient_data.text_editing.first_focus;
+
+    let text_color = theme.text_color_for_bg(theme.node_fill);
+    let text_width = ui
+        .fonts(|f| f.layout_no_wrap(transient_data.text_editing.current_text.clone(), font_id.clone(), text_color))
+        .size()
+        .x
+        .max(80.0);
+    let edit_rect = egui::Rect::from_center_size(event_pos, egui::Vec2::new(text_width + 16.0, 20.0));
+    ui.painter().rect_filled(edit_rect, egui::CornerRadius::same(10), theme.node_fill);
+
+    let text_edit = egui::TextEdit::singleline(&mut transient_data.text_editing.current_text)
+        .id(egui::Id::new("edge_label_edit"))
+        .font(font_id)
+        .text_color(text_color)
+        .desired_width(text_width)
+        .horizontal_align(egui::Align::Center)
+        .margin(egui::Vec2::ZERO);
+    let mut child_ui = ui.new_child(egui::UiBuilder::new()
+        .max_rect(edit_rect.shrink2(egui::Vec2::new(8.0, 2.0)))
+        .layout(egui::Layout::left_to_right(egui::Align::Center)));
+    let output = text_edit.show(&mut child_ui);
+
+    if should_focus {
+        output.response.request_focus();
+        // Select all text on first focus so typing replaces the current label
+        if first_focus {
+            let mut new_state = output.state.clone();
+            new_state.cursor.set_char_range(Some(egui::text::CCursorRange::select_all(&output.galley)));
+            new_state.store(ui.ctx(), output.response.id);
+        }
+        transient_data.text_editing.should_focus = false;
+    }
+    if first_focus {
+        transient_data.text_editing.first_focus = false;
+    }
+}
+
 /// Handle text editing completion (Enter key or click outside)
 fn handle_text_editing_completion(
     ui: &mut egui::Ui,
@@ -533,12 +1570,23 @@ fn handle_text_editing_completion(
     commands: &mut Commands,
 ) {
     if transient_data.text_editing.editing_entity.is_some() {
-        let pressed_enter = ui.input(|i| i.key_pressed(egui::Key::Enter));
+        // Enter is consumed so keyboard navigation doesn't also add a sibling
+        let pressed_enter = ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Enter));
         let pressed_escape = ui.input(|i| i.key_pressed(egui::Key::Escape));
 
         if pressed_enter || pressed_escape {
             if pressed_escape {
                 transient_data.text_editing.cancel_editing();
+            } else if transient_data.text_editing.editing_edge_label {
+                if let Some((edge_entity, new_label)) = transient_data.text_editing.stop_editing() {
+                    let trimmed_label = new_label.trim();
+                    if trimmed_label.is_empty() {
+                        // Clearing the label falls back to the edge's Name (the event type)
+                        commands.entity(edge_entity).remove::<EdgeLabel>();
+                    } else {
+                        commands.entity(edge_entity).insert(EdgeLabel(trimmed_label.to_string()));
+                    }
+                }
             } else if let Some((entity, new_name)) = transient_data.text_editing.stop_editing() {
                 let trimmed_name = new_name.trim();
                 if !trimmed_name.is_empty() {
@@ -555,15 +1603,23 @@ fn handle_text_editing_completion(
 fn render_initial_state_indicators(
     ui: &mut egui::Ui,
     persistent_data: &StateMachinePersistentData,
-    all_entities: &Query<(Entity, Option<&Name>, Option<&InitialState>)>,
+    all_entities: &EntityNamesQuery,
+    q_child_of: &Query<&bevy_gearbox::StateChildOf>,
     selected_root: Entity,
+    hidden_entities: &HashSet<Entity>,
+    show_indicators: bool,
+    color: egui::Color32,
+    commands: &mut Commands,
 ) {
     let painter = ui.painter();
     
     // Find all entities with InitialState component that belong to the current state machine
-    for (parent_entity, _name, initial_state_opt) in all_entities.iter() {
+    for (parent_entity, _name, initial_state_opt, _) in all_entities.iter() {
         if let Some(initial_state) = initial_state_opt {
             let target_entity = initial_state.0;
+            if hidden_entities.contains(&target_entity) {
+                continue;
+            }
             
             // Only render if both parent and target are in our editor nodes and belong to current state machine
             if let (Some(_parent_node), Some(target_node)) = (
@@ -573,17 +1629,59 @@ fn render_initial_state_indicators(
                 // Check if this belongs to the currently selected state machine
                 // (We can do this by checking if the parent entity is a child of selected_root or is selected_root)
                 let belongs_to_current_machine = parent_entity == selected_root || 
-                    all_entities.iter().any(|(entity, _, _)| {
+                    all_entities.iter().any(|(entity, _, _, _)| {
                         entity == selected_root && 
                         // This is a simplified check - in a real implementation you'd traverse the hierarchy
                         true // For now, assume all nodes in persistent_data.nodes belong to current machine
                     });
                 
                 if belongs_to_current_machine {
-                    render_initial_state_indicator(
-                        &painter,
-                        target_node.current_rect(),
-                    );
+                    if show_indicators {
+                        let target_rect = target_node.current_rect();
+                        render_initial_state_indicator(
+                            &painter,
+                            target_rect,
+                            color,
+                        );
+                        
+                        // The circle doubles as a handle: drag it onto a sibling to make that the initial state
+                        let circle_center = initial_indicator_circle_center(target_rect);
+                        let handle_rect = egui::Rect::from_center_size(circle_center, egui::Vec2::splat(12.0));
+                        let response = ui
+                            .interact(handle_rect, ui.id().with(("initial_state_handle", parent_entity)), egui::Sense::drag())
+                            .on_hover_cursor(egui::CursorIcon::Grab);
+                        if response.dragged() || response.drag_stopped() {
+                            let Some(pointer_pos) = layer_pointer_pos(ui) else { continue; };
+                            let sibling = topmost_node_at(persistent_data, pointer_pos, q_child_of)
+                                .and_then(|hovered| child_containing(hovered, parent_entity, q_child_of));
+                            if response.dragged() {
+                                let color = if sibling.is_some() { egui::Color32::from_rgb(120, 200, 255) } else { egui::Color32::GRAY };
+                                let end = sibling
+                                    .and_then(|sibling| persistent_data.nodes.get(&sibling))
+                                    .map(|node| node.current_rect().left_center())
+                                    .unwrap_or(pointer_pos);
+                                draw_dashed_arrow(&painter, circle_center, end, color);
+                                if let Some(sibling_node) = sibling.and_then(|sibling| persistent_data.nodes.get(&sibling)) {
+                                    painter.rect_stroke(
+                                        sibling_node.current_rect().expand(3.0),
+                                        egui::CornerRadius::same(10),
+                                        egui::Stroke::new(2.0, color),
+                                        egui::StrokeKind::Outside,
+                                    );
+                                }
+                            } else {
+                                match sibling {
+                                    Some(sibling) if sibling != target_entity => {
+                                        commands.trigger(SetInitialStateRequested { child_entity: sibling });
+                                    }
+                                    Some(_) => {}
+                                    None => warn!("⚠️ Initial state must be a child of {:?}; drop the arrow on a sibling", parent_entity),
+                                }
+                            }
+                        }
+                    } else {
+                        render_initial_state_marker(&painter, target_node.current_rect());
+                    }
                 }
             }
         }
@@ -594,24 +1692,23 @@ fn render_initial_state_indicators(
 fn render_initial_state_indicator(
     painter: &egui::Painter,
     target_rect: egui::Rect,
+    color: egui::Color32,
 ) {
-    // Circle position: to the left and lower relative to target node (moved 6px right)
-    let circle_offset = egui::Vec2::new(-13.0, 1.0);
-    let circle_center = target_rect.left_top() + circle_offset;
+    let circle_center = initial_indicator_circle_center(target_rect);
     let circle_radius = 3.0;
     
-    // Draw the circle (white)
+    // Draw the circle
     painter.circle_filled(
         circle_center,
         circle_radius,
-        egui::Color32::WHITE,
+        color,
     );
     
-    // Draw circle border (slightly darker white/light gray)
+    // Draw circle border (slightly dimmer)
     painter.circle_stroke(
         circle_center,
         circle_radius,
-        egui::Stroke::new(1.5, egui::Color32::from_rgb(200, 200, 200)),
+        egui::Stroke::new(1.5, color.gamma_multiply(0.8)),
     );
     
     // Calculate curved arrow that hits the left side at 16px from top
@@ -635,7 +1732,7 @@ fn render_initial_state_indicator(
         
         painter.line_segment(
             [prev_point, current_point],
-            egui::Stroke::new(2.0, egui::Color32::WHITE),
+            egui::Stroke::new(2.0, color),
         );
         
         prev_point = current_point;
@@ -651,11 +1748,46 @@ fn render_initial_state_indicator(
     
     painter.line_segment(
         [arrow_end, arrowhead_point1],
-        egui::Stroke::new(2.0, egui::Color32::WHITE),
+        egui::Stroke::new(2.0, color),
     );
     painter.line_segment(
         [arrow_end, arrowhead_point2],
-        egui::Stroke::new(2.0, egui::Color32::WHITE),
+        egui::Stroke::new(2.0, color),
+    );
+}
+
+/// Center of the initial state indicator circle: to the left of and slightly below the target's top-left corner
+fn initial_indicator_circle_center(target_rect: egui::Rect) -> egui::Pos2 {
+    target_rect.left_top() + egui::Vec2::new(-13.0, 1.0)
+}
+
+/// The direct child of `parent` that is `entity` or one of its ancestors
+fn child_containing(
+    mut entity: Entity,
+    parent: Entity,
+    q_child_of: &Query<&bevy_gearbox::StateChildOf>,
+) -> Option<Entity> {
+    loop {
+        let child_of = q_child_of.get(entity).ok()?;
+        if child_of.0 == parent {
+            return Some(entity);
+        }
+        entity = child_of.0;
+    }
+}
+
+/// Subtle initial state marker used when the indicator arrows are hidden (thin left border)
+fn render_initial_state_marker(
+    painter: &egui::Painter,
+    target_rect: egui::Rect,
+) {
+    let inset = 6.0;
+    painter.line_segment(
+        [
+            egui::Pos2::new(target_rect.left() + 1.5, target_rect.top() + inset),
+            egui::Pos2::new(target_rect.left() + 1.5, target_rect.bottom() - inset),
+        ],
+        egui::Stroke::new(3.0, egui::Color32::from_rgb(120, 200, 255)),
     );
 }
 
@@ -673,6 +1805,11 @@ fn quadratic_bezier(start: egui::Pos2, control: egui::Pos2, end: egui::Pos2, t:
 
 /// Draw a dashed arrow from start to end position
 fn draw_dashed_arrow(painter: &egui::Painter, start: egui::Pos2, end: egui::Pos2, color: egui::Color32) {
+    draw_dashed_arrow_with_stroke(painter, start, end, egui::Stroke::new(2.0, color));
+}
+
+/// Dashed line from start to end with an arrowhead, drawn with the given stroke
+fn draw_dashed_arrow_with_stroke(painter: &egui::Painter, start: egui::Pos2, end: egui::Pos2, stroke: egui::Stroke) {
     let direction = end - start;
     let distance = direction.length();
     
@@ -694,7 +1831,7 @@ fn draw_dashed_arrow(painter: &egui::Painter, start: egui::Pos2, end: egui::Pos2
         
         painter.line_segment(
             [dash_start, dash_end],
-            egui::Stroke::new(2.0, color),
+            stroke,
         );
         
         current_distance += dash_and_gap;
@@ -709,14 +1846,40 @@ fn draw_dashed_arrow(painter: &egui::Painter, start: egui::Pos2, end: egui::Pos2
     
     painter.line_segment(
         [end, arrowhead_point1],
-        egui::Stroke::new(2.0, color),
+        stroke,
     );
     painter.line_segment(
         [end, arrowhead_point2],
-        egui::Stroke::new(2.0, color),
+        stroke,
     );
 }
 
+/// Draw dashes along a polyline, shifted forward by `phase` so they appear to travel toward the end
+fn draw_marching_ants(painter: &egui::Painter, path: &[egui::Pos2], phase: f32, stroke: egui::Stroke) {
+    const DASH_LENGTH: f32 = 6.0;
+    const DASH_PERIOD: f32 = 14.0;
+    let mut travelled = 0.0;
+    for segment in path.windows(2) {
+        let (start, end) = (segment[0], segment[1]);
+        let length = start.distance(end);
+        if length < 1.0 {
+            continue;
+        }
+        let direction = (end - start) / length;
+        // First dash that could overlap this segment, in path distance
+        let mut dash_start = phase + ((travelled - phase) / DASH_PERIOD).floor() * DASH_PERIOD;
+        while dash_start < travelled + length {
+            let from = (dash_start - travelled).max(0.0);
+            let to = (dash_start + DASH_LENGTH - travelled).min(length);
+            if to > from {
+                painter.line_segment([start + direction * from, start + direction * to], stroke);
+            }
+            dash_start += DASH_PERIOD;
+        }
+        travelled += length;
+    }
+}
+
 fn is_ancestor_of(source: Entity, target: Entity, q_child_of: &Query<&bevy_gearbox::StateChildOf>) -> bool {
     let mut current = target;
     while let Ok(child_of) = q_child_of.get(current) {
@@ -732,7 +1895,7 @@ fn draw_fish_hook_to_point(
     painter: &egui::Painter,
     parent_rect: egui::Rect,
     event_pos: egui::Pos2,
-    color: egui::Color32,
+    stroke: egui::Stroke,
 ) {
     // p0: closest point on parent's edge to the event position
     let p0 = closest_point_on_rect_edge(parent_rect, event_pos);
@@ -753,8 +1916,51 @@ fn draw_fish_hook_to_point(
     let p3 = p2 - dir * 10.0;
 
     // Curve to p3, then straight line to event_pos (no arrow here; arrow on final segment to target)
-    draw_cubic_bezier(painter, p0, p1, p2, p3, color);
-    painter.line_segment([p3, event_pos], egui::Stroke::new(2.0, color));
+    draw_cubic_bezier(painter, p0, p1, p2, p3, stroke);
+    painter.line_segment([p3, event_pos], stroke);
+}
+
+/// Draw a self-transition loop that leaves the top edge of the node near its right corner,
+/// passes through the event node, and re-enters the right edge with an arrowhead
+fn draw_self_loop(
+    painter: &egui::Painter,
+    node_rect: egui::Rect,
+    event_pos: egui::Pos2,
+    stroke: egui::Stroke,
+) {
+    let corner_inset = (node_rect.width().min(node_rect.height()) * 0.25).min(15.0);
+    let exit = egui::Pos2::new(node_rect.max.x - corner_inset, node_rect.min.y);
+    let entry = egui::Pos2::new(node_rect.max.x, node_rect.min.y + corner_inset);
+    let bulge = 20.0;
+
+    // Up out of the top edge and over to the event node
+    draw_cubic_bezier(
+        painter,
+        exit,
+        exit + egui::Vec2::new(0.0, -bulge),
+        event_pos + egui::Vec2::new(-bulge, 0.0),
+        event_pos,
+        stroke,
+    );
+
+    // Down from the event node and back into the right edge
+    let approach = entry + egui::Vec2::new(bulge, 0.0);
+    draw_cubic_bezier(
+        painter,
+        event_pos,
+        event_pos + egui::Vec2::new(0.0, bulge),
+        approach,
+        entry,
+        stroke,
+    );
+
+    // Arrowhead following the final tangent of the curve
+    let direction = (entry - approach).normalized();
+    let perpendicular = egui::Vec2::new(-direction.y, direction.x);
+    let arrow_length = 8.0;
+    let arrow_base = entry - direction * arrow_length;
+    painter.line_segment([entry, arrow_base + perpendicular * arrow_length * 0.5], stroke);
+    painter.line_segment([entry, arrow_base - perpendicular * arrow_length * 0.5], stroke);
 }
 
 fn draw_cubic_bezier(
@@ -763,14 +1969,14 @@ fn draw_cubic_bezier(
     p1: egui::Pos2,
     p2: egui::Pos2,
     p3: egui::Pos2,
-    color: egui::Color32,
+    stroke: egui::Stroke,
 ) {
     let segments = 24;
     let mut prev = p0;
     for i in 1..=segments {
         let t = i as f32 / segments as f32;
         let pt = cubic_bezier_point(p0, p1, p2, p3, t);
-        painter.line_segment([prev, pt], egui::Stroke::new(2.0, color));
+        painter.line_segment([prev, pt], stroke);
         prev = pt;
     }
 }
@@ -794,3 +2000,74 @@ fn hierarchy_depth_from_pairs(mut entity: Entity, q_child_of: &Query<&bevy_gearb
     }
     depth
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor_state::snap_to_grid;
+
+    const GRID: f32 = 20.0;
+
+    fn viewport() -> egui::Rect {
+        egui::Rect::from_min_size(egui::pos2(10.0, 30.0), egui::vec2(400.0, 300.0))
+    }
+
+    /// Whether every dot maps back onto a grid multiple in node coordinates
+    fn dots_lie_on_snap_points(transform: CanvasTransform) -> bool {
+        let origin = viewport().min;
+        grid_dot_positions(viewport(), GRID, transform, origin).iter().all(|dot| {
+            let node_pos = transform.from_screen(*dot, origin);
+            (snap_to_grid(node_pos, GRID) - node_pos).length() < 1e-3
+        })
+    }
+
+    #[test]
+    fn tiny_grid_draws_nothing() {
+        assert!(grid_dot_positions(viewport(), 2.0, CanvasTransform::default(), viewport().min).is_empty());
+    }
+
+    #[test]
+    fn dots_follow_pan_onto_snapped_positions() {
+        let transform = CanvasTransform { offset: egui::vec2(7.0, -13.0), zoom: 1.0 };
+        let dots = grid_dot_positions(viewport(), GRID, transform, viewport().min);
+        assert!(!dots.is_empty());
+        assert!(dots.iter().all(|dot| viewport().contains(*dot)));
+        assert!(dots_lie_on_snap_points(transform));
+        // A snapped node corner is drawn exactly on a dot
+        let snapped = snap_to_grid(egui::pos2(123.0, 77.0), GRID);
+        let on_screen = transform.to_screen(snapped, viewport().min);
+        assert!(dots.iter().any(|dot| (*dot - on_screen).length() < 1e-3));
+    }
+
+    #[test]
+    fn dot_spacing_scales_with_zoom() {
+        for zoom in [0.5, 1.0, 2.5] {
+            let transform = CanvasTransform { offset: egui::vec2(-31.0, 9.0), zoom };
+            let dots = grid_dot_positions(viewport(), GRID, transform, viewport().min);
+            assert!(dots.len() >= 2);
+            assert!((dots[1].x - dots[0].x - GRID * zoom).abs() < 1e-3);
+            assert!(dots_lie_on_snap_points(transform));
+        }
+    }
+
+    #[test]
+    fn zooming_far_out_hides_the_grid() {
+        let transform = CanvasTransform { offset: egui::Vec2::ZERO, zoom: 0.1 };
+        assert!(grid_dot_positions(viewport(), GRID, transform, viewport().min).is_empty());
+    }
+
+    #[test]
+    fn arrow_navigation_skips_hidden_nodes() {
+        let mut world = World::new();
+        let (from, hidden, visible) = (world.spawn_empty().id(), world.spawn_empty().id(), world.spawn_empty().id());
+        let mut persistent_data = StateMachinePersistentData::default();
+        persistent_data.nodes.insert(from, NodeType::Leaf(LeafNode::new(egui::pos2(0.0, 0.0))));
+        persistent_data.nodes.insert(hidden, NodeType::Leaf(LeafNode::new(egui::pos2(100.0, 0.0))));
+        persistent_data.nodes.insert(visible, NodeType::Leaf(LeafNode::new(egui::pos2(200.0, 0.0))));
+        let right = egui::vec2(1.0, 0.0);
+
+        assert_eq!(nearest_node_in_direction(&persistent_data, &HashSet::default(), from, right), Some(hidden));
+        let hidden_nodes: HashSet<Entity> = [hidden].into_iter().collect();
+        assert_eq!(nearest_node_in_direction(&persistent_data, &hidden_nodes, from, right), Some(visible));
+    }
+}
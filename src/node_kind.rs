@@ -5,7 +5,7 @@ use bevy::prelude::*;
 use bevy_gearbox::prelude::*;
 use bevy_gearbox::transitions::{Source, Target, EventEdge};
 
-use crate::editor_state::StateMachinePersistentData;
+use crate::editor_state::{EditorConfig, StateMachinePersistentData};
 use crate::editor_state::{DeleteNode, MachineNodesPopulated};
 use crate::components::{NodeType, LeafNode};
 use crate::editor_state::SetInitialStateRequested;
@@ -16,8 +16,20 @@ pub struct NodeKindRoot;
 
 /// Associates a NodeKind machine or its state node with the editor state entity it controls
 #[derive(Component, Copy, Clone)]
+#[require(EditorInternal)]
 pub struct NodeKindFor(pub Entity);
 
+/// Marker on entities the editor spawns for its own bookkeeping (NodeKind machines, their states and edges)
+///
+/// These are hidden from every user-facing machine list and from the World Inspector.
+#[derive(Component, Default)]
+pub struct EditorInternal;
+
+/// Whether `entity` belongs to the editor's own bookkeeping rather than the user's machines
+pub fn is_editor_internal(entity: Entity, world: &World) -> bool {
+    world.get::<EditorInternal>(entity).is_some()
+}
+
 /// Markers for NodeKind variant states
 #[derive(Component)]
 pub struct NodeKindLeaf;
@@ -28,6 +40,32 @@ pub struct NodeKindParent;
 #[derive(Component)]
 pub struct NodeKindParallel;
 
+/// Kind of an editor state, as tracked by its NodeKind machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    Leaf,
+    Parent,
+    Parallel,
+}
+
+/// Active NodeKind variant states, with the NodeKind machine root they belong to
+pub type ActiveNodeKindQuery<'w, 's> = Query<'w, 's, (&'static ChildOf, Has<NodeKindLeaf>, Has<NodeKindParent>, Has<NodeKindParallel>), With<bevy_gearbox::active::Active>>;
+
+/// Current kind of the NodeKind machine rooted at `nk_root`
+pub fn active_node_kind(nk_root: Entity, q_active_kinds: &ActiveNodeKindQuery) -> Option<NodeKind> {
+    q_active_kinds.iter().find_map(|(child_of, leaf, parent, parallel)| {
+        if child_of.parent() != nk_root {
+            return None;
+        }
+        match (leaf, parent, parallel) {
+            (true, _, _) => Some(NodeKind::Leaf),
+            (_, true, _) => Some(NodeKind::Parent),
+            (_, _, true) => Some(NodeKind::Parallel),
+            _ => None,
+        }
+    })
+}
+
 // Events that drive NodeKind transitions (entity-targeted at the NodeKind machine root)
 #[derive(SimpleTransition, EntityEvent, Clone)]
 pub struct AddChildClicked {
@@ -184,13 +222,14 @@ pub fn on_enter_nodekind_state_parallel(
         if has_child { return; }
 
         let child = world.spawn((bevy_gearbox::StateChildOf(state), Name::new("New State"))).id();
+        let child_offset = world.resource::<EditorConfig>().child_spawn_offset;
         if let Some(mut persistent) = world.get_mut::<StateMachinePersistentData>(root) {
             if let Some(parent_node) = persistent.nodes.get(&state) {
                 let parent_pos = match parent_node {
                     NodeType::Leaf(leaf) => leaf.entity_node.position,
                     NodeType::Parent(parent) => parent.entity_node.position,
                 };
-                let pos = parent_pos + egui::Vec2::new(50.0, 50.0);
+                let pos = parent_pos + child_offset;
                 persistent.nodes.insert(child, NodeType::Leaf(LeafNode::new(pos)));
             }
         }
@@ -218,13 +257,14 @@ pub fn on_enter_nodekind_state_parent(
             .and_then(|children| children.into_iter().next().copied())
             .or_else(|| {
                 let child = world.spawn((bevy_gearbox::StateChildOf(state), Name::new("New State"))).id();
+                let child_offset = world.resource::<EditorConfig>().child_spawn_offset;
                 let Some(mut persistent) = world.get_mut::<StateMachinePersistentData>(root) else { return None; };
                 let Some(parent_node) = persistent.nodes.get(&state) else { return None; };
                 let parent_pos = match parent_node {
                     NodeType::Leaf(leaf) => leaf.entity_node.position,
                     NodeType::Parent(parent) => parent.entity_node.position,
                 };
-                let pos = parent_pos + egui::Vec2::new(50.0, 50.0);
+                let pos = parent_pos + child_offset;
                 persistent.nodes.insert(child, NodeType::Leaf(LeafNode::new(pos)));
                 Some(child)
             });
@@ -266,6 +306,7 @@ pub fn on_enter_nodekind_state_parent_via_make_parent(
             .and_then(|children| children.into_iter().next().copied());
         if first_child.is_none() {
             let child = world.spawn((bevy_gearbox::StateChildOf(state), Name::new("New State"))).id();
+            let child_offset = world.resource::<EditorConfig>().child_spawn_offset;
             first_child = Some(child);
             let Some(mut persistent) = world.get_mut::<StateMachinePersistentData>(root) else { return; };
             let Some(parent_node) = persistent.nodes.get(&state) else { return; };
@@ -273,7 +314,7 @@ pub fn on_enter_nodekind_state_parent_via_make_parent(
                 NodeType::Leaf(leaf) => leaf.entity_node.position,
                 NodeType::Parent(parent) => parent.entity_node.position,
             };
-            let pos = parent_pos + egui::Vec2::new(50.0, 50.0);
+            let pos = parent_pos + child_offset;
             persistent.nodes.insert(child, NodeType::Leaf(LeafNode::new(pos)));
         }
         let Some(init) = first_child else { return; };
@@ -0,0 +1,68 @@
+//! First-run onboarding overlay
+//!
+//! This module handles:
+//! - Rendering a dismissible overlay explaining the core editor gestures
+//! - Recording that the overlay was seen in the layout sidecar
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+
+use crate::editor_state::{EditorState, EditorTheme};
+use crate::window_management::EditorHotkeys;
+
+/// Render the onboarding overlay if it should be visible
+pub fn render_onboarding_overlay(
+    ctx: &egui::Context,
+    editor_state: &mut EditorState,
+    theme: &EditorTheme,
+    hotkeys: &EditorHotkeys,
+) {
+    if !editor_state.show_onboarding {
+        return;
+    }
+
+    let mut dismissed = false;
+    egui::Window::new("Welcome to the Gearbox Editor")
+        .id(egui::Id::new("onboarding_overlay"))
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .collapsible(false)
+        .resizable(false)
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            ui.set_max_width(380.0);
+            ui.label("A quick tour of the core gestures:");
+            ui.separator();
+            ui.label(format!("• {} opens this editor window.", hotkeys.open_editor));
+            ui.label("• Right-click the canvas to open or create state machines.");
+            ui.label("• Right-click a state or transition pill for more actions.");
+            ui.label("• Select a state, click the blue \"+\", then click a target state to create a transition.");
+            ui.label("• Drag states and transition pills to arrange them.");
+            ui.separator();
+            ui.label("Colors:");
+            color_legend_row(ui, theme.active, "Active state");
+            color_legend_row(ui, theme.bright_active, "Recently entered state or fired transition");
+            color_legend_row(ui, theme.node_fill, "Inactive state or idle transition");
+            ui.label("Dotted borders mark the regions of a parallel state.");
+            ui.separator();
+            if ui.button("Got it").clicked() {
+                dismissed = true;
+            }
+        });
+
+    if dismissed {
+        editor_state.show_onboarding = false;
+        editor_state.onboarding_seen = true;
+        if let Err(e) = editor_state.save_layout(&editor_state.layout_path) {
+            warn!("⚠️ Failed to save editor layout to {:?}: {}", editor_state.layout_path, e);
+        }
+    }
+}
+
+/// Draw a small color swatch followed by its meaning
+fn color_legend_row(ui: &mut egui::Ui, color: egui::Color32, meaning: &str) {
+    ui.horizontal(|ui| {
+        let (rect, _) = ui.allocate_exact_size(egui::Vec2::new(14.0, 14.0), egui::Sense::hover());
+        ui.painter().rect_filled(rect, egui::CornerRadius::same(3), color);
+        ui.label(meaning);
+    });
+}
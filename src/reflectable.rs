@@ -1,12 +1,17 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::path::Path;
 
 use bevy::{
     prelude::*,
-    scene::{DynamicScene, DynamicSceneBuilder, DynamicSceneRoot},
+    ecs::entity::EntityHashMap,
+    scene::{serde::SceneDeserializer, DynamicScene, DynamicSceneBuilder, DynamicSceneRoot},
     tasks::IoTaskPool,
 };
 use bevy::platform::collections::HashMap;
+use serde::de::DeserializeSeed;
 use bevy_ecs::component::{Mutable, StorageType};
+use bevy_gearbox::active::Active;
 use bevy_gearbox::transitions::Transitions as EdgeTransitions;
 
 use crate::{StateMachinePersistentData, TransitionConnection};
@@ -17,6 +22,12 @@ use crate::components::{NodeType, LeafNode, ParentNode};
 pub struct ReflectableStateMachinePersistentData {
     pub nodes: HashMap<Entity, ReflectableNode>,
     pub visual_transitions: Vec<ReflectableTransitionConnection>,
+    #[reflect(default)]
+    pub collapsed: Vec<Entity>,
+    #[reflect(default)]
+    pub orthogonal_edges: bool,
+    #[reflect(default)]
+    pub inspected_entity: Option<Entity>,
 }
 
 impl Component for ReflectableStateMachinePersistentData {
@@ -43,6 +54,9 @@ impl Component for ReflectableStateMachinePersistentData {
             });
         }
         this.visual_transitions = new_visual_transitions;
+
+        this.collapsed = this.collapsed.iter().map(|entity| entity_mapper.get_mapped(*entity)).collect();
+        this.inspected_entity = this.inspected_entity.map(|entity| entity_mapper.get_mapped(entity));
     }
 }
 
@@ -50,6 +64,12 @@ impl Component for ReflectableStateMachinePersistentData {
 pub struct ReflectableNode {
     pub position: Vec2,
     pub node_type: ReflectableNodeType,
+    /// Custom base color (unmultiplied RGBA)
+    #[reflect(default)]
+    pub color: Option<[u8; 4]>,
+    /// Manual stacking among siblings
+    #[reflect(default)]
+    pub z_bias: i32,
 }
 
 #[derive(Reflect, Clone)]
@@ -99,6 +119,8 @@ impl ReflectableStateMachinePersistentData {
             nodes.insert(entity, ReflectableNode {
                 position: vec2_from_pos2(node.position()),
                 node_type,
+                color: state_machine.node_colors.get(&entity).map(|color| color.to_srgba_unmultiplied()),
+                z_bias: state_machine.z_bias.get(&entity).copied().unwrap_or_default(),
             });
         }
 
@@ -115,12 +137,22 @@ impl ReflectableStateMachinePersistentData {
         }
 
         // Caller is responsible for inserting this reflectable on the root before save
-        Self { nodes, visual_transitions }
+        let collapsed = state_machine.collapsed.iter().copied().collect();
+
+        Self {
+            nodes,
+            visual_transitions,
+            collapsed,
+            orthogonal_edges: state_machine.orthogonal_edges,
+            inspected_entity: state_machine.inspected_entity,
+        }
     }
 
     /// Convert back to StateMachinePersistentData
     pub fn to_persistent_data(&self) -> StateMachinePersistentData {
         let mut nodes = HashMap::new();
+        let mut node_colors = HashMap::new();
+        let mut z_bias = HashMap::new();
         let mut visual_transitions = Vec::new();
 
         // Convert nodes back to NodeType
@@ -135,6 +167,12 @@ impl ReflectableStateMachinePersistentData {
                 }
             };
             nodes.insert(entity, node);
+            if let Some([r, g, b, a]) = reflectable_node.color {
+                node_colors.insert(entity, egui::Color32::from_rgba_unmultiplied(r, g, b, a));
+            }
+            if reflectable_node.z_bias != 0 {
+                z_bias.insert(entity, reflectable_node.z_bias);
+            }
         }
 
         // Convert visual transitions back
@@ -149,12 +187,27 @@ impl ReflectableStateMachinePersistentData {
                 event_node_position: pos2_from_vec2(reflectable_transition.position),
                 is_dragging_event_node: false,
                 event_node_offset: egui_vec2_from_vec2(reflectable_transition.offset),
+                sibling_index: 0,
+                sibling_count: 1,
+                guards: Vec::new(),
+                is_always: false, // Synced from the edge entity
+                is_internal: false,
+                order_badge: None,
+                listener_event: reflectable_transition.event_type.clone(),
+                is_disabled: false, // Synced from the edge entity
             });
         }
 
         StateMachinePersistentData {
             nodes,
             visual_transitions,
+            collapsed: self.collapsed.iter().copied().collect(),
+            orthogonal_edges: self.orthogonal_edges,
+            node_colors,
+            z_bias,
+            layout_dirty: true,
+            sized_counts: (0, 0),
+            inspected_entity: self.inspected_entity,
         }
     }
 
@@ -192,11 +245,10 @@ impl ReflectableStateMachinePersistentData {
         // Find all entities in the state machine hierarchy
         let hierarchy_entities = Self::collect_state_machine_entities(world, root_entity)?;
         
-        // Create scene using DynamicSceneBuilder
-        let scene_builder = DynamicSceneBuilder::from_world(world);
-        let scene = scene_builder
+        // Runtime state is left out so the loaded machine starts at its InitialState;
+        // the editor's transient data does not implement reflect
+        let scene = deny_runtime_components(DynamicSceneBuilder::from_world(world))
             .extract_entities(hierarchy_entities.iter().copied())
-            .allow_all() // No need to deny anything as transient data does not implement reflect
             .build();
         
         Ok(scene)
@@ -223,6 +275,46 @@ impl ReflectableStateMachinePersistentData {
         entity
     }
 
+    /// Load a state machine scene file directly into the world
+    ///
+    /// Deserializes the scene synchronously and spawns its entities (states, edges and their
+    /// listeners), remapping serialized entity IDs to fresh live entities. Editor data is
+    /// restored on every loaded machine root, and the roots are returned.
+    pub fn load_state_machine_into_world(
+        world: &mut World,
+        file_path: impl AsRef<Path>,
+    ) -> Result<Vec<Entity>, Box<dyn std::error::Error>> {
+        let contents = std::fs::read_to_string(file_path.as_ref())?;
+
+        // Deserialize the scene using the app's type registry
+        let scene: DynamicScene = {
+            let type_registry = world.resource::<AppTypeRegistry>().clone();
+            let registry = type_registry.read();
+            let mut deserializer = ron::de::Deserializer::from_str(&contents)?;
+            SceneDeserializer { type_registry: &registry }.deserialize(&mut deserializer)?
+        };
+
+        // Spawn the entities; the map records serialized -> live entity IDs
+        let mut entity_map = EntityHashMap::default();
+        scene.write_to_world(world, &mut entity_map)?;
+
+        // Machine roots are loaded StateMachines that aren't a state of another machine
+        let roots: Vec<Entity> = entity_map
+            .values()
+            .copied()
+            .filter(|&entity| {
+                world.get::<bevy_gearbox::StateMachine>(entity).is_some()
+                    && world.get::<bevy_gearbox::StateChildOf>(entity).is_none()
+            })
+            .collect();
+
+        for &root in &roots {
+            Self::restore_editor_data_after_load(world, root)?;
+        }
+
+        Ok(roots)
+    }
+
     /// Collect all entities that belong to a state machine hierarchy
     fn collect_state_machine_entities(
         world: &World,
@@ -258,6 +350,16 @@ impl ReflectableStateMachinePersistentData {
             }
         }
 
+        // Disabled edges aren't in any `Transitions`; the editor still draws them, so save them too
+        if let Some(persistent_data) = world.get::<StateMachinePersistentData>(root_entity) {
+            for transition in &persistent_data.visual_transitions {
+                let edge = transition.edge_entity;
+                if world.get::<crate::DisabledEdge>(edge).is_some() && !entities.contains(&edge) {
+                    entities.push(edge);
+                }
+            }
+        }
+
         Ok(entities)
     }
 
@@ -285,6 +387,14 @@ impl ReflectableStateMachinePersistentData {
     }
 }
 
+/// Leave out components that only describe a running machine (currently `Active`)
+///
+/// Saved, copied and snapshotted machines then start inert and are entered from their `InitialState`
+/// when spawned. Editor-only markers such as `EditorPaused` are not reflected and never extracted.
+pub fn deny_runtime_components(builder: DynamicSceneBuilder<'_>) -> DynamicSceneBuilder<'_> {
+    builder.deny_component::<Active>()
+}
+
 /// Determine the node type based on whether the entity has children
 fn determine_node_type(entity: Entity, world: &World) -> ReflectableNodeType {
     // Parent if it has logical state children; otherwise Leaf
@@ -310,11 +420,52 @@ pub(crate) fn on_add_reflectable_state_machine(
         .insert(persistent_data);
 }
 
+/// Hash of everything `from_persistent_data` serializes
+/// 
+/// Per-node and collapsed entries are combined order-independently since they come from sets/maps.
+fn persisted_fingerprint(data: &StateMachinePersistentData) -> u64 {
+    fn hash_one(value: impl Hash) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+    let pos_bits = |pos: egui::Pos2| (pos.x.to_bits(), pos.y.to_bits());
+
+    let nodes = data.nodes.iter().fold(0u64, |sum, (entity, node)| {
+        let is_parent = matches!(node, NodeType::Parent(_));
+        sum.wrapping_add(hash_one((entity, pos_bits(node.position()), is_parent, data.node_colors.get(entity), data.z_bias.get(entity))))
+    });
+    let collapsed = data.collapsed.iter().fold(0u64, |sum, entity| sum.wrapping_add(hash_one(entity)));
+
+    let mut hasher = DefaultHasher::new();
+    (nodes, collapsed, data.orthogonal_edges, data.inspected_entity).hash(&mut hasher);
+    for transition in &data.visual_transitions {
+        (transition.source_entity, transition.edge_entity, transition.target_entity, &transition.event_type).hash(&mut hasher);
+        pos_bits(transition.event_node_position).hash(&mut hasher);
+        (transition.event_node_offset.x.to_bits(), transition.event_node_offset.y.to_bits()).hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Rebuild the reflectable form when persisted data actually changed
+/// 
+/// `Changed<StateMachinePersistentData>` fires every frame for open machines (the canvas and
+/// the edge sync write derived fields through `Mut`), so a fingerprint of the serialized fields
+/// decides whether the rebuild is needed. Fingerprints of despawned machines are dropped.
 pub(crate) fn sync_reflectable_on_persistent_change(
-    query: Query<Entity, Changed<StateMachinePersistentData>>,
+    query: Query<(Entity, &StateMachinePersistentData), Changed<StateMachinePersistentData>>,
+    mut removed: RemovedComponents<StateMachinePersistentData>,
+    mut fingerprints: Local<HashMap<Entity, u64>>,
     mut commands: Commands,
 ) {
-    for entity in query.iter() {
+    for entity in removed.read() {
+        fingerprints.remove(&entity);
+    }
+    for (entity, persistent_data) in query.iter() {
+        let fingerprint = persisted_fingerprint(persistent_data);
+        if fingerprints.insert(entity, fingerprint) == Some(fingerprint) {
+            continue;
+        }
         commands.queue(move |world: &mut World| {
             if !world.entities().contains(entity) {
                 return;
@@ -0,0 +1,201 @@
+//! Manually firing events at running machines
+//!
+//! This module handles:
+//! - Registering event types the editor may construct (`register_sendable_event`)
+//! - Building those events through reflection with their entity fields set to the target machine
+//! - Rendering the "Send event" window listing discovered and registered event types
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use bevy::reflect::{DynamicStruct, TypeInfo, Typed};
+use bevy_egui::egui;
+
+use crate::editor_state::{EditorState, OpenMachine};
+
+/// Constructs an event for a machine and triggers it
+type SendFn = fn(&mut World, Entity) -> Result<(), String>;
+
+/// An event type the "Send event" panel can fire
+#[derive(Clone)]
+struct SendableEvent {
+    send: SendFn,
+    /// Why the event can't be built by the editor (fields other than entities), if it can't
+    unsupported: Option<String>,
+}
+
+/// Event types registered with `register_sendable_event`, keyed by their short type name
+#[derive(Resource, Default)]
+pub struct SendableEvents {
+    events: BTreeMap<String, SendableEvent>,
+}
+
+impl SendableEvents {
+    /// Register `E` so the editor can construct and trigger it
+    pub fn register<E>(&mut self)
+    where
+        E: EntityEvent + FromReflect + Typed,
+        for<'a> E::Trigger<'a>: Default,
+    {
+        self.events.insert(
+            E::short_type_path().to_string(),
+            SendableEvent { send: send_reflected::<E>, unsupported: unsupported_fields(E::type_info()) },
+        );
+    }
+
+    /// Whether an event type with this short name has been registered
+    pub fn contains(&self, event_type: &str) -> bool {
+        self.events.contains_key(event_type)
+    }
+
+    /// Short names of all registered event types
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.events.keys().map(String::as_str)
+    }
+}
+
+/// App extension for making events sendable from the editor
+pub trait SendableEventAppExt {
+    /// Let the "Send event" panel fire `E`; every `Entity` field is set to the target machine
+    fn register_sendable_event<E>(&mut self) -> &mut Self
+    where
+        E: EntityEvent + FromReflect + Typed,
+        for<'a> E::Trigger<'a>: Default;
+}
+
+impl SendableEventAppExt for App {
+    fn register_sendable_event<E>(&mut self) -> &mut Self
+    where
+        E: EntityEvent + FromReflect + Typed,
+        for<'a> E::Trigger<'a>: Default,
+    {
+        self.init_resource::<SendableEvents>();
+        self.world_mut().resource_mut::<SendableEvents>().register::<E>();
+        self
+    }
+}
+
+/// Why an event type can't be built from just a target entity, if it can't
+fn unsupported_fields(type_info: &TypeInfo) -> Option<String> {
+    let TypeInfo::Struct(struct_info) = type_info else {
+        return Some("Only events with named fields are supported".to_string());
+    };
+    struct_info
+        .iter()
+        .find(|field| !field.is::<Entity>())
+        .map(|field| format!("Field `{}` needs a value; only entity fields are filled in", field.name()))
+}
+
+/// Build `E` with all of its entity fields set to `target` and trigger it
+fn send_reflected<E>(world: &mut World, target: Entity) -> Result<(), String>
+where
+    E: EntityEvent + FromReflect + Typed,
+    for<'a> E::Trigger<'a>: Default,
+{
+    if let Some(reason) = unsupported_fields(E::type_info()) {
+        return Err(reason);
+    }
+    let TypeInfo::Struct(struct_info) = E::type_info() else {
+        return Err("Only events with named fields are supported".to_string());
+    };
+    let mut dynamic = DynamicStruct::default();
+    for field in struct_info.iter() {
+        dynamic.insert(field.name(), target);
+    }
+    let event = E::from_reflect(&dynamic).ok_or_else(|| format!("Could not build {} from reflection", E::short_type_path()))?;
+    world.trigger(event);
+    Ok(())
+}
+
+/// Render the "Send event" window if it should be visible
+///
+/// `discovered` are the event types edges listen for (from `EventEdge<E>` registrations);
+/// they are listed alongside registered ones, greyed out until registered.
+pub fn render_send_event_panel(
+    ctx: &egui::Context,
+    editor_state: &mut EditorState,
+    sendable: &SendableEvents,
+    discovered: &[String],
+    selected_machine: Option<Entity>,
+    commands: &mut Commands,
+) {
+    if !editor_state.show_send_event_panel {
+        editor_state.send_event_preview = None;
+        return;
+    }
+
+    // The machine picked in the window, else the selected state's machine, else the first open one
+    let target = editor_state
+        .send_event_machine
+        .or(selected_machine)
+        .filter(|machine| editor_state.is_machine_open(*machine))
+        .or_else(|| editor_state.view.open_machines.first().map(|machine| machine.entity));
+
+    let mut names: Vec<&str> = discovered.iter().map(String::as_str).chain(sendable.names()).collect();
+    names.sort_unstable();
+    names.dedup();
+
+    let mut open = true;
+    let mut picked_machine = None;
+    let mut hovered_event = None;
+    egui::Window::new("Send event")
+        .id(egui::Id::new("send_event_panel"))
+        .open(&mut open)
+        .default_size(egui::vec2(280.0, 300.0))
+        .show(ctx, |ui| {
+            let display_name = |machine: Option<Entity>| {
+                machine
+                    .and_then(|machine| editor_state.view.open_machines.iter().find(|m| m.entity == machine))
+                    .map_or("No machine open".to_string(), |m: &OpenMachine| m.display_name.clone())
+            };
+            egui::ComboBox::from_label("Target")
+                .selected_text(display_name(target))
+                .show_ui(ui, |ui| {
+                    for open_machine in &editor_state.view.open_machines {
+                        if ui.selectable_label(target == Some(open_machine.entity), &open_machine.display_name).clicked() {
+                            picked_machine = Some(open_machine.entity);
+                        }
+                    }
+                });
+            ui.separator();
+
+            if names.is_empty() {
+                ui.weak("No event types found");
+            }
+            egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                egui::Grid::new("send_event_grid").striped(true).num_columns(2).show(ui, |ui| {
+                    for name in names {
+                        // Hovering a row previews the edges that listen for this event
+                        let label = ui.label(name);
+                        let event = sendable.events.get(name);
+                        let reason = match event {
+                            None => Some(format!("Register it with `app.register_sendable_event::<{}>()` to send it from the editor", name)),
+                            Some(event) => event.unsupported.clone(),
+                        };
+                        let enabled = target.is_some() && reason.is_none();
+                        let response = ui
+                            .add_enabled(enabled, egui::Button::new("Send"))
+                            .on_disabled_hover_text(reason.unwrap_or_else(|| "Open a machine to send events to".to_string()));
+                        if let (true, Some(event), Some(machine)) = (response.clicked(), event, target) {
+                            let send = event.send;
+                            let name = name.to_string();
+                            commands.queue(move |world: &mut World| match send(world, machine) {
+                                Ok(()) => info!("📨 Sent {} to {:?}", name, machine),
+                                Err(e) => warn!("⚠️ Failed to send {} to {:?}: {}", name, machine, e),
+                            });
+                        }
+                        if label.hovered() || response.hovered() {
+                            hovered_event = Some(name.to_string());
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+
+    editor_state.send_event_preview = hovered_event;
+    if picked_machine.is_some() {
+        editor_state.send_event_machine = picked_machine;
+    }
+    editor_state.show_send_event_panel = open;
+}
@@ -0,0 +1,65 @@
+//! Pause and single-step of transition feedback for a running machine
+//!
+//! This module handles:
+//! - Toggling the `EditorPaused` marker on a machine root
+//! - Releasing buffered transitions one at a time (Step) or all at once (Resume)
+//!
+//! gearbox does not consult `EditorPaused` yet, so the machine itself keeps running; what is
+//! paused is the editor's view of it. Transitions observed while paused are buffered instead of
+//! pulsing and entering the timeline, and Step replays them in firing order.
+
+use bevy::prelude::*;
+use bevy_gearbox::StateMachine;
+
+use crate::editor_state::{EditorConfig, EditorPaused, StepMachine, StateMachineTransientData, TogglePauseMachine, TransitionPulse, TransitionRecord, TRANSITION_HISTORY_LEN};
+
+/// Record a transition in the timeline, dropping the oldest once full
+pub(crate) fn push_transition_history(transient: &mut StateMachineTransientData, record: TransitionRecord) {
+    if transient.transition_history.len() >= TRANSITION_HISTORY_LEN {
+        transient.transition_history.pop_front();
+    }
+    transient.transition_history.push_back(record);
+}
+
+/// Observer to pause or resume a machine's transition feedback
+///
+/// Resuming moves everything still buffered into the timeline without pulsing it.
+pub fn handle_toggle_pause_machine(
+    toggle: On<TogglePauseMachine>,
+    mut q_sm: Query<(&mut StateMachineTransientData, Has<EditorPaused>), With<StateMachine>>,
+    mut commands: Commands,
+) {
+    let Ok((mut transient, paused)) = q_sm.get_mut(toggle.entity) else {
+        return;
+    };
+    if paused {
+        let buffered: Vec<TransitionRecord> = transient.paused_transitions.drain(..).collect();
+        let released = buffered.len();
+        for record in buffered {
+            push_transition_history(&mut transient, record);
+        }
+        commands.entity(toggle.entity).remove::<EditorPaused>();
+        info!("▶ Resumed {:?} ({} buffered transitions released)", toggle.entity, released);
+    } else {
+        commands.entity(toggle.entity).insert(EditorPaused);
+        info!("⏸ Paused {:?}", toggle.entity);
+    }
+}
+
+/// Observer to release the oldest buffered transition of a paused machine
+pub fn handle_step_machine(
+    step: On<StepMachine>,
+    mut q_sm: Query<&mut StateMachineTransientData, (With<StateMachine>, With<EditorPaused>)>,
+    config: Res<EditorConfig>,
+) {
+    let Ok(mut transient) = q_sm.get_mut(step.entity) else {
+        return;
+    };
+    let Some(record) = transient.paused_transitions.pop_front() else {
+        return;
+    };
+    if !config.disable_animations {
+        transient.transition_pulses.push(TransitionPulse::new(record.source, record.target, record.edge, config.transition_pulse_seconds));
+    }
+    push_transition_history(&mut transient, record);
+}
@@ -0,0 +1,66 @@
+//! Headless harness for driving the editor without a window
+//!
+//! This module handles:
+//! - Building a minimal `App` with the editor's observers and sync systems but no egui UI
+//! - Spawning state machine hierarchies and opening them on the (virtual) canvas
+//! - Reading back editor data so tests can assert on world state
+//!
+//! Only available with the `test_support` feature.
+
+use bevy::prelude::*;
+use bevy_gearbox::{GearboxPlugin, InitialState, StateChildOf, StateMachine};
+
+use crate::{GearboxEditorPlugin, OpenMachineRequested, StateMachinePersistentData, StateMachineTransientData};
+
+/// Build an `App` running the editor logic headlessly
+///
+/// Egui windows, hotkeys and inspector systems are left out; observers (transition creation,
+/// deletion, reparenting, ...) and the per-frame sync systems are registered as usual. The
+/// layout sidecar points at a temp file so no user layout is read.
+pub fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, GearboxPlugin))
+        .add_plugins(GearboxEditorPlugin {
+            layout_path: std::env::temp_dir().join("bevy_gearbox_editor_test_layout.ron"),
+            headless: true,
+            ..Default::default()
+        });
+    app
+}
+
+/// Spawn a state machine root with one leaf child per entry of `children`
+///
+/// The first child becomes the root's `InitialState`. Returns the root and the children in order.
+pub fn spawn_machine(world: &mut World, name: &str, children: &[&str]) -> (Entity, Vec<Entity>) {
+    let root = world.spawn(Name::new(name.to_string())).id();
+    let children: Vec<Entity> = children
+        .iter()
+        .map(|child| world.spawn((Name::new(child.to_string()), StateChildOf(root))).id())
+        .collect();
+    // Insert the machine last so it starts with its hierarchy in place
+    match children.first() {
+        Some(&first) => world.entity_mut(root).insert((StateMachine::new(), InitialState(first))),
+        None => world.entity_mut(root).insert(StateMachine::new()),
+    };
+    (root, children)
+}
+
+/// Open `machine` in the editor and run a frame so its nodes and edge visuals are populated
+pub fn open_machine(app: &mut App, machine: Entity) {
+    app.world_mut().trigger(OpenMachineRequested {
+        entity: machine,
+        position: None,
+        focus_child: None,
+    });
+    app.update();
+}
+
+/// The editor's persisted data for an open machine
+pub fn persistent_data(app: &App, machine: Entity) -> Option<&StateMachinePersistentData> {
+    app.world().get::<StateMachinePersistentData>(machine)
+}
+
+/// The editor's transient data for an open machine
+pub fn transient_data(app: &App, machine: Entity) -> Option<&StateMachineTransientData> {
+    app.world().get::<StateMachineTransientData>(machine)
+}
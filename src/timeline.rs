@@ -0,0 +1,79 @@
+//! Timeline of recently fired transitions
+//!
+//! This module handles:
+//! - Rendering a scrollable window listing each open machine's recent transitions
+//! - Selecting both endpoints and re-flashing a transition when its row is clicked
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+use bevy_gearbox::StateMachine;
+
+use crate::editor_state::{entity_display_name, EditorConfig, EntityNamesQuery, EditorState, Select, StateMachinePersistentData, StateMachineTransientData, TransitionPulse};
+
+/// Render the transition timeline window if it should be visible
+pub fn render_transition_timeline(
+    ctx: &egui::Context,
+    editor_state: &mut EditorState,
+    q_sm_data: &mut Query<(Entity, Option<&Name>, Option<&mut StateMachinePersistentData>, Option<&mut StateMachineTransientData>), With<StateMachine>>,
+    all_entities: &EntityNamesQuery,
+    config: &EditorConfig,
+    commands: &mut Commands,
+) {
+    if !editor_state.show_transition_timeline {
+        return;
+    }
+
+    let mut open = true;
+    let mut clicked: Option<(Entity, usize)> = None;
+    egui::Window::new("Transition timeline")
+        .id(egui::Id::new("transition_timeline"))
+        .open(&mut open)
+        .default_size(egui::vec2(420.0, 260.0))
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                for open_machine in &editor_state.view.open_machines {
+                    let Ok((_, _, _, Some(transient_data))) = q_sm_data.get(open_machine.entity) else {
+                        continue;
+                    };
+                    ui.strong(&open_machine.display_name);
+                    if transient_data.transition_history.is_empty() {
+                        ui.weak("No transitions yet");
+                    }
+                    egui::Grid::new(("transition_timeline_grid", open_machine.entity))
+                        .striped(true)
+                        .num_columns(4)
+                        .show(ui, |ui| {
+                            // Newest first
+                            for (index, record) in transient_data.transition_history.iter().enumerate().rev() {
+                                let time = ui.selectable_label(false, format!("{:.3}s", record.time));
+                                ui.label(entity_display_name(record.source, all_entities));
+                                ui.label(format!("→ {}", entity_display_name(record.target, all_entities)));
+                                ui.label(&record.event_type);
+                                ui.end_row();
+                                if time.on_hover_text("Select both states and flash this transition").clicked() {
+                                    clicked = Some((open_machine.entity, index));
+                                }
+                            }
+                        });
+                    ui.separator();
+                }
+            });
+        });
+    editor_state.show_transition_timeline = open;
+
+    let Some((machine, index)) = clicked else {
+        return;
+    };
+    let Ok((_, _, _, Some(mut transient_data))) = q_sm_data.get_mut(machine) else {
+        return;
+    };
+    let Some(record) = transient_data.transition_history.get(index).cloned() else {
+        return;
+    };
+    // Multi-select both endpoints; selecting the source keeps the set since it is a member
+    editor_state.view.selected_nodes = [record.source, record.target].into_iter().collect();
+    if !config.disable_animations {
+        transient_data.transition_pulses.push(TransitionPulse::new(record.source, record.target, record.edge, config.transition_pulse_seconds));
+    }
+    commands.trigger(Select { selected: Some(record.source) });
+}
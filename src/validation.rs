@@ -0,0 +1,257 @@
+//! Static validation of state machines
+//!
+//! This module handles:
+//! - Walking a machine from its root through `InitialState`, parallel regions and edge targets, entering
+//!   ancestors of a target without their initial state
+//! - Reporting states that can never be entered
+//! - Reporting parents with children but no `InitialState`
+
+use bevy::prelude::*;
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy_gearbox::{InitialState, Parallel, StateChildOf, StateChildren};
+use bevy_gearbox::transitions::{Target, Transitions as EdgeTransitions};
+
+use crate::editor_state::{entity_display_name, EntityNamesQuery, StateMachineTransientData, ValidateMachine, ValidationIssue};
+
+/// Observer to validate a machine and store the issues on its transient data
+pub fn handle_validate_machine(
+    validate: On<ValidateMachine>,
+    q_children: Query<&StateChildren>,
+    q_child_of: Query<&StateChildOf>,
+    q_initial: Query<&InitialState>,
+    q_parallel: Query<(), With<Parallel>>,
+    q_transitions: Query<&EdgeTransitions>,
+    q_target: Query<&Target>,
+    q_entities: EntityNamesQuery,
+    mut q_transient: Query<&mut StateMachineTransientData>,
+) {
+    let root = validate.entity;
+    let Ok(mut transient_data) = q_transient.get_mut(root) else {
+        warn!("⚠️ Cannot validate {:?}: not an open state machine", root);
+        return;
+    };
+
+    // Snapshot the machine into plain maps for the reachability walk
+    let mut graph = ReachabilityGraph::default();
+    for state in std::iter::once(root).chain(q_children.iter_descendants(root)) {
+        if let Ok(child_of) = q_child_of.get(state) {
+            graph.parent.insert(state, child_of.0);
+        }
+        if let Ok(children) = q_children.get(state) {
+            graph.children.insert(state, children.into_iter().copied().collect());
+        }
+        if let Ok(initial) = q_initial.get(state) {
+            graph.initial.insert(state, initial.0);
+        }
+        if q_parallel.contains(state) {
+            graph.parallel.insert(state);
+        }
+        if let Ok(transitions) = q_transitions.get(state) {
+            let targets = transitions.into_iter().filter_map(|edge| q_target.get(*edge).ok().map(|target| target.0)).collect();
+            graph.targets.insert(state, targets);
+        }
+    }
+    let reachable = reachable_states(root, &graph);
+
+    transient_data.validation_issues.clear();
+    let states = std::iter::once(root).chain(q_children.iter_descendants(root));
+    for state in states {
+        let mut issues = Vec::new();
+        if !reachable.contains(&state) {
+            issues.push(ValidationIssue::Unreachable);
+        }
+        let has_children = q_children.get(state).is_ok_and(|children| children.into_iter().next().is_some());
+        if has_children && !q_parallel.contains(state) && !q_initial.contains(state) {
+            issues.push(ValidationIssue::MissingInitialState);
+        }
+        if issues.is_empty() {
+            continue;
+        }
+        let name = entity_display_name(state, &q_entities);
+        for issue in &issues {
+            warn!("⚠️ {}: {}", name, issue.description());
+        }
+        transient_data.validation_issues.insert(state, issues);
+    }
+
+    if transient_data.validation_issues.is_empty() {
+        info!("✅ Validated {:?}: no issues found", root);
+    } else {
+        warn!("⚠️ Validated {:?}: {} state(s) with issues", root, transient_data.validation_issues.len());
+    }
+}
+
+/// The parts of a machine the reachability walk needs, as plain maps
+#[derive(Default)]
+pub(crate) struct ReachabilityGraph {
+    /// `StateChildOf` of every non-root state
+    pub parent: HashMap<Entity, Entity>,
+    /// `StateChildren` of every parent, in order
+    pub children: HashMap<Entity, Vec<Entity>>,
+    /// `InitialState` of every parent that has one
+    pub initial: HashMap<Entity, Entity>,
+    /// States marked `Parallel`
+    pub parallel: HashSet<Entity>,
+    /// Target states of each state's outgoing edges
+    pub targets: HashMap<Entity, Vec<Entity>>,
+}
+
+/// Every state that can become active, starting from `root`
+///
+/// A state entered by default (the root, a transition target, an initial state or a parallel
+/// region) enters its own initial state or all of its regions. Its ancestors are entered only
+/// on the way down to it: they don't enter their initial state, except that a parallel ancestor
+/// still enters its other regions.
+pub(crate) fn reachable_states(root: Entity, graph: &ReachabilityGraph) -> HashSet<Entity> {
+    let mut reachable = HashSet::new();
+    let mut default_entered = HashSet::new();
+    let mut to_enter = vec![root];
+    // Each newly reachable state's transitions are followed once
+    let mark = |state: Entity, reachable: &mut HashSet<Entity>, to_enter: &mut Vec<Entity>| {
+        if reachable.insert(state) {
+            to_enter.extend(graph.targets.get(&state).into_iter().flatten().copied());
+        }
+    };
+    while let Some(state) = to_enter.pop() {
+        if !default_entered.insert(state) {
+            continue;
+        }
+        mark(state, &mut reachable, &mut to_enter);
+
+        // Ancestors entered on the way down
+        let mut child = state;
+        while let Some(&parent) = graph.parent.get(&child) {
+            mark(parent, &mut reachable, &mut to_enter);
+            if graph.parallel.contains(&parent) {
+                let regions = graph.children.get(&parent).into_iter().flatten();
+                to_enter.extend(regions.filter(|region| **region != child).copied());
+            }
+            child = parent;
+        }
+
+        // Default entry of the state itself
+        if graph.parallel.contains(&state) {
+            to_enter.extend(graph.children.get(&state).into_iter().flatten().copied());
+        } else if let Some(&initial) = graph.initial.get(&state) {
+            to_enter.push(initial);
+        }
+    }
+    reachable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A graph over `count` fresh entities
+    fn entities(count: usize) -> Vec<Entity> {
+        let mut world = World::new();
+        (0..count).map(|_| world.spawn_empty().id()).collect()
+    }
+
+    fn add_child(graph: &mut ReachabilityGraph, parent: Entity, child: Entity) {
+        graph.parent.insert(child, parent);
+        graph.children.entry(parent).or_default().push(child);
+    }
+
+    #[test]
+    fn initial_chain_is_reachable_and_other_children_are_not() {
+        let e = entities(3);
+        let (root, a, b) = (e[0], e[1], e[2]);
+        let mut graph = ReachabilityGraph::default();
+        add_child(&mut graph, root, a);
+        add_child(&mut graph, root, b);
+        graph.initial.insert(root, a);
+
+        let reachable = reachable_states(root, &graph);
+        assert!(reachable.contains(&root) && reachable.contains(&a));
+        assert!(!reachable.contains(&b));
+    }
+
+    #[test]
+    fn direct_target_into_non_initial_child_does_not_enter_its_initial_sibling() {
+        // root -> A (initial); A -> P; P has children X (initial) and Y; only Y is targeted
+        let e = entities(5);
+        let (root, a, p, x, y) = (e[0], e[1], e[2], e[3], e[4]);
+        let mut graph = ReachabilityGraph::default();
+        add_child(&mut graph, root, a);
+        add_child(&mut graph, root, p);
+        add_child(&mut graph, p, x);
+        add_child(&mut graph, p, y);
+        graph.initial.insert(root, a);
+        graph.initial.insert(p, x);
+        graph.targets.insert(a, vec![y]);
+
+        let reachable = reachable_states(root, &graph);
+        assert!(reachable.contains(&y));
+        assert!(reachable.contains(&p), "the target's parent is entered on the way down");
+        assert!(!reachable.contains(&x), "P was never entered by default");
+    }
+
+    #[test]
+    fn parallel_regions_are_all_entered() {
+        // root (parallel) with regions R1 and R2; R2 has initial C
+        let e = entities(4);
+        let (root, r1, r2, c) = (e[0], e[1], e[2], e[3]);
+        let mut graph = ReachabilityGraph::default();
+        add_child(&mut graph, root, r1);
+        add_child(&mut graph, root, r2);
+        add_child(&mut graph, r2, c);
+        graph.parallel.insert(root);
+        graph.initial.insert(r2, c);
+
+        let reachable = reachable_states(root, &graph);
+        assert!([root, r1, r2, c].iter().all(|state| reachable.contains(state)));
+    }
+
+    #[test]
+    fn targeting_into_one_region_enters_the_other_regions() {
+        // root -> A (initial); A -> X inside region R1 of parallel P; R2 is entered by default
+        let e = entities(7);
+        let (root, a, p, r1, r2, x, r2_initial) = (e[0], e[1], e[2], e[3], e[4], e[5], e[6]);
+        let mut graph = ReachabilityGraph::default();
+        add_child(&mut graph, root, a);
+        add_child(&mut graph, root, p);
+        add_child(&mut graph, p, r1);
+        add_child(&mut graph, p, r2);
+        add_child(&mut graph, r1, x);
+        add_child(&mut graph, r2, r2_initial);
+        graph.initial.insert(root, a);
+        graph.parallel.insert(p);
+        graph.initial.insert(r2, r2_initial);
+        graph.targets.insert(a, vec![x]);
+
+        let reachable = reachable_states(root, &graph);
+        assert!([p, r1, x, r2, r2_initial].iter().all(|state| reachable.contains(state)));
+    }
+
+    #[test]
+    fn parent_without_initial_state_enters_no_children() {
+        let e = entities(3);
+        let (root, a, b) = (e[0], e[1], e[2]);
+        let mut graph = ReachabilityGraph::default();
+        add_child(&mut graph, root, a);
+        add_child(&mut graph, root, b);
+
+        let reachable = reachable_states(root, &graph);
+        assert!(reachable.contains(&root));
+        assert!(!reachable.contains(&a) && !reachable.contains(&b));
+    }
+
+    #[test]
+    fn transitions_from_climbed_ancestors_are_followed() {
+        // root -> A (initial); A -> Y inside P; P -> B
+        let e = entities(5);
+        let (root, a, p, y, b) = (e[0], e[1], e[2], e[3], e[4]);
+        let mut graph = ReachabilityGraph::default();
+        add_child(&mut graph, root, a);
+        add_child(&mut graph, root, p);
+        add_child(&mut graph, root, b);
+        add_child(&mut graph, p, y);
+        graph.initial.insert(root, a);
+        graph.targets.insert(a, vec![y]);
+        graph.targets.insert(p, vec![b]);
+
+        assert!(reachable_states(root, &graph).contains(&b));
+    }
+}
@@ -1,43 +1,425 @@
 //! Multi-window support for the editor
 //! 
 //! This module handles:
-//! - Creating new editor windows via hotkeys
+//! - Creating new editor windows via (configurable) hotkeys
 //! - Managing window entities and cameras
 //! - Setting up Egui contexts for multiple windows
+//! - Giving each window its own view (open machines, selection) and tracking the focused one
+//! - Restoring and saving the editor layout as windows open and close
+//! - Framing all open machines in the window ("fit to view", F)
 
 use bevy::camera::RenderTarget;
 use bevy::prelude::*;
 use bevy::window::{PrimaryWindow, WindowRef, WindowResolution};
-use bevy_egui::EguiMultipassSchedule;
+use bevy_egui::{egui, EguiContext, EguiMultipassSchedule, PrimaryEguiContext};
 
-use crate::editor_state::EditorWindow;
-use crate::EditorWindowContextPass;
+use crate::editor_state::{EditorState, EditorWindow, EditorWindowCamera, EditorWindowView, MIN_CANVAS_ZOOM};
+use crate::{EditorWindowContextPass, EditorWindowPass};
+
+/// A key plus the modifiers that must be held with it, read from Bevy input
+///
+/// Modifiers not asked for must be released, so Ctrl+O and Ctrl+Shift+O can be bound separately.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub key: KeyCode,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl KeyBinding {
+    /// The key on its own
+    pub const fn key(key: KeyCode) -> Self {
+        Self { key, ctrl: false, shift: false, alt: false }
+    }
+
+    /// Ctrl + `key`
+    pub const fn ctrl(key: KeyCode) -> Self {
+        Self { key, ctrl: true, shift: false, alt: false }
+    }
+
+    /// Ctrl + Shift + `key`
+    pub const fn ctrl_shift(key: KeyCode) -> Self {
+        Self { key, ctrl: true, shift: true, alt: false }
+    }
+
+    /// Shift + `key`
+    pub const fn shift(key: KeyCode) -> Self {
+        Self { key, ctrl: false, shift: true, alt: false }
+    }
+
+    /// Whether the binding was pressed this frame
+    pub fn just_pressed(&self, input: &ButtonInput<KeyCode>) -> bool {
+        let held = |left, right| input.any_pressed([left, right]);
+        input.just_pressed(self.key)
+            && held(KeyCode::ControlLeft, KeyCode::ControlRight) == self.ctrl
+            && held(KeyCode::ShiftLeft, KeyCode::ShiftRight) == self.shift
+            && held(KeyCode::AltLeft, KeyCode::AltRight) == self.alt
+    }
+
+    /// Whether the binding was pressed this frame in an egui window
+    ///
+    /// Ctrl matches egui's `command` modifier, so it is Cmd on macOS.
+    pub fn pressed_in(&self, input: &egui::InputState) -> bool {
+        egui_key(self.key).is_some_and(|key| input.key_pressed(key) && input.modifiers.matches_exact(self.egui_modifiers()))
+    }
+
+    /// Like `pressed_in`, but consumes the press so later widgets don't see it
+    pub fn consume_in(&self, input: &mut egui::InputState) -> bool {
+        egui_key(self.key).is_some_and(|key| input.consume_key(self.egui_modifiers(), key))
+    }
+
+    fn egui_modifiers(&self) -> egui::Modifiers {
+        egui::Modifiers {
+            alt: self.alt,
+            shift: self.shift,
+            command: self.ctrl,
+            ..egui::Modifiers::NONE
+        }
+    }
+}
+
+impl std::fmt::Display for KeyBinding {
+    /// Human-readable form, e.g. `Ctrl+Shift+O`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.ctrl {
+            f.write_str("Ctrl+")?;
+        }
+        if self.shift {
+            f.write_str("Shift+")?;
+        }
+        if self.alt {
+            f.write_str("Alt+")?;
+        }
+        match egui_key(self.key) {
+            Some(key) => f.write_str(key.symbol_or_name()),
+            None => write!(f, "{:?}", self.key),
+        }
+    }
+}
+
+/// egui key for a Bevy key code, for the keys editor shortcuts are likely to use
+fn egui_key(key: KeyCode) -> Option<egui::Key> {
+    use egui::Key;
+    Some(match key {
+        KeyCode::KeyA => Key::A,
+        KeyCode::KeyB => Key::B,
+        KeyCode::KeyC => Key::C,
+        KeyCode::KeyD => Key::D,
+        KeyCode::KeyE => Key::E,
+        KeyCode::KeyF => Key::F,
+        KeyCode::KeyG => Key::G,
+        KeyCode::KeyH => Key::H,
+        KeyCode::KeyI => Key::I,
+        KeyCode::KeyJ => Key::J,
+        KeyCode::KeyK => Key::K,
+        KeyCode::KeyL => Key::L,
+        KeyCode::KeyM => Key::M,
+        KeyCode::KeyN => Key::N,
+        KeyCode::KeyO => Key::O,
+        KeyCode::KeyP => Key::P,
+        KeyCode::KeyQ => Key::Q,
+        KeyCode::KeyR => Key::R,
+        KeyCode::KeyS => Key::S,
+        KeyCode::KeyT => Key::T,
+        KeyCode::KeyU => Key::U,
+        KeyCode::KeyV => Key::V,
+        KeyCode::KeyW => Key::W,
+        KeyCode::KeyX => Key::X,
+        KeyCode::KeyY => Key::Y,
+        KeyCode::KeyZ => Key::Z,
+        KeyCode::Digit0 => Key::Num0,
+        KeyCode::Digit1 => Key::Num1,
+        KeyCode::Digit2 => Key::Num2,
+        KeyCode::Digit3 => Key::Num3,
+        KeyCode::Digit4 => Key::Num4,
+        KeyCode::Digit5 => Key::Num5,
+        KeyCode::Digit6 => Key::Num6,
+        KeyCode::Digit7 => Key::Num7,
+        KeyCode::Digit8 => Key::Num8,
+        KeyCode::Digit9 => Key::Num9,
+        KeyCode::ArrowLeft => Key::ArrowLeft,
+        KeyCode::ArrowRight => Key::ArrowRight,
+        KeyCode::ArrowUp => Key::ArrowUp,
+        KeyCode::ArrowDown => Key::ArrowDown,
+        KeyCode::Enter => Key::Enter,
+        KeyCode::Escape => Key::Escape,
+        KeyCode::Tab => Key::Tab,
+        KeyCode::Space => Key::Space,
+        KeyCode::Backspace => Key::Backspace,
+        KeyCode::Delete => Key::Delete,
+        KeyCode::Home => Key::Home,
+        KeyCode::End => Key::End,
+        KeyCode::PageUp => Key::PageUp,
+        KeyCode::PageDown => Key::PageDown,
+        KeyCode::Backquote => Key::Backtick,
+        KeyCode::Minus => Key::Minus,
+        KeyCode::Equal => Key::Equals,
+        KeyCode::F1 => Key::F1,
+        KeyCode::F2 => Key::F2,
+        KeyCode::F3 => Key::F3,
+        KeyCode::F4 => Key::F4,
+        KeyCode::F5 => Key::F5,
+        KeyCode::F6 => Key::F6,
+        KeyCode::F7 => Key::F7,
+        KeyCode::F8 => Key::F8,
+        KeyCode::F9 => Key::F9,
+        KeyCode::F10 => Key::F10,
+        KeyCode::F11 => Key::F11,
+        KeyCode::F12 => Key::F12,
+        _ => return None,
+    })
+}
+
+/// Editor hotkeys
+///
+/// Set through `GearboxEditorPlugin::hotkeys` to avoid clashing with the game's own bindings.
+/// The window bindings and `cancel_transition` are read from Bevy input, so they fire even when
+/// the editor window isn't focused; the canvas bindings go through egui and only apply while an
+/// editor window has focus and no text field is being edited.
+#[derive(Resource, Clone, Debug)]
+pub struct EditorHotkeys {
+    /// Open the editor window (default Ctrl+O)
+    pub open_editor: KeyBinding,
+    /// Open an additional editor window (default Ctrl+Shift+O)
+    pub new_window: KeyBinding,
+    /// Focus the next editor window (default Ctrl+`)
+    pub cycle_windows: KeyBinding,
+    /// Cancel in-progress transition creation (default Escape)
+    pub cancel_transition: KeyBinding,
+    /// Frame all open machines in the window (default F)
+    pub fit_to_view: KeyBinding,
+    /// Undo the last node move or deletion (default Ctrl+Z)
+    pub undo: KeyBinding,
+    /// Redo the last undone operation (default Ctrl+Shift+Z)
+    pub redo: KeyBinding,
+    /// Copy the selected state and its subtree (default Ctrl+C)
+    pub copy: KeyBinding,
+    /// Paste the copied subtree next to the original (default Ctrl+V)
+    pub paste: KeyBinding,
+    /// Add a sibling below the selected state (default Enter)
+    pub add_sibling: KeyBinding,
+    /// Select the nearest state to the left (default Left arrow)
+    pub select_left: KeyBinding,
+    /// Select the nearest state to the right (default Right arrow)
+    pub select_right: KeyBinding,
+    /// Select the nearest state above (default Up arrow)
+    pub select_up: KeyBinding,
+    /// Select the nearest state below (default Down arrow)
+    pub select_down: KeyBinding,
+    /// Select the next sibling (default Tab)
+    pub next_sibling: KeyBinding,
+    /// Select the previous sibling (default Shift+Tab)
+    pub previous_sibling: KeyBinding,
+}
+
+impl Default for EditorHotkeys {
+    fn default() -> Self {
+        Self {
+            open_editor: KeyBinding::ctrl(KeyCode::KeyO),
+            new_window: KeyBinding::ctrl_shift(KeyCode::KeyO),
+            cycle_windows: KeyBinding::ctrl(KeyCode::Backquote),
+            cancel_transition: KeyBinding::key(KeyCode::Escape),
+            fit_to_view: KeyBinding::key(KeyCode::KeyF),
+            undo: KeyBinding::ctrl(KeyCode::KeyZ),
+            redo: KeyBinding::ctrl_shift(KeyCode::KeyZ),
+            copy: KeyBinding::ctrl(KeyCode::KeyC),
+            paste: KeyBinding::ctrl(KeyCode::KeyV),
+            add_sibling: KeyBinding::key(KeyCode::Enter),
+            select_left: KeyBinding::key(KeyCode::ArrowLeft),
+            select_right: KeyBinding::key(KeyCode::ArrowRight),
+            select_up: KeyBinding::key(KeyCode::ArrowUp),
+            select_down: KeyBinding::key(KeyCode::ArrowDown),
+            next_sibling: KeyBinding::key(KeyCode::Tab),
+            previous_sibling: KeyBinding::shift(KeyCode::Tab),
+        }
+    }
+}
+
+/// An editor OS window and the camera its egui context lives on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EditorWindowHandle {
+    pub window: Entity,
+    pub camera: Entity,
+}
+
+/// The editor window whose egui pass is running; only present during `EditorWindowContextPass`
+#[derive(Resource, Clone, Copy, Debug, Deref)]
+pub struct CurrentEditorWindow(pub EditorWindowHandle);
+
+/// The editor window whose view currently lives in `EditorState` (the most recently focused one)
+#[derive(Resource, Default, Debug)]
+pub struct ActiveEditorWindow(pub Option<EditorWindowHandle>);
 
 /// System to handle hotkeys for opening editor windows
 /// 
-/// Listens for Ctrl+O to spawn new editor windows that go directly to the canvas.
-/// Only creates a new window if one doesn't already exist.
+/// `open_editor` (Ctrl+O) spawns the editor window that goes directly to the canvas; it is only
+/// created if one doesn't already exist. `new_window` (Ctrl+Shift+O) opens an additional, empty
+/// editor window, and `cycle_windows` (Ctrl+`) brings the next editor window to the front.
 pub fn handle_editor_hotkeys(
     input: Res<ButtonInput<KeyCode>>,
+    hotkeys: Res<EditorHotkeys>,
     primary_window: Query<Entity, With<PrimaryWindow>>,
-    existing_editor_windows: Query<Entity, With<EditorWindow>>,
+    mut q_editor_windows: Query<(Entity, &mut Window), With<EditorWindow>>,
     mut commands: Commands,
 ) {
-    if input.pressed(KeyCode::ControlLeft) && input.just_pressed(KeyCode::KeyO) {
+    if hotkeys.cycle_windows.just_pressed(&input) {
+        cycle_editor_window_focus(&mut q_editor_windows);
+    }
+    let open_editor = hotkeys.open_editor.just_pressed(&input);
+    let new_window = hotkeys.new_window.just_pressed(&input);
+    if open_editor || new_window {
         if let Ok(_primary_entity) = primary_window.single() {
             // Only spawn a new editor window if one doesn't already exist
-            if existing_editor_windows.is_empty() {
+            if q_editor_windows.is_empty() {
+                spawn_editor_window(&mut commands);
+                // Re-open machines recorded in the persisted layout
+                commands.queue(crate::layout::restore_pending_layout);
+            } else if new_window {
                 spawn_editor_window(&mut commands);
             } else {
-                info!("🪟 Editor window already exists, ignoring Ctrl+O");
+                info!("🪟 Editor window already exists, ignoring the open hotkey (the new-window hotkey opens another)");
             }
         }
     }
 }
 
+/// Focus the editor window after the focused one (in spawn order), wrapping around
+///
+/// Does nothing with fewer than two editor windows.
+fn cycle_editor_window_focus(q_editor_windows: &mut Query<(Entity, &mut Window), With<EditorWindow>>) {
+    let mut windows: Vec<Entity> = q_editor_windows.iter().map(|(entity, _)| entity).collect();
+    if windows.len() < 2 {
+        return;
+    }
+    windows.sort();
+    let focused = windows
+        .iter()
+        .position(|entity| q_editor_windows.get(*entity).is_ok_and(|(_, window)| window.focused));
+    let next = windows[focused.map_or(0, |index| (index + 1) % windows.len())];
+    if let Ok((_, mut window)) = q_editor_windows.get_mut(next) {
+        // Setting `focused` asks the OS to focus (and raise) the window
+        window.focused = true;
+        info!("🪟 Focused editor window {:?}", next);
+    }
+}
+
+/// System to make the focused editor window the active one
+///
+/// The outgoing window's view is stashed in its `EditorWindowView` and the focused window's view
+/// is loaded into `EditorState`, so observers and hotkeys act on the window the user is in.
+pub fn track_focused_editor_window(
+    mut q_windows: Query<(Entity, &Window, &EditorWindowCamera, &mut EditorWindowView), With<EditorWindow>>,
+    mut active: ResMut<ActiveEditorWindow>,
+    mut editor_state: ResMut<EditorState>,
+) {
+    let Some(focused) = q_windows
+        .iter()
+        .find(|(_, window, _, _)| window.focused)
+        .map(|(window, _, camera, _)| EditorWindowHandle { window, camera: camera.0 })
+    else {
+        return;
+    };
+    if active.0 == Some(focused) {
+        return;
+    }
+    if let Some(previous) = active.0 {
+        if let Ok((_, _, _, mut view)) = q_windows.get_mut(previous.window) {
+            view.swap_with(&mut editor_state);
+        }
+    }
+    if let Ok((_, _, _, mut view)) = q_windows.get_mut(focused.window) {
+        view.swap_with(&mut editor_state);
+    }
+    active.0 = Some(focused);
+}
+
+/// Run `EditorWindowContextPass` for one editor window
+///
+/// Windows other than the active one get their view swapped into `EditorState` for the pass,
+/// and `CurrentEditorWindow` tells the pass systems which egui context to draw into.
+fn run_editor_window_pass(world: &mut World, handle: EditorWindowHandle) {
+    let is_active = world.resource::<ActiveEditorWindow>().0 == Some(handle);
+    if !is_active {
+        swap_window_view(world, handle.window);
+    }
+    world.insert_resource(CurrentEditorWindow(handle));
+    world.run_schedule(EditorWindowContextPass);
+    world.remove_resource::<CurrentEditorWindow>();
+    if !is_active {
+        swap_window_view(world, handle.window);
+    }
+}
+
+/// Exchange a window's stashed view with the one in `EditorState`
+fn swap_window_view(world: &mut World, window: Entity) {
+    world.resource_scope(|world, mut editor_state: Mut<EditorState>| {
+        if let Some(mut view) = world.get_mut::<EditorWindowView>(window) {
+            view.swap_with(&mut editor_state);
+        }
+    });
+}
+
+/// Margin kept around the machines when fitting them to the view
+const FIT_TO_VIEW_MARGIN: f32 = 40.0;
+
+/// System to request framing all open machines when `EditorHotkeys::fit_to_view` (F) is pressed in the editor window
+pub fn handle_fit_to_view_hotkey(
+    mut q_editor_context: Query<&mut EguiContext, (With<EditorWindow>, Without<PrimaryEguiContext>)>,
+    current_window: Res<CurrentEditorWindow>,
+    hotkeys: Res<EditorHotkeys>,
+    mut editor_state: ResMut<EditorState>,
+) {
+    let Ok(mut egui_context) = q_editor_context.get_mut(current_window.camera) else {
+        return;
+    };
+    let ctx = egui_context.get_mut();
+    if ctx.wants_keyboard_input() {
+        return;
+    }
+    if ctx.input(|i| hotkeys.fit_to_view.pressed_in(i)) {
+        editor_state.view.fit_to_view_requested = true;
+    }
+}
+
+/// Move (and if needed zoom out) every open machine so their combined bounds fit in `viewport`
+///
+/// `machine_bounds` returns a machine's node bounds in its own (offset-free) coordinates.
+/// Machines keep their positions relative to each other; zoom never goes above 1.0 here.
+pub fn fit_open_machines_to_view(
+    editor_state: &mut EditorState,
+    machine_bounds: impl Fn(Entity) -> Option<egui::Rect>,
+    viewport: egui::Rect,
+) {
+    let mut bounds = egui::Rect::NOTHING;
+    for open_machine in &editor_state.view.open_machines {
+        if let Some(rect) = machine_bounds(open_machine.entity) {
+            bounds = bounds.union(rect.translate(open_machine.canvas_offset));
+        }
+    }
+    if !bounds.is_positive() {
+        return;
+    }
+
+    let available = viewport.shrink(FIT_TO_VIEW_MARGIN).size().max(egui::Vec2::splat(1.0));
+    let zoom = (available.x / bounds.width())
+        .min(available.y / bounds.height())
+        .clamp(MIN_CANVAS_ZOOM, 1.0);
+
+    // Shift every machine by the same amount so the bounds' center lands on the viewport's center
+    let origin = editor_state.view.canvas_origin.unwrap_or(viewport.min);
+    let shift = (viewport.center() - origin) / zoom + (origin - bounds.center());
+    for open_machine in &mut editor_state.view.open_machines {
+        open_machine.canvas_offset += shift;
+        open_machine.canvas_zoom = zoom;
+    }
+    info!("🖼️ Fit {} machine(s) to view at zoom {:.2}", editor_state.view.open_machines.len(), zoom);
+}
+
 /// Spawn a new editor window
 /// 
-/// Creates a new window entity with its own camera and Egui context.
+/// Creates a new window entity with its own camera, Egui context and view. The first editor
+/// window becomes the active one, so it shows the machines already in `EditorState`.
 fn spawn_editor_window(commands: &mut Commands) {
     // Spawn the window
     let window_entity = commands.spawn((
@@ -47,19 +429,33 @@ fn spawn_editor_window(commands: &mut Commands) {
             ..default()
         },
         EditorWindow,
+        EditorWindowView::default(),
     )).id();
     
-    // Spawn a camera for this window with the editor multipass schedule
-    commands.spawn((
+    // Spawn a camera for this window with its own multipass schedule
+    let camera_entity = commands.spawn((
         Camera3d::default(),
         Camera {
             order: 3,
             target: RenderTarget::Window(WindowRef::Entity(window_entity)),
             ..default()
         },
-        EguiMultipassSchedule::new(EditorWindowContextPass),
+        EguiMultipassSchedule::new(EditorWindowPass(window_entity)),
         EditorWindow, // Mark this camera as belonging to the editor
-    ));
+    )).id();
+    commands.entity(window_entity).insert(EditorWindowCamera(camera_entity));
+
+    // The window's pass schedule runs the shared editor pass against this window's context and view
+    let handle = EditorWindowHandle { window: window_entity, camera: camera_entity };
+    commands.queue(move |world: &mut World| {
+        world
+            .resource_mut::<Schedules>()
+            .add_systems(EditorWindowPass(handle.window), move |world: &mut World| run_editor_window_pass(world, handle));
+        let mut active = world.resource_mut::<ActiveEditorWindow>();
+        if active.0.is_none() {
+            active.0 = Some(handle);
+        }
+    });
     
     info!("🪟 Spawned new editor window");
 }
@@ -69,10 +465,15 @@ fn spawn_editor_window(commands: &mut Commands) {
 pub fn cleanup_editor_window(
     remove: On<Remove, Window>,
     cameras: Query<(Entity, &Camera), With<EditorWindow>>,
+    q_editor_windows: Query<(), (With<Window>, With<EditorWindow>)>,
     mut editor_state: ResMut<crate::editor_state::EditorState>,
+    mut active: ResMut<ActiveEditorWindow>,
     mut commands: Commands,
 ) {
     let removed_window = remove.entity;
+    if !q_editor_windows.contains(removed_window) {
+        return;
+    }
     for (cam_entity, camera) in cameras.iter() {
         if let RenderTarget::Window(WindowRef::Entity(win_entity)) = camera.target {
             if win_entity == removed_window {
@@ -80,6 +481,61 @@ pub fn cleanup_editor_window(
             }
         }
     }
-    // Clear all open machines when window closes
-    editor_state.open_machines.clear();
+    commands.queue(move |world: &mut World| {
+        world.resource_mut::<Schedules>().remove(EditorWindowPass(removed_window));
+    });
+
+    // Other windows' views go away with their entity; the active one lives in `EditorState`
+    if active.0.is_some_and(|handle| handle.window == removed_window) {
+        // Persist the layout before the open machines are cleared
+        if let Err(e) = editor_state.save_layout(&editor_state.layout_path) {
+            warn!("⚠️ Failed to save editor layout to {:?}: {}", editor_state.layout_path, e);
+        }
+        EditorWindowView::default().swap_with(&mut editor_state);
+        active.0 = None;
+    }
+}
+
+/// Save the editor layout when the app exits while an editor window is still open
+pub fn save_layout_on_exit(
+    mut exit: MessageReader<AppExit>,
+    q_editor_windows: Query<(), (With<Window>, With<EditorWindow>)>,
+    editor_state: Res<crate::editor_state::EditorState>,
+) {
+    if exit.read().next().is_none() || q_editor_windows.is_empty() {
+        return;
+    }
+    if let Err(e) = editor_state.save_layout(&editor_state.layout_path) {
+        warn!("⚠️ Failed to save editor layout to {:?}: {}", editor_state.layout_path, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bindings_display_with_modifiers() {
+        assert_eq!(KeyBinding::ctrl(KeyCode::KeyO).to_string(), "Ctrl+O");
+        assert_eq!(KeyBinding::ctrl_shift(KeyCode::KeyZ).to_string(), "Ctrl+Shift+Z");
+        assert_eq!(KeyBinding::key(KeyCode::KeyF).to_string(), "F");
+    }
+
+    #[test]
+    fn bindings_match_egui_input_exactly() {
+        let mut input = egui::InputState::default();
+        input.keys_down.insert(egui::Key::Z);
+        input.events.push(egui::Event::Key {
+            key: egui::Key::Z,
+            physical_key: None,
+            pressed: true,
+            repeat: false,
+            modifiers: egui::Modifiers::COMMAND,
+        });
+        input.modifiers = egui::Modifiers::COMMAND;
+        let hotkeys = EditorHotkeys::default();
+        assert!(hotkeys.undo.pressed_in(&input));
+        assert!(!hotkeys.redo.pressed_in(&input));
+        assert!(!KeyBinding::key(KeyCode::KeyZ).pressed_in(&input));
+    }
 }
@@ -0,0 +1,89 @@
+//! Integration tests for the editor's observers, driven through the headless harness
+
+use bevy::prelude::*;
+use bevy_gearbox::transitions::{Target, Transitions};
+use bevy_gearbox_editor::test_support::{headless_app, open_machine, persistent_data, spawn_machine};
+use bevy_gearbox_editor::{CreateTransition, DeleteNode, DisabledEdge, ToggleTransitionEnabled};
+
+/// Edges listed in `source`'s `Transitions`, in evaluation order
+fn outgoing(app: &App, source: Entity) -> Vec<Entity> {
+    app.world()
+        .get::<Transitions>(source)
+        .map(|transitions| transitions.into_iter().copied().collect())
+        .unwrap_or_default()
+}
+
+fn create_always(app: &mut App, source: Entity, target: Entity) -> Entity {
+    app.world_mut().trigger(CreateTransition {
+        source_entity: source,
+        target_entity: target,
+        event_type: "Always".to_string(),
+        internal: false,
+    });
+    app.update();
+    *outgoing(app, source).last().expect("the edge was added to its source's Transitions")
+}
+
+#[test]
+fn create_transition_spawns_an_edge() {
+    let mut app = headless_app();
+    let (machine, children) = spawn_machine(app.world_mut(), "Door", &["Closed", "Open"]);
+    open_machine(&mut app, machine);
+
+    let edge = create_always(&mut app, children[0], children[1]);
+
+    assert_eq!(app.world().get::<Target>(edge).map(|target| target.0), Some(children[1]));
+    let visuals = &persistent_data(&app, machine).unwrap().visual_transitions;
+    assert!(visuals.iter().any(|visual| visual.edge_entity == edge && visual.target_entity == children[1]));
+}
+
+#[test]
+fn delete_node_removes_the_state_and_its_incoming_edges() {
+    let mut app = headless_app();
+    let (machine, children) = spawn_machine(app.world_mut(), "Door", &["Closed", "Open"]);
+    open_machine(&mut app, machine);
+    let edge = create_always(&mut app, children[0], children[1]);
+
+    app.world_mut().trigger(DeleteNode { entity: children[1] });
+    app.update();
+
+    assert!(app.world().get_entity(children[1]).is_err());
+    assert!(app.world().get_entity(edge).is_err());
+    assert!(outgoing(&app, children[0]).is_empty());
+    assert!(!persistent_data(&app, machine).unwrap().nodes.contains_key(&children[1]));
+}
+
+#[test]
+fn reenabling_an_edge_restores_its_priority() {
+    let mut app = headless_app();
+    let (machine, children) = spawn_machine(app.world_mut(), "Door", &["Closed", "Open", "Locked"]);
+    open_machine(&mut app, machine);
+    create_always(&mut app, children[0], children[1]);
+    let middle = create_always(&mut app, children[0], children[2]);
+    create_always(&mut app, children[0], children[1]);
+    let before = outgoing(&app, children[0]);
+
+    app.world_mut().trigger(ToggleTransitionEnabled { edge_entity: middle });
+    app.update();
+    assert!(!outgoing(&app, children[0]).contains(&middle));
+    assert!(app.world().get::<DisabledEdge>(middle).is_some());
+
+    app.world_mut().trigger(ToggleTransitionEnabled { edge_entity: middle });
+    app.update();
+    assert_eq!(outgoing(&app, children[0]), before);
+}
+
+#[test]
+fn deleting_a_source_removes_its_disabled_edges() {
+    let mut app = headless_app();
+    let (machine, children) = spawn_machine(app.world_mut(), "Door", &["Closed", "Open"]);
+    open_machine(&mut app, machine);
+    let edge = create_always(&mut app, children[1], children[0]);
+    app.world_mut().trigger(ToggleTransitionEnabled { edge_entity: edge });
+    app.update();
+
+    app.world_mut().trigger(DeleteNode { entity: children[1] });
+    app.update();
+
+    assert!(app.world().get_entity(edge).is_err());
+}
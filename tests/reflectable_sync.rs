@@ -0,0 +1,67 @@
+//! Regression tests for mirroring the editor's persisted data into its reflectable form
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+use bevy_gearbox_editor::reflectable::ReflectableStateMachinePersistentData;
+use bevy_gearbox_editor::test_support::{headless_app, open_machine, spawn_machine};
+use bevy_gearbox_editor::StateMachinePersistentData;
+
+/// Tick at which the machine's reflectable data was last (re)inserted
+fn reflectable_changed_at(app: &App, machine: Entity) -> bevy::ecs::component::Tick {
+    app.world()
+        .entity(machine)
+        .get_ref::<ReflectableStateMachinePersistentData>()
+        .expect("open machines are mirrored into reflectable data")
+        .last_changed()
+}
+
+#[test]
+fn unchanged_machine_is_not_reserialized() {
+    let mut app = headless_app();
+    let (machine, _) = spawn_machine(app.world_mut(), "Door", &["Closed", "Open"]);
+    open_machine(&mut app, machine);
+    // Let parent sizing and edge sync settle first
+    for _ in 0..5 {
+        app.update();
+    }
+
+    let settled = reflectable_changed_at(&app, machine);
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(reflectable_changed_at(&app, machine), settled);
+}
+
+#[test]
+fn moved_node_is_reserialized() {
+    let mut app = headless_app();
+    let (machine, children) = spawn_machine(app.world_mut(), "Door", &["Closed", "Open"]);
+    open_machine(&mut app, machine);
+    for _ in 0..5 {
+        app.update();
+    }
+
+    let settled = reflectable_changed_at(&app, machine);
+    let mut persistent_data = app.world_mut().get_mut::<StateMachinePersistentData>(machine).unwrap();
+    let node = persistent_data.nodes.get_mut(&children[1]).unwrap();
+    let position = node.position();
+    node.set_position(position + egui::vec2(40.0, 0.0));
+    app.update();
+    assert_ne!(reflectable_changed_at(&app, machine), settled);
+}
+
+#[test]
+fn despawned_machine_is_forgotten() {
+    let mut app = headless_app();
+    let (machine, _) = spawn_machine(app.world_mut(), "Door", &["Closed", "Open"]);
+    open_machine(&mut app, machine);
+    app.update();
+    app.world_mut().despawn(machine);
+    app.update();
+
+    // A new machine is mirrored as usual, whatever id it is given
+    let (replacement, _) = spawn_machine(app.world_mut(), "Door", &["Closed", "Open"]);
+    open_machine(&mut app, replacement);
+    app.update();
+    assert!(app.world().get::<ReflectableStateMachinePersistentData>(replacement).is_some());
+}
@@ -0,0 +1,88 @@
+//! Round-trip test for saving a machine to a scene file and loading it back into the world
+
+use bevy::prelude::*;
+use bevy_gearbox::transitions::{Source, Target, Transitions};
+use bevy_gearbox::StateChildOf;
+use bevy_gearbox_editor::reflectable::ReflectableStateMachinePersistentData;
+use bevy_gearbox_editor::test_support::{headless_app, open_machine, persistent_data, spawn_machine};
+use bevy_gearbox_editor::{CreateTransition, StateMachinePersistentData};
+
+/// The live entity named `name`
+fn named(app: &mut App, name: &str) -> Entity {
+    let mut q_names = app.world_mut().query::<(Entity, &Name)>();
+    let matches: Vec<Entity> = q_names
+        .iter(app.world())
+        .filter(|(_, entity_name)| entity_name.as_str() == name)
+        .map(|(entity, _)| entity)
+        .collect();
+    assert_eq!(matches.len(), 1, "exactly one entity named {name}");
+    matches[0]
+}
+
+#[test]
+fn saved_machine_loads_with_editor_data_remapped() {
+    let mut app = headless_app();
+    let (machine, children) = spawn_machine(app.world_mut(), "Door", &["Closed", "Open"]);
+    let (closed, open) = (children[0], children[1]);
+    let ajar = app.world_mut().spawn((Name::new("Ajar"), StateChildOf(open))).id();
+    open_machine(&mut app, machine);
+
+    app.world_mut().trigger(CreateTransition {
+        source_entity: closed,
+        target_entity: open,
+        event_type: "Always".to_string(),
+        internal: false,
+    });
+    app.update();
+    let edge = *app.world().get::<Transitions>(closed).unwrap().into_iter().last().unwrap();
+
+    {
+        let mut persistent = app.world_mut().get_mut::<StateMachinePersistentData>(machine).unwrap();
+        persistent.collapsed.insert(open);
+        persistent.inspected_entity = Some(closed);
+    }
+    // Mirror the editor data into its reflectable form before saving
+    app.update();
+
+    let scene = ReflectableStateMachinePersistentData::create_state_machine_scene(app.world_mut(), machine).unwrap();
+    let serialized = {
+        let type_registry = app.world().resource::<AppTypeRegistry>().read();
+        scene.serialize(&type_registry).unwrap()
+    };
+    let path = std::env::temp_dir().join("bevy_gearbox_editor_scene_round_trip.scn.ron");
+    std::fs::write(&path, serialized).unwrap();
+
+    for entity in [edge, ajar, open, closed, machine] {
+        if let Ok(entity) = app.world_mut().get_entity_mut(entity) {
+            entity.despawn();
+        }
+    }
+
+    let roots = ReflectableStateMachinePersistentData::load_state_machine_into_world(app.world_mut(), &path).unwrap();
+    assert_eq!(roots.len(), 1);
+    let new_machine = roots[0];
+    let (new_closed, new_open, new_ajar) = (named(&mut app, "Closed"), named(&mut app, "Open"), named(&mut app, "Ajar"));
+    assert_ne!(new_closed, closed);
+
+    let persistent = persistent_data(&app, new_machine).expect("editor data is restored on the loaded root");
+    for state in [new_machine, new_closed, new_open, new_ajar] {
+        assert!(persistent.nodes.contains_key(&state), "{state:?} has a node");
+    }
+    assert!(persistent.nodes.keys().all(|entity| app.world().get_entity(*entity).is_ok()), "no node is keyed by a despawned entity");
+    assert!(persistent.collapsed.contains(&new_open));
+    assert_eq!(persistent.collapsed.len(), 1);
+    assert_eq!(persistent.inspected_entity, Some(new_closed));
+
+    // The edge is recreated and the visual points at it and its new endpoints
+    let new_edge = *app.world().get::<Transitions>(new_closed).unwrap().into_iter().last().unwrap();
+    assert_eq!(app.world().get::<Source>(new_edge).map(|source| source.0), Some(new_closed));
+    assert_eq!(app.world().get::<Target>(new_edge).map(|target| target.0), Some(new_open));
+    let visual = persistent
+        .visual_transitions
+        .iter()
+        .find(|visual| visual.edge_entity == new_edge)
+        .expect("the edge's visual is restored");
+    assert_eq!((visual.source_entity, visual.target_entity), (new_closed, new_open));
+
+    let _ = std::fs::remove_file(&path);
+}
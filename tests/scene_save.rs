@@ -0,0 +1,36 @@
+//! Regression tests for leaving runtime state out of saved state machine scenes
+
+use std::any::TypeId;
+
+use bevy::prelude::*;
+use bevy_gearbox::active::Active;
+use bevy_gearbox_editor::reflectable::ReflectableStateMachinePersistentData;
+use bevy_gearbox_editor::test_support::{headless_app, open_machine, spawn_machine};
+
+#[test]
+fn saved_scene_contains_no_active() {
+    let mut app = headless_app();
+    let (machine, children) = spawn_machine(app.world_mut(), "Door", &["Closed", "Open"]);
+    open_machine(&mut app, machine);
+    app.update();
+    app.world_mut().entity_mut(machine).insert(Active);
+    app.world_mut().entity_mut(children[1]).insert(Active);
+
+    let scene = ReflectableStateMachinePersistentData::create_state_machine_scene(app.world_mut(), machine)
+        .expect("scene builds");
+
+    assert!(scene.entities.iter().any(|e| e.entity == children[1]));
+    for entity in &scene.entities {
+        assert!(
+            !entity.components.iter().any(|component| component
+                .get_represented_type_info()
+                .is_some_and(|info| info.type_id() == TypeId::of::<Active>())),
+            "{:?} was saved with Active",
+            entity.entity
+        );
+    }
+
+    // Building the scene leaves the live machine running as it was
+    assert!(app.world().entity(machine).contains::<Active>());
+    assert!(app.world().entity(children[1]).contains::<Active>());
+}
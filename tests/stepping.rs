@@ -0,0 +1,68 @@
+//! Integration tests for pausing a machine's feedback and stepping through buffered transitions
+
+use bevy::prelude::*;
+use bevy_gearbox_editor::test_support::{headless_app, open_machine, spawn_machine, transient_data};
+use bevy_gearbox_editor::{EditorPaused, StateMachineTransientData, StepMachine, TogglePauseMachine, TransitionRecord};
+
+/// Pause `machine` and buffer `count` transitions as if they fired while paused
+fn pause_with_buffered(app: &mut App, machine: Entity, states: &[Entity], count: usize) {
+    app.world_mut().trigger(TogglePauseMachine { entity: machine });
+    app.update();
+    let mut transient = app.world_mut().get_mut::<StateMachineTransientData>(machine).unwrap();
+    for index in 0..count {
+        transient.paused_transitions.push_back(TransitionRecord {
+            time: index as f32,
+            source: states[0],
+            target: states[1],
+            edge: Entity::PLACEHOLDER,
+            event_type: format!("Event{index}"),
+        });
+    }
+}
+
+#[test]
+fn step_releases_exactly_one_transition_in_firing_order() {
+    let mut app = headless_app();
+    let (machine, states) = spawn_machine(app.world_mut(), "Door", &["Closed", "Open"]);
+    open_machine(&mut app, machine);
+    pause_with_buffered(&mut app, machine, &states, 3);
+    let history_before = transient_data(&app, machine).unwrap().transition_history.len();
+
+    app.world_mut().trigger(StepMachine { entity: machine });
+    app.update();
+
+    let transient = transient_data(&app, machine).unwrap();
+    assert_eq!(transient.transition_history.len(), history_before + 1);
+    assert_eq!(transient.transition_history.back().unwrap().event_type, "Event0");
+    assert_eq!(transient.paused_transitions.len(), 2);
+    assert!(app.world().get::<EditorPaused>(machine).is_some());
+}
+
+#[test]
+fn resume_releases_the_rest_and_unpauses() {
+    let mut app = headless_app();
+    let (machine, states) = spawn_machine(app.world_mut(), "Door", &["Closed", "Open"]);
+    open_machine(&mut app, machine);
+    pause_with_buffered(&mut app, machine, &states, 2);
+
+    app.world_mut().trigger(TogglePauseMachine { entity: machine });
+    app.update();
+
+    let transient = transient_data(&app, machine).unwrap();
+    assert!(transient.paused_transitions.is_empty());
+    assert_eq!(transient.transition_history.back().unwrap().event_type, "Event1");
+    assert!(app.world().get::<EditorPaused>(machine).is_none());
+}
+
+#[test]
+fn step_does_nothing_while_running() {
+    let mut app = headless_app();
+    let (machine, _) = spawn_machine(app.world_mut(), "Door", &["Closed", "Open"]);
+    open_machine(&mut app, machine);
+    let history_before = transient_data(&app, machine).unwrap().transition_history.len();
+
+    app.world_mut().trigger(StepMachine { entity: machine });
+    app.update();
+
+    assert_eq!(transient_data(&app, machine).unwrap().transition_history.len(), history_before);
+}
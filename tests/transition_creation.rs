@@ -0,0 +1,58 @@
+//! Regression tests for abandoning an in-progress transition when its states go away
+
+use bevy::prelude::*;
+use bevy_gearbox_editor::test_support::{headless_app, open_machine, spawn_machine, transient_data};
+use bevy_gearbox_editor::{DeleteNode, StateMachineTransientData};
+
+/// Start creating a transition from `source`, as the "+" button does
+fn start_transition(app: &mut App, machine: Entity, source: Entity) {
+    app.world_mut()
+        .get_mut::<StateMachineTransientData>(machine)
+        .unwrap()
+        .transition_creation
+        .start_transition(source);
+}
+
+#[test]
+fn deleting_the_source_cancels_creation() {
+    let mut app = headless_app();
+    let (machine, children) = spawn_machine(app.world_mut(), "Door", &["Closed", "Open"]);
+    open_machine(&mut app, machine);
+    start_transition(&mut app, machine, children[1]);
+
+    app.world_mut().trigger(DeleteNode { entity: children[1] });
+    app.update();
+
+    let creation = &transient_data(&app, machine).unwrap().transition_creation;
+    assert!(!creation.is_active());
+    assert_eq!(creation.source_entity, None);
+    assert!(app.world().get_entity(children[1]).is_err());
+}
+
+#[test]
+fn deleting_an_unrelated_state_keeps_creation() {
+    let mut app = headless_app();
+    let (machine, children) = spawn_machine(app.world_mut(), "Door", &["Closed", "Open", "Locked"]);
+    open_machine(&mut app, machine);
+    start_transition(&mut app, machine, children[1]);
+
+    app.world_mut().trigger(DeleteNode { entity: children[2] });
+    app.update();
+
+    let creation = &transient_data(&app, machine).unwrap().transition_creation;
+    assert_eq!(creation.source_entity, Some(children[1]));
+}
+
+#[test]
+fn despawned_source_is_cancelled_next_frame() {
+    let mut app = headless_app();
+    let (machine, children) = spawn_machine(app.world_mut(), "Door", &["Closed", "Open"]);
+    open_machine(&mut app, machine);
+    start_transition(&mut app, machine, children[1]);
+
+    // Removed behind the editor's back, without `DeleteNode`
+    app.world_mut().despawn(children[1]);
+    app.update();
+
+    assert!(!transient_data(&app, machine).unwrap().transition_creation.is_active());
+}